@@ -0,0 +1,1012 @@
+//! 信号解析モジュール
+//!
+//! このモジュールは、生成した信号を解析するための機能を提供します。
+//! 窓関数の計算や短時間フーリエ変換（STFT）によるスペクトログラムの計算、
+//! Welch法による平均化したパワースペクトル密度（PSD）の計算、
+//! RMSレベルやPAPR（ピーク対平均電力比）などの統計量の計算、
+//! 占有帯域幅や隣接チャネル漏洩電力比（ACPR）などのスペクトラム測定が可能です。
+
+use num_complex::Complex;
+use rustfft::FftPlanner;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// FFT前に適用する窓関数の種類
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Copy)]
+pub enum WindowFunction {
+    /// 矩形窓（窓なし）
+    Rectangular,
+    /// 三角窓 - 両端の係数が0にならない形（マルチトーンの振幅の重み付けにも使用）
+    Triangular,
+    /// ハン窓
+    Hann,
+    /// ハミング窓
+    Hamming,
+    /// ブラックマン窓
+    Blackman,
+    /// ブラックマン・ハリス窓（4項）
+    BlackmanHarris,
+    /// フラットトップ窓 - 振幅測定の精度が高い
+    FlatTop,
+    /// テューキー窓（テーパー付き余弦窓）
+    ///
+    /// 両端のテーパー部分の比率 (0.0〜1.0) を持ちます。
+    /// 0.0で矩形窓、1.0でハン窓と同じになります。
+    Tukey(f64),
+}
+
+/// テューキー窓のテーパー比の既定値
+pub const DEFAULT_TUKEY_RATIO: f64 = 0.5;
+
+impl WindowFunction {
+    /// 選択肢として表示するすべての窓関数（テューキー窓は既定のテーパー比）
+    pub const ALL: [WindowFunction; 8] = [
+        WindowFunction::Rectangular,
+        WindowFunction::Triangular,
+        WindowFunction::Hann,
+        WindowFunction::Hamming,
+        WindowFunction::Blackman,
+        WindowFunction::BlackmanHarris,
+        WindowFunction::FlatTop,
+        WindowFunction::Tukey(DEFAULT_TUKEY_RATIO),
+    ];
+}
+
+/// 窓関数の係数を計算
+///
+/// 対称型（両端が対称）の窓係数を生成します。
+/// 矩形窓・三角窓・テューキー窓以外はすべて余弦和窓 w[k] = Σ (-1)^m·a_m·cos(2πmk/(N-1)) として計算します。
+///
+/// # 引数
+/// * `kind` - 窓関数の種類
+/// * `n` - 窓の長さ（サンプル数）
+///
+/// # 戻り値
+/// 長さnの窓係数
+pub fn window(kind: WindowFunction, n: usize) -> Vec<f64> {
+    (0..n).map(|k| window_coefficient(kind, n, k)).collect()
+}
+
+/// 長さnの窓関数のk番目の係数を計算
+///
+/// `window(kind, n)[k]`と同じ値を、窓全体を生成せずに計算します。
+///
+/// # 引数
+/// * `kind` - 窓関数の種類
+/// * `n` - 窓の長さ（サンプル数）
+/// * `k` - 係数の位置 (0..n)
+///
+/// # 戻り値
+/// k番目の窓係数
+pub fn window_coefficient(kind: WindowFunction, n: usize, k: usize) -> f64 {
+    match kind {
+        WindowFunction::Triangular => triangular_coefficient(n, k),
+        // 長さ1の窓は係数1とする（N-1で割れないため）
+        _ if n <= 1 => 1.0,
+        _ => window_at(kind, k as f64 / (n - 1) as f64),
+    }
+}
+
+/// N個のトーンの振幅の重み付けに使う、k番目のトーンの窓係数を計算
+///
+/// 窓を0〜1の区間をN等分した各区間の中央 (k + 0.5) / N で評価します。
+/// 左右対称のまま両端のトーンも0にならないため、ハン窓やブラックマン窓でも
+/// トーン数が少ないときに全トーンが消えることはありません。
+///
+/// # 引数
+/// * `kind` - 窓関数の種類
+/// * `n` - トーン数
+/// * `k` - トーンの位置 (0..n)
+///
+/// # 戻り値
+/// k番目のトーンの振幅の重み
+pub fn tone_window_coefficient(kind: WindowFunction, n: usize, k: usize) -> f64 {
+    if n == 0 {
+        return 1.0;
+    }
+    window_at(kind, (k as f64 + 0.5) / n as f64)
+}
+
+/// 窓の区間を0〜1に正規化した位置tで窓関数を評価
+fn window_at(kind: WindowFunction, t: f64) -> f64 {
+    let coeffs: &[f64] = match kind {
+        WindowFunction::Rectangular => &[1.0],
+        WindowFunction::Hann => &[0.5, 0.5],
+        WindowFunction::Hamming => &[0.54, 0.46],
+        WindowFunction::Blackman => &[0.42, 0.5, 0.08],
+        WindowFunction::BlackmanHarris => &[0.35875, 0.48829, 0.14128, 0.01168],
+        WindowFunction::FlatTop => &[
+            0.21557895,
+            0.41663158,
+            0.277263158,
+            0.083578947,
+            0.006947368,
+        ],
+        WindowFunction::Triangular => return 1.0 - (2.0 * t - 1.0).abs(),
+        WindowFunction::Tukey(ratio) => return tukey_coefficient(ratio, t),
+    };
+
+    let x = 2.0 * PI * t;
+    coeffs
+        .iter()
+        .enumerate()
+        .map(|(m, a)| {
+            let sign = if m % 2 == 0 { 1.0 } else { -1.0 };
+            sign * a * (m as f64 * x).cos()
+        })
+        .sum()
+}
+
+/// 三角窓のk番目の係数を計算
+///
+/// w[k] = 1 - |2k - (N-1)| / L（Nが奇数ではL = N+1、偶数ではL = N）で、
+/// 中央が1、両端が0より大きい値になります（SciPyの`triang`と同じ）。
+fn triangular_coefficient(n: usize, k: usize) -> f64 {
+    let l = (n + n % 2) as f64;
+    1.0 - (2.0 * k as f64 - (n as f64 - 1.0)).abs() / l
+}
+
+/// 正規化した位置t (0.0〜1.0) でのテューキー窓の係数を計算
+///
+/// 窓の両端の`ratio / 2`ずつを余弦でテーパーし、中央部分は1とします。
+fn tukey_coefficient(ratio: f64, t: f64) -> f64 {
+    if ratio <= 0.0 {
+        return 1.0;
+    }
+    let ratio = ratio.min(1.0);
+    // 窓の中心に対して対称な位置 (0.0〜0.5)
+    let edge = t.min(1.0 - t);
+    if edge >= ratio / 2.0 {
+        1.0
+    } else {
+        0.5 * (1.0 - (2.0 * PI * edge / ratio).cos())
+    }
+}
+
+/// 窓関数の等価雑音帯域幅（ENBW）をビン数で計算
+///
+/// ENBW = N·Σw² / (Σw)² で、白色雑音に対して1ビンあたりに含まれる雑音電力が
+/// 矩形窓の何倍になるかを表します。
+///
+/// # 引数
+/// * `coeffs` - 窓関数の係数
+///
+/// # 戻り値
+/// ENBW（ビン数）。係数の総和が0の場合は無限大
+pub fn enbw(coeffs: &[f64]) -> f64 {
+    let sum: f64 = coeffs.iter().sum();
+    let sum_sq: f64 = coeffs.iter().map(|w| w * w).sum();
+    coeffs.len() as f64 * sum_sq / (sum * sum)
+}
+
+/// 窓関数のスカラッピング損失を計算
+///
+/// トーンがビンの中間（0.5ビンずれた位置）にある場合の振幅の低下量です。
+///
+/// # 引数
+/// * `coeffs` - 窓関数の係数
+///
+/// # 戻り値
+/// スカラッピング損失 (dB、正の値)
+pub fn scalloping_loss_db(coeffs: &[f64]) -> f64 {
+    let n = coeffs.len() as f64;
+    let sum: f64 = coeffs.iter().sum();
+    let half_bin: Complex<f64> = coeffs
+        .iter()
+        .enumerate()
+        .map(|(k, w)| Complex::from_polar(*w, -PI * k as f64 / n))
+        .sum();
+    -20.0 * (half_bin.norm() / sum).log10()
+}
+
+/// 短時間フーリエ変換（STFT）を計算
+///
+/// 信号を`size - overlap`サンプルずつずらしながら長さ`size`のフレームに分割し、
+/// 各フレームに窓関数を適用してFFTした振幅スペクトラムを返します。
+/// 振幅は窓関数のコヒーレントゲインで正規化され、各フレームの周波数ビンは
+/// 負の周波数から正の周波数の順（-fs/2 〜 +fs/2）に並べ替えられています。
+///
+/// # 引数
+/// * `samples` - 解析する複素数サンプル配列
+/// * `size` - 1フレームのFFTサイズ
+/// * `overlap` - 隣接フレーム間の重なりサンプル数（`size`未満）
+/// * `window` - 各フレームに適用する窓関数
+///
+/// # 戻り値
+/// フレームごとの線形振幅スペクトラム（フレーム数 × `size`）
+pub fn stft(
+    samples: &[Complex<f64>],
+    size: usize,
+    overlap: usize,
+    window: WindowFunction,
+) -> Vec<Vec<f64>> {
+    if size == 0 || overlap >= size || samples.len() < size {
+        return Vec::new();
+    }
+
+    let hop = size - overlap;
+    let coeffs = self::window(window, size);
+    let window_sum: f64 = coeffs.iter().sum();
+    let fft = FftPlanner::new().plan_fft_forward(size);
+
+    samples
+        .windows(size)
+        .step_by(hop)
+        .map(|frame| {
+            let mut buffer: Vec<Complex<f64>> =
+                frame.iter().zip(&coeffs).map(|(s, w)| s * w).collect();
+            fft.process(&mut buffer);
+            // FFT結果をシフトして負の周波数から並べる
+            (0..size)
+                .map(|i| buffer[(i + size / 2) % size].norm() / window_sum)
+                .collect()
+        })
+        .collect()
+}
+
+/// Welch法によるパワースペクトル密度（PSD）を計算
+///
+/// 信号を`seg_len - overlap`サンプルずつずらしながら長さ`seg_len`のセグメントに分割し、
+/// 各セグメントに窓関数を適用したピリオドグラムを平均します。
+/// 窓関数の電力（係数の2乗和）で正規化するため、白色雑音のPSDは周波数によらず
+/// 雑音電力（分散）に等しくなります。サンプリングレート`fs`で割ると単位周波数 (Hz) あたりの
+/// PSDになります。各ビンは負の周波数から正の周波数の順（-fs/2 〜 +fs/2）に並べ替えられています。
+///
+/// # 引数
+/// * `samples` - 解析する複素数サンプル配列
+/// * `seg_len` - 1セグメントのFFTサイズ
+/// * `overlap` - 隣接セグメント間の重なりサンプル数（`seg_len`未満）
+/// * `window` - 各セグメントに適用する窓関数
+///
+/// # 戻り値
+/// 正規化周波数あたりのPSD（長さ`seg_len`）。セグメントが1つも取れない場合は空
+pub fn welch_psd(
+    samples: &[Complex<f64>],
+    seg_len: usize,
+    overlap: usize,
+    window: WindowFunction,
+) -> Vec<f64> {
+    if seg_len == 0 || overlap >= seg_len || samples.len() < seg_len {
+        return Vec::new();
+    }
+
+    let hop = seg_len - overlap;
+    let coeffs = self::window(window, seg_len);
+    let window_power: f64 = coeffs.iter().map(|w| w * w).sum();
+    let fft = FftPlanner::new().plan_fft_forward(seg_len);
+
+    let mut psd = vec![0.0; seg_len];
+    let mut segments = 0;
+    for segment in samples.windows(seg_len).step_by(hop) {
+        let mut buffer: Vec<Complex<f64>> =
+            segment.iter().zip(&coeffs).map(|(s, w)| s * w).collect();
+        fft.process(&mut buffer);
+        // FFT結果をシフトして負の周波数から並べる
+        for (i, p) in psd.iter_mut().enumerate() {
+            *p += buffer[(i + seg_len / 2) % seg_len].norm_sqr();
+        }
+        segments += 1;
+    }
+    let scale = 1.0 / (segments as f64 * window_power);
+    psd.iter_mut().for_each(|p| *p *= scale);
+    psd
+}
+
+/// RMS（実効値）レベルを計算
+///
+/// 複素サンプルの振幅の2乗平均の平方根を返します。
+///
+/// # 引数
+/// * `samples` - 複素数サンプル配列
+///
+/// # 戻り値
+/// RMSレベル（空の配列の場合は0）
+pub fn rms(samples: &[Complex<f64>]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s.norm_sqr()).sum::<f64>() / samples.len() as f64).sqrt()
+}
+
+/// PAPR（ピーク対平均電力比）をdBで計算
+///
+/// # 引数
+/// * `samples` - 複素数サンプル配列
+///
+/// # 戻り値
+/// ピーク電力と平均電力の比 (dB)。信号がない場合は0
+pub fn papr_db(samples: &[Complex<f64>]) -> f64 {
+    let mean_power = rms(samples).powi(2);
+    if mean_power == 0.0 {
+        return 0.0;
+    }
+    let peak_power = samples.iter().map(|s| s.norm_sqr()).fold(0.0, f64::max);
+    10.0 * (peak_power / mean_power).log10()
+}
+
+/// 周波数順に並べた（fftshift済みの）スペクトラムのビンkの周波数を計算
+///
+/// ビン n/2 が0Hzに対応します。
+fn bin_frequency(k: usize, n: usize, sample_rate: f64) -> f64 {
+    (k as f64 - n as f64 / 2.0) * sample_rate / n as f64
+}
+
+/// 指定した周波数範囲に含まれるビンの電力の合計を計算
+fn band_power(spectrum: &[f64], sample_rate: f64, low: f64, high: f64) -> f64 {
+    spectrum
+        .iter()
+        .enumerate()
+        .filter(|(k, _)| {
+            let freq = bin_frequency(*k, spectrum.len(), sample_rate);
+            freq >= low && freq <= high
+        })
+        .map(|(_, p)| p)
+        .sum()
+}
+
+/// 占有帯域幅を計算
+///
+/// 全電力のうち`fraction`（例: 0.99）を含む帯域幅を求めます。
+/// 帯域の上下にはそれぞれ (1 - fraction)/2 の電力が残ります。
+/// 帯域の端は、端のビンの中心から周波数分解能の半分だけ外側の周波数です。
+///
+/// # 引数
+/// * `spectrum` - 周波数順に並べた（fftshift済みの）各ビンの電力
+/// * `sample_rate` - サンプリングレート (Hz)
+/// * `fraction` - 帯域内に含める電力の割合 (0-1)
+///
+/// # 戻り値
+/// (下端の周波数 (Hz), 上端の周波数 (Hz), 占有帯域幅 (Hz))。電力がない場合はすべて0
+pub fn occupied_bandwidth(spectrum: &[f64], sample_rate: f64, fraction: f64) -> (f64, f64, f64) {
+    let total: f64 = spectrum.iter().sum();
+    if spectrum.is_empty() || total <= 0.0 {
+        return (0.0, 0.0, 0.0);
+    }
+    let tail = total * (1.0 - fraction) / 2.0;
+
+    // 累積電力が下側の余り分を超える最初のビンと、上側の余り分を超える最後のビン
+    let mut cumulative = 0.0;
+    let mut lower = 0;
+    for (k, p) in spectrum.iter().enumerate() {
+        cumulative += p;
+        if cumulative > tail {
+            lower = k;
+            break;
+        }
+    }
+    let mut cumulative = 0.0;
+    let mut upper = spectrum.len() - 1;
+    for (k, p) in spectrum.iter().enumerate().rev() {
+        cumulative += p;
+        if cumulative > tail {
+            upper = k;
+            break;
+        }
+    }
+
+    // fftshift済みのビンkの中心周波数は (k - N/2)·fs/N
+    let resolution = sample_rate / spectrum.len() as f64;
+    let center = spectrum.len() as f64 / 2.0;
+    let low = (lower as f64 - center - 0.5) * resolution;
+    let high = (upper.max(lower) as f64 - center + 0.5) * resolution;
+    (low, high, high - low)
+}
+
+/// 隣接チャネル漏洩電力比（ACPR）を計算
+///
+/// 中心周波数を中心とする幅`channel_bw`の主チャネルの電力に対する、
+/// ±`offset`だけ離れた同じ幅の隣接チャネルの電力の比を求めます。
+///
+/// # 引数
+/// * `spectrum` - 周波数順に並べた（fftshift済みの）各ビンの電力
+/// * `sample_rate` - サンプリングレート (Hz)
+/// * `center` - 主チャネルの中心周波数 (Hz)
+/// * `channel_bw` - チャネル帯域幅 (Hz)
+/// * `offset` - 主チャネルと隣接チャネルの中心周波数の差 (Hz)
+///
+/// # 戻り値
+/// (下側隣接チャネル, 上側隣接チャネル) の主チャネルに対する電力比 (dBc)。
+/// 主チャネルに電力がない場合はNone
+pub fn acpr(
+    spectrum: &[f64],
+    sample_rate: f64,
+    center: f64,
+    channel_bw: f64,
+    offset: f64,
+) -> Option<(f64, f64)> {
+    let channel = |c: f64| {
+        band_power(
+            spectrum,
+            sample_rate,
+            c - channel_bw / 2.0,
+            c + channel_bw / 2.0,
+        )
+    };
+    let main = channel(center);
+    if main <= 0.0 {
+        return None;
+    }
+    let ratio_db = |p: f64| 10.0 * (p / main).log10();
+    Some((
+        ratio_db(channel(center - offset)),
+        ratio_db(channel(center + offset)),
+    ))
+}
+
+/// 雑音電力比（NPR）を計算
+///
+/// ノッチで取り除いたトーンの位置に漏れ込んだ電力の平均に対する、
+/// 残したトーンの電力の平均の比を求めます。各トーンの電力は、最寄りのビンの前後
+/// `THD_SPAN_BINS`ビン（窓関数のメインローブ分）の合計とします。
+/// ノッチ内の電力は`floor_db`を下限とするため、ノッチに電力がなくてもNPRは有限の値
+/// （最大で`-floor_db`）になります。
+///
+/// # 引数
+/// * `spectrum` - 周波数順に並べた（fftshift済みの）各ビンの電力
+/// * `sample_rate` - サンプリングレート (Hz)
+/// * `tone_freqs` - 残したトーンの周波数 (Hz)
+/// * `notch_freqs` - ノッチで取り除いたトーンの周波数 (Hz)
+/// * `floor_db` - 残したトーンの電力に対するノッチ内の電力の下限 (dB)
+///
+/// # 戻り値
+/// NPR (dB)。トーンまたはノッチが空の場合、または残したトーンに電力がない場合はNone
+pub fn npr(
+    spectrum: &[f64],
+    sample_rate: f64,
+    tone_freqs: &[f64],
+    notch_freqs: &[f64],
+    floor_db: f64,
+) -> Option<f64> {
+    if spectrum.is_empty() || tone_freqs.is_empty() || notch_freqs.is_empty() {
+        return None;
+    }
+    let n = spectrum.len() as f64;
+    let mean_power = |freqs: &[f64]| {
+        freqs
+            .iter()
+            .map(|f| {
+                // 周波数に最も近いビン（ナイキスト周波数を超える場合は折り返す）
+                let k = (f / sample_rate * n + n / 2.0).round().rem_euclid(n);
+                power_around(spectrum, k as isize)
+            })
+            .sum::<f64>()
+            / freqs.len() as f64
+    };
+    let tone_power = mean_power(tone_freqs);
+    if tone_power <= 0.0 {
+        return None;
+    }
+    let notch_power = mean_power(notch_freqs).max(tone_power * 10f64.powf(floor_db / 10.0));
+    Some(10.0 * (tone_power / notch_power).log10())
+}
+
+/// THD計算で高調波として扱う最大の次数の標準値
+pub const DEFAULT_THD_HARMONICS: usize = 10;
+
+/// THD/SINAD計算で各成分の電力を合計する、ピークの前後のビン数（窓関数のメインローブ分）
+const THD_SPAN_BINS: usize = 4;
+
+/// 指定したビンの前後`THD_SPAN_BINS`ビンを含めた電力の合計
+fn power_around(spectrum: &[f64], k: isize) -> f64 {
+    let low = (k - THD_SPAN_BINS as isize).max(0) as usize;
+    let high = ((k + THD_SPAN_BINS as isize) as usize).min(spectrum.len() - 1);
+    spectrum[low..=high].iter().sum()
+}
+
+/// 単一トーンの基本波のビンを探す
+///
+/// 0Hz（DCオフセット）の前後`THD_SPAN_BINS`ビンを除いた最大のピークを基本波とします。
+///
+/// # 引数
+/// * `spectrum` - 周波数順に並べた（fftshift済みの）各ビンの電力
+///
+/// # 戻り値
+/// 基本波のビン番号。電力がない場合はNone
+pub fn fundamental_bin(spectrum: &[f64]) -> Option<usize> {
+    let center = spectrum.len() / 2;
+    spectrum
+        .iter()
+        .enumerate()
+        .filter(|(k, _)| k.abs_diff(center) > THD_SPAN_BINS)
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .filter(|(_, &p)| p > 0.0)
+        .map(|(k, _)| k)
+}
+
+/// 全高調波歪み（THD）を計算
+///
+/// 基本波のビンを中心に、0Hzからの周波数が2倍〜`num_harmonics`倍となる高調波の電力を
+/// 合計し、基本波の電力との比を振幅比で求めます。ナイキスト周波数を超える高調波は無視します。
+///
+/// # 引数
+/// * `spectrum` - 周波数順に並べた（fftshift済みの）各ビンの電力
+/// * `fundamental` - 基本波のビン番号
+/// * `num_harmonics` - 高調波として扱う最大の次数
+///
+/// # 戻り値
+/// THD（振幅比、1.0 = 100%）。基本波が0Hzにある場合や電力がない場合は0
+pub fn thd(spectrum: &[f64], fundamental: usize, num_harmonics: usize) -> f64 {
+    let center = spectrum.len() / 2;
+    let offset = fundamental as isize - center as isize;
+    if offset == 0 || fundamental >= spectrum.len() {
+        return 0.0;
+    }
+
+    let fundamental_power = power_around(spectrum, fundamental as isize);
+    if fundamental_power <= 0.0 {
+        return 0.0;
+    }
+    let harmonic_power: f64 = (2..=num_harmonics as isize)
+        .map(|h| center as isize + h * offset)
+        .take_while(|&k| k >= 0 && k < spectrum.len() as isize)
+        .map(|k| power_around(spectrum, k))
+        .sum();
+    (harmonic_power / fundamental_power).sqrt()
+}
+
+/// SINAD（信号対雑音・歪み比）を計算
+///
+/// 基本波の前後`THD_SPAN_BINS`ビンの電力を信号とし、0Hz付近（DCオフセット）を除いた
+/// 残りすべてのビンの電力を雑音と歪みとして、両者の電力比を求めます。
+///
+/// # 引数
+/// * `spectrum` - 周波数順に並べた（fftshift済みの）各ビンの電力
+/// * `fundamental` - 基本波のビン番号
+///
+/// # 戻り値
+/// SINAD (dB)。雑音と歪みがない場合は無限大、信号がない場合は負の無限大
+pub fn sinad(spectrum: &[f64], fundamental: usize) -> f64 {
+    if fundamental >= spectrum.len() {
+        return f64::NEG_INFINITY;
+    }
+    let center = spectrum.len() / 2;
+    let signal = power_around(spectrum, fundamental as isize);
+    let total: f64 = spectrum
+        .iter()
+        .enumerate()
+        .filter(|(k, _)| k.abs_diff(center) > THD_SPAN_BINS)
+        .map(|(_, p)| p)
+        .sum();
+    // 基本波の範囲がDC付近と重なる部分は両方から除かれるため、差は負にならないよう制限
+    let noise_distortion = (total - signal).max(0.0);
+    10.0 * (signal / noise_distortion).log10()
+}
+
+/// SFDR計算で信号ピークの一部とみなす、ピークの前後のビン数
+const SFDR_GUARD_BINS: usize = 4;
+
+/// スプリアスフリーダイナミックレンジ（SFDR）を計算
+///
+/// 最大のピークを信号とし、その前後`SFDR_GUARD_BINS`ビンより外側にある
+/// 局所最大のうち最大のものを最悪スプリアスとして、両者の電力比を求めます。
+///
+/// # 引数
+/// * `spectrum` - 各ビンの電力
+///
+/// # 戻り値
+/// (SFDR (dB), 最悪スプリアスのビン番号)。スプリアスがない場合のSFDRは無限大
+pub fn sfdr(spectrum: &[f64]) -> (f64, usize) {
+    let Some((peak, &peak_power)) = spectrum
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+    else {
+        return (0.0, 0);
+    };
+
+    let is_local_max = |k: usize| {
+        let left = k.checked_sub(1).map_or(0.0, |i| spectrum[i]);
+        let right = spectrum.get(k + 1).copied().unwrap_or(0.0);
+        spectrum[k] >= left && spectrum[k] >= right
+    };
+    let spur = (0..spectrum.len())
+        .filter(|&k| k.abs_diff(peak) > SFDR_GUARD_BINS && is_local_max(k))
+        .max_by(|&a, &b| spectrum[a].total_cmp(&spectrum[b]));
+
+    match spur {
+        Some(k) if spectrum[k] > 0.0 => (10.0 * (peak_power / spectrum[k]).log10(), k),
+        _ => (f64::INFINITY, peak),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 窓関数の係数が既知の値と一致することをテスト
+    #[test]
+    fn test_window_known_values() {
+        let epsilon = 1e-9;
+        let check = |kind: WindowFunction, expected: &[f64]| {
+            let w = window(kind, expected.len());
+            for (a, b) in w.iter().zip(expected) {
+                assert!(
+                    (a - b).abs() < epsilon,
+                    "{:?}: {:?} != {:?}",
+                    kind,
+                    w,
+                    expected
+                );
+            }
+        };
+
+        check(WindowFunction::Rectangular, &[1.0, 1.0, 1.0, 1.0, 1.0]);
+        check(
+            WindowFunction::Triangular,
+            &[1.0 / 3.0, 2.0 / 3.0, 1.0, 2.0 / 3.0, 1.0 / 3.0],
+        );
+        check(WindowFunction::Triangular, &[0.25, 0.75, 0.75, 0.25]);
+        check(WindowFunction::Hann, &[0.0, 0.5, 1.0, 0.5, 0.0]);
+        check(WindowFunction::Hamming, &[0.08, 0.54, 1.0, 0.54, 0.08]);
+        check(WindowFunction::Blackman, &[0.0, 0.34, 1.0, 0.34, 0.0]);
+        check(
+            WindowFunction::BlackmanHarris,
+            &[6e-5, 0.21747, 1.0, 0.21747, 6e-5],
+        );
+        // フラットトップ窓の中央は係数の総和（≈1.0）
+        assert!((window(WindowFunction::FlatTop, 5)[2] - 1.000000003).abs() < epsilon);
+        check(
+            WindowFunction::Tukey(0.5),
+            &[0.0, 0.75, 1.0, 1.0, 1.0, 0.75, 0.0],
+        );
+        // テーパー比0は矩形窓、1はハン窓と一致する
+        check(WindowFunction::Tukey(0.0), &[1.0, 1.0, 1.0, 1.0, 1.0]);
+        check(WindowFunction::Tukey(1.0), &[0.0, 0.5, 1.0, 0.5, 0.0]);
+    }
+
+    /// 窓関数のENBWとスカラッピング損失が既知の値と一致することをテスト
+    #[test]
+    fn test_enbw_and_scalloping_loss() {
+        let n = 4096;
+        let enbw_of = |kind| enbw(&window(kind, n));
+        assert!((enbw_of(WindowFunction::Rectangular) - 1.0).abs() < 1e-12);
+        assert!((enbw_of(WindowFunction::Hann) - 1.5).abs() < 1e-3);
+        assert!((enbw_of(WindowFunction::BlackmanHarris) - 2.004).abs() < 1e-2);
+        // テューキー窓はテーパー比に応じて矩形窓とハン窓の間になる
+        let tukey = enbw_of(WindowFunction::Tukey(0.5));
+        assert!(tukey > 1.0 && tukey < 1.5, "Tukey ENBW {}", tukey);
+
+        let loss_of = |kind| scalloping_loss_db(&window(kind, n));
+        assert!((loss_of(WindowFunction::Rectangular) - 3.92).abs() < 0.01);
+        assert!((loss_of(WindowFunction::Hann) - 1.42).abs() < 0.01);
+        assert!(loss_of(WindowFunction::FlatTop) < 0.01);
+    }
+
+    /// STFTのフレーム数と、トーンのピーク位置をテスト
+    #[test]
+    fn test_stft_tone_peak() {
+        // fs=1000Hzで125Hzのトーン（FFTサイズ64でちょうどビン8）
+        let fs = 1000.0;
+        let samples: Vec<Complex<f64>> = (0..1024)
+            .map(|n| Complex::from_polar(1.0, 2.0 * PI * 125.0 * n as f64 / fs))
+            .collect();
+
+        let frames = stft(&samples, 64, 32, WindowFunction::Hann);
+        // (1024 - 64) / 32 + 1 = 31フレーム
+        assert_eq!(frames.len(), 31);
+
+        for frame in &frames {
+            assert_eq!(frame.len(), 64);
+            let peak = frame
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .unwrap()
+                .0;
+            // シフト後はインデックス32が0Hz
+            assert_eq!(peak, 32 + 8);
+            assert!((frame[peak] - 1.0).abs() < 1e-9);
+        }
+    }
+
+    /// 単一トーンのメインローブ外への漏れがHann窓で矩形窓より小さくなることをテスト
+    #[test]
+    fn test_hann_leakage_lower_than_rectangular() {
+        // ビンの中間（ビン10.5）に位置する、漏れが最も大きくなるトーン
+        let n = 256;
+        let samples: Vec<Complex<f64>> = (0..n)
+            .map(|k| Complex::from_polar(1.0, 2.0 * PI * 10.5 * k as f64 / n as f64))
+            .collect();
+
+        // メインローブから10ビン以上離れた位置の最大振幅 (dB)
+        let leakage_db = |kind: WindowFunction| {
+            let frame = &stft(&samples, n, 0, kind)[0];
+            let tone = n / 2 + 10;
+            frame
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| i.abs_diff(tone) >= 10)
+                .map(|(_, m)| 20.0 * m.log10())
+                .fold(f64::NEG_INFINITY, f64::max)
+        };
+
+        let rect = leakage_db(WindowFunction::Rectangular);
+        let hann = leakage_db(WindowFunction::Hann);
+        assert!(
+            hann < rect - 20.0,
+            "Hann {} dB vs Rectangular {} dB",
+            hann,
+            rect
+        );
+    }
+
+    /// RMSとPAPRの計算をテスト
+    ///
+    /// CW信号はPAPRが0dBになり、マルチトーンではSchroeder位相が
+    /// ゼロ位相よりもPAPRを低くすることを確認
+    #[test]
+    fn test_rms_and_papr() {
+        use crate::signal::{ModulationType, MultitonePhase, SignalGenerator, SignalParams};
+
+        let cw = SignalGenerator::new().generate_block(&SignalParams::default(), 1000);
+        assert!((rms(&cw) - 1.0).abs() < 1e-12);
+        assert!(papr_db(&cw).abs() < 1e-9);
+
+        let zero = SignalParams {
+            mod_type: ModulationType::Multitone,
+            multitone_count: 16,
+            multitone_spacing: 1000.0,
+            multitone_phase: MultitonePhase::Zero,
+            ..Default::default()
+        };
+        let schroeder = SignalParams {
+            multitone_phase: MultitonePhase::Schroeder,
+            ..zero.clone()
+        };
+        let zero_papr = papr_db(&SignalGenerator::new().generate_block(&zero, 1000));
+        let schroeder_papr = papr_db(&SignalGenerator::new().generate_block(&schroeder, 1000));
+        // ゼロ位相ではPAPRは10·log10(N) ≈ 12dB
+        assert!((zero_papr - 10.0 * 16.0_f64.log10()).abs() < 0.1);
+        assert!(schroeder_papr < zero_papr - 6.0);
+    }
+
+    /// 帯域が既知のスペクトラムで占有帯域幅を計算できることをテスト
+    #[test]
+    fn test_occupied_bandwidth() {
+        // 1000ビン (fs=1000Hz、1Hz/ビン) のうち中央の200Hzに一様な電力
+        let n = 1000;
+        let spectrum: Vec<f64> = (0..n)
+            .map(|k| if (400..600).contains(&k) { 1.0 } else { 0.0 })
+            .collect();
+        let (low, high, obw) = occupied_bandwidth(&spectrum, 1000.0, 0.99);
+        assert!((obw - 198.0).abs() <= 1.0, "OBW = {}", obw);
+        assert!((low + 100.0).abs() <= 1.0 && (high - 100.0).abs() <= 3.0);
+        // 全電力ではビン400〜599（-100〜99Hz）の外側の端まで
+        assert_eq!(
+            occupied_bandwidth(&spectrum, 1000.0, 1.0),
+            (-100.5, 99.5, 200.0)
+        );
+        // 割合を下げると帯域は狭くなる
+        let (_, _, half) = occupied_bandwidth(&spectrum, 1000.0, 0.5);
+        assert!((half - 100.0).abs() <= 1.0, "OBW = {}", half);
+        assert_eq!(
+            occupied_bandwidth(&vec![0.0; n], 1000.0, 0.99),
+            (0.0, 0.0, 0.0)
+        );
+    }
+
+    /// 主チャネルと隣接チャネルの電力比をテスト
+    #[test]
+    fn test_acpr() {
+        // 0Hzを中心とする100Hz幅の主チャネルに1.0、上側隣接チャネル(200Hz)に0.01、
+        // 下側隣接チャネル(-200Hz)に0.001の電力
+        let n = 1000;
+        let spectrum: Vec<f64> = (0..n)
+            .map(|k| match k as i64 - 500 {
+                -50..=49 => 1.0,
+                150..=249 => 0.01,
+                -250..=-151 => 0.001,
+                _ => 0.0,
+            })
+            .collect();
+        let (lower, upper) = acpr(&spectrum, 1000.0, 0.0, 100.0, 200.0).unwrap();
+        assert!((upper + 20.0).abs() < 0.5, "upper = {}", upper);
+        assert!((lower + 30.0).abs() < 0.5, "lower = {}", lower);
+
+        // 主チャネルに電力がなければ比を計算しない
+        assert_eq!(acpr(&vec![0.0; n], 1000.0, 0.0, 100.0, 200.0), None);
+        assert_eq!(acpr(&spectrum, 1000.0, 300.0, 100.0, 200.0), None);
+    }
+
+    /// ノッチ内に漏れ込む電力がなければNPRは非常に高く、クリップによる相互変調で低下することをテスト
+    #[test]
+    fn test_npr() {
+        use crate::signal::{clip_magnitude, ModulationType, SignalGenerator, SignalParams};
+
+        let n = 1000;
+        let fs = 100000.0;
+        let params = SignalParams {
+            frequency: 10000.0,
+            sample_rate: fs,
+            mod_type: ModulationType::Multitone,
+            multitone_count: 16,
+            multitone_spacing: 1000.0,
+            notch_start_tone: 6,
+            notch_width_tones: 4,
+            ..Default::default()
+        };
+        let tones: Vec<f64> = params.multitone_tones().iter().map(|t| t.freq).collect();
+        let notch = tones[6..10].to_vec();
+        let kept: Vec<f64> = tones[..6].iter().chain(&tones[10..]).copied().collect();
+        let measure = |params: &SignalParams, clip: Option<f64>| {
+            let mut spectrum = SignalGenerator::new().generate_block(params, n);
+            if let Some(level) = clip {
+                clip_magnitude(&mut spectrum, level);
+            }
+            FftPlanner::new().plan_fft_forward(n).process(&mut spectrum);
+            let mut power: Vec<f64> = spectrum.iter().map(|c| c.norm_sqr()).collect();
+            power.rotate_left(n / 2);
+            npr(&power, fs, &kept, &notch, -200.0).unwrap()
+        };
+
+        // ビンに一致するトーンでは、ノッチ内にはほとんど電力が残らない
+        let clean = measure(&params, None);
+        assert!(
+            clean.is_finite() && clean > 100.0 && clean <= 200.0,
+            "clean NPR = {}",
+            clean
+        );
+
+        // クリップすると相互変調がノッチを埋める
+        let clipped = measure(&params, Some(0.3));
+        assert!(
+            clipped.is_finite() && clipped < 40.0,
+            "clipped NPR = {}",
+            clipped
+        );
+
+        // ノッチ内の電力が0でも、下限で打ち切った有限の値になる
+        let mut power = vec![0.0; n];
+        power[n / 2] = 1.0;
+        assert_eq!(npr(&power, fs, &[0.0], &[20000.0], -120.0), Some(120.0));
+
+        // ノッチがない、または残したトーンに電力がなければ計算しない
+        assert_eq!(npr(&[1.0; 16], fs, &kept, &[], -120.0), None);
+        assert_eq!(npr(&vec![0.0; n], fs, &kept, &notch, -120.0), None);
+    }
+
+    /// クリーンな信号では隣接チャネル電力が非常に低く、クリップでスペクトル再成長が生じることをテスト
+    #[test]
+    fn test_acpr_clipping() {
+        use crate::signal::{clip_magnitude, ModulationType, SignalGenerator, SignalParams};
+
+        let n = 1000;
+        let fs = 100000.0;
+        let measure = |params: &SignalParams, clip: Option<f64>| {
+            let mut spectrum = SignalGenerator::new().generate_block(params, n);
+            if let Some(level) = clip {
+                clip_magnitude(&mut spectrum, level);
+            }
+            FftPlanner::new().plan_fft_forward(n).process(&mut spectrum);
+            let mut power: Vec<f64> = spectrum.iter().map(|c| c.norm_sqr()).collect();
+            power.rotate_left(n / 2);
+            // 主チャネル 10kHz±2kHz、隣接チャネルは±5kHz離れた同じ幅
+            acpr(&power, fs, 10000.0, 4000.0, 5000.0).unwrap()
+        };
+
+        // ビンに一致するCWは主チャネルにしか電力を持たない
+        let cw = SignalParams {
+            frequency: 10000.0,
+            sample_rate: fs,
+            ..Default::default()
+        };
+        let (lower, upper) = measure(&cw, None);
+        assert!(lower < -100.0 && upper < -100.0, "{} {}", lower, upper);
+
+        // 主チャネル内の4トーンをクリップすると相互変調が隣接チャネルに広がる
+        let multitone = SignalParams {
+            mod_type: ModulationType::Multitone,
+            multitone_count: 4,
+            multitone_spacing: 1000.0,
+            ..cw.clone()
+        };
+        let (clean_lower, clean_upper) = measure(&multitone, None);
+        assert!(clean_lower < -100.0 && clean_upper < -100.0);
+        let (lower, upper) = measure(&multitone, Some(0.3));
+        assert!(lower > -40.0 && upper > -40.0, "{} {}", lower, upper);
+    }
+
+    /// 既知の高調波を含むスペクトラムのTHDをテスト
+    #[test]
+    fn test_thd() {
+        let n = 1024;
+        let center = n / 2;
+        let mut spectrum = vec![0.0; n];
+        // 基本波 +20ビン、2次高調波は振幅0.1、3次高調波は振幅0.01
+        spectrum[center + 20] = 1.0;
+        spectrum[center + 40] = 0.01;
+        spectrum[center + 60] = 0.0001;
+        let expected = (0.01_f64 + 0.0001).sqrt();
+        assert!((thd(&spectrum, center + 20, DEFAULT_THD_HARMONICS) - expected).abs() < 1e-12);
+        // 2次高調波までに制限
+        assert!((thd(&spectrum, center + 20, 2) - 0.1).abs() < 1e-12);
+
+        // 負の周波数の基本波でも0Hzから外側に高調波を探す
+        let mut spectrum = vec![0.0; n];
+        spectrum[center - 100] = 1.0;
+        spectrum[center - 300] = 0.04;
+        assert!((thd(&spectrum, center - 100, DEFAULT_THD_HARMONICS) - 0.2).abs() < 1e-12);
+
+        // 基本波が0Hzの場合は定義できないので0
+        assert_eq!(thd(&spectrum, center, DEFAULT_THD_HARMONICS), 0.0);
+    }
+
+    /// 基本波の検出でDCを無視し、SINADが雑音と歪みの電力から求まることをテスト
+    #[test]
+    fn test_sinad() {
+        let n = 1024;
+        let center = n / 2;
+        let mut spectrum = vec![0.0; n];
+        // DCオフセットは基本波より大きくても無視される
+        spectrum[center] = 4.0;
+        spectrum[center + 20] = 1.0;
+        spectrum[center + 40] = 0.001;
+        spectrum[center - 200] = 0.009;
+        assert_eq!(fundamental_bin(&spectrum), Some(center + 20));
+        assert!((sinad(&spectrum, center + 20) - 20.0).abs() < 1e-9);
+
+        // 雑音と歪みがなければ無限大
+        let mut spectrum = vec![0.0; n];
+        spectrum[center + 20] = 1.0;
+        assert_eq!(sinad(&spectrum, center + 20), f64::INFINITY);
+        assert_eq!(fundamental_bin(&vec![0.0; n]), None);
+    }
+
+    /// 1本の大きなビンと1本の小さなビンからなるスペクトラムのSFDRをテスト
+    #[test]
+    fn test_sfdr() {
+        let mut spectrum = vec![0.0; 256];
+        spectrum[100] = 1.0;
+        spectrum[30] = 1e-6;
+        let (db, spur) = sfdr(&spectrum);
+        assert_eq!(spur, 30);
+        assert!((db - 60.0).abs() < 1e-9);
+
+        // 信号ピークのすぐ隣のビン（窓関数による広がり）はスプリアスとみなさない
+        spectrum[101] = 0.5;
+        spectrum[99] = 0.5;
+        let (db, spur) = sfdr(&spectrum);
+        assert_eq!(spur, 30);
+        assert!((db - 60.0).abs() < 1e-9);
+
+        // スプリアスがない場合は無限大
+        let mut spectrum = vec![0.0; 256];
+        spectrum[100] = 1.0;
+        assert_eq!(sfdr(&spectrum).0, f64::INFINITY);
+    }
+
+    /// 白色雑音のWelch PSDが雑音電力に一致し、平均化でばらつきが減ることをテスト
+    #[test]
+    fn test_welch_psd_white_noise() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+        use rand_distr::StandardNormal;
+
+        // I/Q各成分の分散0.5（複素雑音の電力1.0）
+        let mut rng = StdRng::seed_from_u64(1);
+        let samples: Vec<Complex<f64>> = (0..65536)
+            .map(|_| {
+                let i: f64 = rng.sample(StandardNormal);
+                let q: f64 = rng.sample(StandardNormal);
+                Complex::new(i, q) * 0.5_f64.sqrt()
+            })
+            .collect();
+
+        let spread = |psd: &[f64]| {
+            let mean = psd.iter().sum::<f64>() / psd.len() as f64;
+            let var = psd.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / psd.len() as f64;
+            (mean, var.sqrt() / mean)
+        };
+
+        let psd = welch_psd(&samples, 256, 128, WindowFunction::Hann);
+        assert_eq!(psd.len(), 256);
+        let (mean, relative_std) = spread(&psd);
+        assert!((mean - 1.0).abs() < 0.05, "mean PSD = {}", mean);
+
+        // セグメント1つ（平均化なし）のピリオドグラムはばらつきが大きい
+        let (_, single_std) = spread(&welch_psd(&samples[..256], 256, 0, WindowFunction::Hann));
+        assert!(relative_std < single_std / 5.0);
+
+        assert!(welch_psd(&samples[..100], 256, 0, WindowFunction::Hann).is_empty());
+        assert!(welch_psd(&samples, 256, 256, WindowFunction::Hann).is_empty());
+    }
+}
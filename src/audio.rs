@@ -0,0 +1,219 @@
+//! 音声再生モジュール
+//!
+//! このモジュールは、生成した信号を既定の音声出力デバイスで再生する機能を提供します。
+//! 可聴域の信号を実際に聞いて確認するためのもので、`audio`フィーチャーを有効にした場合のみ使用できます。
+//! 生成スレッドが連続して生成したサンプルをリングバッファに蓄え、出力デバイスのコールバックがそこから読み出します。
+
+use crate::{scale_samples, OutputMode, SignalGenerator, StreamSettings};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 再生できるサンプリングレートの範囲 (Hz)
+pub const AUDIO_SAMPLE_RATE_RANGE: RangeInclusive<f64> = 8000.0..=192000.0;
+
+/// リングバッファに蓄える再生時間 (秒) - 設定の変更が聞こえるまでの遅延にもなる
+const AUDIO_BUFFER_SECONDS: f64 = 0.2;
+
+/// 生成スレッドが一度に生成するサンプル数
+const AUDIO_BLOCK_SIZE: usize = 1024;
+
+/// 再生待ちのサンプル（[I, Q]のフレーム）のリングバッファ
+type AudioRing = Arc<Mutex<VecDeque<[f32; 2]>>>;
+
+/// 生成した信号を既定の出力デバイスで連続再生するプレーヤー
+///
+/// 複素IQでは左チャンネルにI成分、右チャンネルにQ成分を、実信号では両チャンネルにI成分を出力します。
+/// 生成器は再生中保持するため位相は連続し、振幅が±1を超える部分はクリップされます。
+pub struct AudioPlayer {
+    /// 再生中のサンプリングレート (Hz)
+    pub sample_rate: f64,
+    /// 再生する信号の設定（GUIの変更をブロックごとに反映）
+    settings: Arc<Mutex<StreamSettings>>,
+    /// 生成スレッドへの停止要求
+    stop: Arc<AtomicBool>,
+    /// 生成スレッド
+    thread: Option<std::thread::JoinHandle<()>>,
+    /// 出力デバイスのストリーム（破棄すると再生が止まる）
+    _stream: cpal::Stream,
+}
+
+impl AudioPlayer {
+    /// 既定の出力デバイスで再生を開始
+    ///
+    /// # 引数
+    /// * `settings` - 再生する信号の設定（サンプリングレートで出力デバイスを開く）
+    ///
+    /// # 戻り値
+    /// 成功時はプレーヤー、サンプリングレートが範囲外の場合や
+    /// 出力デバイスを開けない場合はエラーメッセージ
+    pub fn start(settings: StreamSettings) -> Result<Self, String> {
+        let sample_rate = settings.0.sample_rate;
+        if !AUDIO_SAMPLE_RATE_RANGE.contains(&sample_rate) {
+            return Err(format!("sample rate {} Hz is not playable", sample_rate));
+        }
+        let rate = cpal::SampleRate(sample_rate.round() as u32);
+
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or("no audio output device")?;
+        // サンプリングレートに対応した設定のうち、ステレオ・32ビット浮動小数点を優先
+        let config = device
+            .supported_output_configs()
+            .map_err(|e| e.to_string())?
+            .filter(|c| c.min_sample_rate() <= rate && rate <= c.max_sample_rate())
+            .max_by_key(|c| {
+                (
+                    c.channels() == 2,
+                    c.sample_format() == cpal::SampleFormat::F32,
+                )
+            })
+            .ok_or_else(|| format!("output device does not support {} Hz", rate.0))?
+            .with_sample_rate(rate);
+
+        let ring: AudioRing = Arc::new(Mutex::new(VecDeque::new()));
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::F32 => build_stream::<f32>(&device, &config.config(), &ring),
+            cpal::SampleFormat::I16 => build_stream::<i16>(&device, &config.config(), &ring),
+            cpal::SampleFormat::U16 => build_stream::<u16>(&device, &config.config(), &ring),
+            format => return Err(format!("unsupported sample format: {}", format)),
+        }?;
+        stream.play().map_err(|e| e.to_string())?;
+
+        let settings = Arc::new(Mutex::new(settings));
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let settings = Arc::clone(&settings);
+            let stop = Arc::clone(&stop);
+            let capacity = (sample_rate * AUDIO_BUFFER_SECONDS) as usize;
+            std::thread::spawn(move || fill_ring(&ring, capacity, &settings, &stop))
+        };
+        Ok(Self {
+            sample_rate,
+            settings,
+            stop,
+            thread: Some(thread),
+            _stream: stream,
+        })
+    }
+
+    /// 再生する信号の設定を更新（次のブロックから反映）
+    pub fn update(&self, settings: StreamSettings) {
+        *self.settings.lock().unwrap() = settings;
+    }
+}
+
+impl Drop for AudioPlayer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// リングバッファから読み出して出力するストリームを作成
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    ring: &AudioRing,
+) -> Result<cpal::Stream, String>
+where
+    T: cpal::SizedSample + cpal::FromSample<f32>,
+{
+    let ring = Arc::clone(ring);
+    let channels = config.channels as usize;
+    device
+        .build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                fill_output(data, channels, &mut ring.lock().unwrap());
+            },
+            |e| eprintln!("Audio output error: {}", e),
+            None,
+        )
+        .map_err(|e| e.to_string())
+}
+
+/// 出力バッファをリングバッファのフレームで埋める
+///
+/// 1チャンネル目にI成分、2チャンネル目にQ成分を書き込み、3チャンネル目以降は無音とします。
+/// リングバッファが空の場合（アンダーラン）は無音を出力します。
+///
+/// # 引数
+/// * `data` - チャンネルがインターリーブされた出力バッファ
+/// * `channels` - 出力のチャンネル数
+/// * `ring` - 再生待ちのフレーム
+fn fill_output<T: cpal::Sample + cpal::FromSample<f32>>(
+    data: &mut [T],
+    channels: usize,
+    ring: &mut VecDeque<[f32; 2]>,
+) {
+    for frame in data.chunks_mut(channels) {
+        let iq = ring.pop_front().unwrap_or([0.0, 0.0]);
+        for (ch, out) in frame.iter_mut().enumerate() {
+            *out = T::from_sample(iq.get(ch).copied().unwrap_or(0.0));
+        }
+    }
+}
+
+/// 停止要求があるまで信号を生成し、リングバッファを一定量に保つ
+fn fill_ring(
+    ring: &Mutex<VecDeque<[f32; 2]>>,
+    capacity: usize,
+    settings: &Mutex<StreamSettings>,
+    stop: &AtomicBool,
+) {
+    let mut generator = SignalGenerator::new();
+    while !stop.load(Ordering::Relaxed) {
+        if ring.lock().unwrap().len() + AUDIO_BLOCK_SIZE > capacity {
+            std::thread::sleep(Duration::from_millis(5));
+            continue;
+        }
+        let (params, amplitude, mode, impairments) = settings.lock().unwrap().clone();
+        let raw = generator.generate_block(&params, AUDIO_BLOCK_SIZE);
+        let mut block = scale_samples(&raw, amplitude, mode);
+        impairments.apply(&mut block);
+        ring.lock().unwrap().extend(block.iter().map(|s| {
+            let i = s.re.clamp(-1.0, 1.0) as f32;
+            match mode {
+                OutputMode::Complex => [i, s.im.clamp(-1.0, 1.0) as f32],
+                OutputMode::Real => [i, i],
+            }
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cpal::Sample;
+
+    /// 出力バッファにI/Qが各チャンネルへ振り分けられ、アンダーランでは無音になることをテスト
+    #[test]
+    fn test_fill_output() {
+        let mut ring: VecDeque<[f32; 2]> = [[0.5, -0.25], [1.0, -1.0]].into();
+
+        // ステレオ：3フレーム目はアンダーランで無音
+        let mut stereo = [9.0f32; 6];
+        fill_output(&mut stereo, 2, &mut ring);
+        assert_eq!(stereo, [0.5, -0.25, 1.0, -1.0, 0.0, 0.0]);
+        assert!(ring.is_empty());
+
+        // モノラルではI成分のみ、4チャンネル以上では3チャンネル目以降が無音
+        ring.extend([[0.5, -0.25]]);
+        let mut mono = [9.0f32; 1];
+        fill_output(&mut mono, 1, &mut ring);
+        assert_eq!(mono, [0.5]);
+        ring.extend([[0.5, -0.25]]);
+        let mut quad = [9i16; 4];
+        fill_output(&mut quad, 4, &mut ring);
+        assert_eq!(
+            quad,
+            [i16::from_sample(0.5f32), i16::from_sample(-0.25f32), 0, 0]
+        );
+    }
+}
@@ -1,18 +1,41 @@
 //! IVSG - Interactive Vector Signal Generator
 //!
 //! IVSGは、様々な変調方式をサポートする対話的なベクトル信号生成器です。
-//! CW、AM、FM、PM、パルス、マルチトーン信号を生成し、
-//! 時間領域・周波数領域でリアルタイムに可視化できます。
-//! 生成した信号はCSVまたはバイナリ形式でエクスポート可能です。
+//! CW、AM、FM、PM、SSB、パルス、マルチトーン、2トーン、BPSK、QPSK、M-PSK、16QAM、ASK、FSK/GFSK、MSK/GMSK、チャープ、掃引、周波数ホッピング、OFDM、雑音信号を生成し、
+//! 時間領域・周波数領域・スペクトログラムでリアルタイムに可視化できます。
+//! 生成した信号はCSV、バイナリ、WAV、SigMF、NumPy(.npy)、MATLAB(.mat)形式でエクスポートでき、
+//! TCPで外部のSDRソフトウェアに連続送信することもできます。
+//! `--headless`オプションを指定すると、GUIを起動せずに信号を生成してファイルに出力します。
 
+use clap::Parser;
 use eframe::egui;
-use egui_plot::{Line, Plot, PlotPoints};
+use egui_plot::{
+    Line, LineStyle, Plot, PlotImage, PlotPoint, PlotPoints, Points, Polygon, Text, VLine,
+};
 use num_complex::Complex;
 use rustfft::FftPlanner;
 use serde::{Deserialize, Serialize};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
 
+mod analysis;
+#[cfg(feature = "audio")]
+mod audio;
 mod signal;
-use signal::{ModulationType, MultitonePhase, SignalGenerator, SignalParams};
+use analysis::{
+    acpr, enbw, fundamental_bin, npr, occupied_bandwidth, papr_db, rms, scalloping_loss_db, sfdr,
+    sinad, stft, thd, welch_psd, window, WindowFunction, DEFAULT_THD_HARMONICS,
+    DEFAULT_TUKEY_RATIO,
+};
+use signal::{
+    crest_factor, min_symbol_rate, phase_noise_dbc_from_rms, phase_noise_rms_from_dbc, quantize,
+    saleh, saturate, shaping_filter_span, signal_stats, AmWaveform, AmplitudeProfile,
+    AmplitudeProfileKind, BurstConfig, ChannelConfig, HopSet, ModulationType, MultitonePhase,
+    MultitoneSpec, OfdmConfig, PrbsOrder, SalehParams, SaturationModel, Sideband, SignalGenerator,
+    SignalParams, SweepMode, Tone,
+};
 
 /// アプリケーションアイコンを読み込む
 ///
@@ -37,741 +60,7214 @@ fn load_icon() -> egui::IconData {
     }
 }
 
-/// アプリケーションのエントリーポイント
-///
-/// eframeフレームワークを使用してGUIアプリケーションを起動します。
-/// ウィンドウサイズは1200x800ピクセルで初期化されます。
-fn main() -> eframe::Result<()> {
-    let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([1200.0, 800.0])
-            .with_icon(load_icon()),
-        ..Default::default()
-    };
-    eframe::run_native(
-        "IVSG",
-        options,
-        Box::new(|_cc| Ok(Box::new(MyApp::default()))),
-    )
-}
-
-/// IVSGアプリケーションのメイン構造体
+/// コマンドライン引数
 ///
-/// 信号生成パラメータ、UI状態、プロット設定などを保持します。
-struct MyApp {
-    // === 基本パラメータ ===
+/// `--headless`または`--out`を指定した場合、その他の引数で指定したパラメータで
+/// 信号を生成して`--out`のファイルに出力します。省略したパラメータはGUIの初期値を使用します。
+#[derive(Parser)]
+#[command(name = "ivsg", about = "Interactive Vector Signal Generator")]
+struct Cli {
+    /// GUIを起動せずに信号を生成してファイルに出力する
+    #[arg(long)]
+    headless: bool,
     /// 搬送波周波数 (Hz)
-    frequency: f64,
-    /// 信号振幅
-    amplitude: f64,
+    #[arg(long)]
+    freq: Option<f64>,
+    /// 搬送波の初期位相 (度)
+    #[arg(long, allow_hyphen_values = true)]
+    initial_phase: Option<f64>,
     /// サンプリングレート (Hz)
-    sample_rate: f64,
-
-    // === 内部状態 ===
-    /// FFT計算用のプランナー
-    fft_planner: FftPlanner<f64>,
+    #[arg(long, alias = "fs")]
+    sample_rate: Option<f64>,
     /// 生成するサンプル数
-    num_samples: usize,
-    /// スペクトラム表示のスケール（線形/dB）
-    spectrum_scale: SpectrumScale,
+    #[arg(long, alias = "n")]
+    num_samples: Option<usize>,
+    /// 信号振幅
+    #[arg(long)]
+    amplitude: Option<f64>,
+    /// 信号レベル (dBm) - `--impedance`の負荷に供給する電力から振幅を求める
+    #[arg(long, conflicts_with = "amplitude", allow_hyphen_values = true)]
+    amplitude_dbm: Option<f64>,
+    /// dBm換算の基準インピーダンス (Ω)
+    #[arg(long)]
+    impedance: Option<f64>,
+    /// 変調方式 (cw, am, fm, pm, ssb, pulse, multitone, two-tone, bpsk, qpsk, psk, qam16, ask, fsk, msk, chirp, sweep, hopping, ofdm, noise)
+    #[arg(long = "mod", value_parser = parse_mod_type)]
+    mod_type: Option<ModulationType>,
+    /// 変調周波数 (Hz) - AM/FM/PMの変調周波数、パルスの繰り返し周波数
+    #[arg(long)]
+    mod_freq: Option<f64>,
+    /// AMの変調度
+    #[arg(long)]
+    am_index: Option<f64>,
+    /// AMの変調波形 (sine, triangle, square, sawtooth)
+    #[arg(long, value_parser = parse_am_waveform)]
+    am_waveform: Option<AmWaveform>,
+    /// FMのピーク周波数偏移 (Hz)
+    #[arg(long)]
+    fm_deviation: Option<f64>,
+    /// PMの変調指数 (rad)
+    #[arg(long)]
+    pm_index: Option<f64>,
+    /// パルスのデューティ比 (0-1)
+    #[arg(long)]
+    duty_cycle: Option<f64>,
+    /// パルスの立ち上がり/立ち下がり時間 (秒)
+    #[arg(long)]
+    pulse_edge_time: Option<f64>,
+    /// マルチトーンのトーン数
+    #[arg(long)]
+    tones: Option<usize>,
+    /// マルチトーンのトーン間隔 (Hz)
+    #[arg(long)]
+    tone_spacing: Option<f64>,
+    /// マルチトーンの初期位相 (zero, random, schroeder, newman)
+    #[arg(long, value_parser = parse_multitone_phase)]
+    multitone_phase: Option<MultitonePhase>,
+    /// マルチトーンの任意配置のトーンリスト（例: 1000,2500:0.5 = 周波数[:振幅]のカンマ区切り）
+    #[arg(long, value_parser = parse_tone_list)]
+    tone_list: Option<MultitoneSpec>,
+    /// マルチトーンの各トーンの振幅に掛ける窓関数 (rectangular, triangular, hann, hamming, blackman, blackman-harris, flattop, tukey[:テーパー比])
+    #[arg(long, value_parser = parse_window_function)]
+    tone_window: Option<WindowFunction>,
+    /// マルチトーンのNPR測定用ノッチの先頭のトーン番号
+    #[arg(long)]
+    notch_start: Option<usize>,
+    /// マルチトーンのNPR測定用ノッチで取り除くトーン数
+    #[arg(long)]
+    notch_width: Option<usize>,
+    /// 2トーンのトーン間隔 (Hz)
+    #[arg(long)]
+    two_tone_spacing: Option<f64>,
+    /// 乱数シード
+    #[arg(long)]
+    seed: Option<u64>,
+    /// SSB変調で残す側波帯 (usb, lsb)
+    #[arg(long, value_parser = parse_sideband)]
+    sideband: Option<Sideband>,
+    /// デジタル変調のシンボルレート (symbol/s)
+    #[arg(long)]
+    symbol_rate: Option<f64>,
+    /// PRBSの次数 (prbs9, prbs15)
+    #[arg(long, value_parser = parse_prbs_order)]
+    prbs: Option<PrbsOrder>,
+    /// ASKでビット0のときの振幅 (0-1)
+    #[arg(long)]
+    ask_low_level: Option<f64>,
+    /// PSKの多値数 (2, 4, 8)
+    #[arg(long, value_parser = parse_psk_order)]
+    psk_order: Option<usize>,
+    /// FSKの周波数偏移 (Hz)
+    #[arg(long)]
+    fsk_deviation: Option<f64>,
+    /// 指定したBT積のガウスフィルタでFSK/MSKを平滑化する（GFSK/GMSK）
+    #[arg(long)]
+    gfsk_bt: Option<f64>,
+    /// 指定したロールオフ率のRRCフィルタでデジタル変調のシンボルを整形する
+    #[arg(long)]
+    rrc_rolloff: Option<f64>,
+    /// RRCフィルタの長さ (シンボル)
+    #[arg(long)]
+    rrc_span: Option<usize>,
+    /// チャープの掃引帯域幅 (Hz)
+    #[arg(long)]
+    chirp_bandwidth: Option<f64>,
+    /// チャープの掃引時間 (秒)
+    #[arg(long)]
+    chirp_duration: Option<f64>,
+    /// 掃引の開始周波数 (Hz)
+    #[arg(long)]
+    sweep_start: Option<f64>,
+    /// 掃引の終了周波数 (Hz)
+    #[arg(long)]
+    sweep_stop: Option<f64>,
+    /// 1回の掃引にかける時間 (秒)
+    #[arg(long)]
+    sweep_time: Option<f64>,
+    /// 掃引の周波数の変化のさせ方 (continuous, linear, log)
+    #[arg(long, value_parser = parse_sweep_mode)]
+    sweep_mode: Option<SweepMode>,
+    /// ステップ掃引で各周波数に留まるサンプル数
+    #[arg(long)]
+    sweep_dwell: Option<usize>,
+    /// 周波数ホッピングの1ホップあたりの滞在時間 (秒)
+    #[arg(long)]
+    hop_dwell: Option<f64>,
+    /// 周波数ホッピングの帯域幅 (Hz)
+    #[arg(long)]
+    hop_bandwidth: Option<f64>,
+    /// 周波数ホッピングのチャネル間隔 (Hz)
+    #[arg(long)]
+    hop_step: Option<f64>,
+    /// 周波数ホッピングのホップ先の周波数リスト（例: 1000,5000,-3000 = 周波数のカンマ区切り）
+    #[arg(long, value_parser = parse_hop_list)]
+    hop_list: Option<HopSet>,
+    /// ホップのたびに搬送波の位相を0に戻す
+    #[arg(long)]
+    hop_reset_phase: bool,
+    /// OFDMのFFTサイズ（サブキャリア数）
+    #[arg(long)]
+    ofdm_fft: Option<usize>,
+    /// OFDMでデータを載せるサブキャリア数
+    #[arg(long)]
+    ofdm_used: Option<usize>,
+    /// OFDMのサイクリックプレフィックスの長さ (サンプル)
+    #[arg(long)]
+    ofdm_cp: Option<usize>,
+    /// OFDMの各サブキャリアのQAMの多値数 (4, 16)
+    #[arg(long)]
+    ofdm_qam: Option<usize>,
+    /// 指定したSNR (dB) で白色ガウス雑音を付加する
+    #[arg(long)]
+    snr: Option<f64>,
+    /// 振幅プロファイル (constant, ramp, staircase)
+    #[arg(long, value_parser = parse_amplitude_profile)]
+    amp_profile: Option<AmplitudeProfileKind>,
+    /// 振幅プロファイルの開始レベル (dB)
+    #[arg(long, allow_hyphen_values = true)]
+    profile_start: Option<f64>,
+    /// 振幅プロファイルの終了レベル (dB)
+    #[arg(long, allow_hyphen_values = true)]
+    profile_stop: Option<f64>,
+    /// 振幅ランプの長さ (サンプル)
+    #[arg(long)]
+    profile_ramp: Option<usize>,
+    /// 振幅の階段のステップ数
+    #[arg(long)]
+    profile_steps: Option<usize>,
+    /// 振幅の階段の各ステップに留まるサンプル数
+    #[arg(long)]
+    profile_dwell: Option<usize>,
+    /// バーストの周期 (サンプル) - 指定するとバーストを有効にする
+    #[arg(long)]
+    burst_period: Option<usize>,
+    /// バーストのオン時間 (サンプル)
+    #[arg(long)]
+    burst_on: Option<usize>,
+    /// バーストの立ち上がり/立ち下がり時間 (サンプル)
+    #[arg(long)]
+    burst_ramp: Option<usize>,
+    /// チャネルのドップラーシフト (Hz) - 指定するとチャネルを有効にする
+    #[arg(long, allow_hyphen_values = true)]
+    doppler: Option<f64>,
+    /// チャネルの遅延タップ（例: 0:1.0,5:0.5 = 遅延サンプル数:利得のカンマ区切り） - 指定するとチャネルを有効にする
+    #[arg(long, value_parser = parse_channel_taps)]
+    channel_taps: Option<Vec<(usize, f64)>>,
+    /// 指定したビット数でI/Qを量子化する
+    #[arg(long)]
+    quantization_bits: Option<u8>,
+    /// 指定した振幅でサンプルをクリップする
+    #[arg(long)]
+    clip_level: Option<f64>,
+    /// クリップのモデル (hard, tanh) - 指定するとクリップを有効にする
+    #[arg(long, value_parser = parse_saturation_model)]
+    clip_model: Option<SaturationModel>,
+    /// tanhクリップの小振幅での利得
+    #[arg(long)]
+    clip_gain: Option<f64>,
+    /// 標準パラメータのSalehモデルの増幅器を通す
+    #[arg(long)]
+    saleh: bool,
+    /// IQ利得不平衡 (dB)
+    #[arg(long, allow_hyphen_values = true)]
+    iq_gain_imbalance: Option<f64>,
+    /// IQ直交位相誤差 (度)
+    #[arg(long, allow_hyphen_values = true)]
+    iq_phase_error: Option<f64>,
+    /// I成分のDCオフセット
+    #[arg(long, allow_hyphen_values = true)]
+    dc_offset_i: Option<f64>,
+    /// Q成分のDCオフセット
+    #[arg(long, allow_hyphen_values = true)]
+    dc_offset_q: Option<f64>,
+    /// 位相雑音：1サンプルあたりの位相ランダムウォークの標準偏差 (度)
+    #[arg(long)]
+    phase_noise: Option<f64>,
+    /// 位相雑音：オフセット周波数でのレベル (dBc/Hz) - --phase-noiseの代わりに指定
+    #[arg(long, allow_hyphen_values = true)]
+    phase_noise_dbc: Option<f64>,
+    /// 位相雑音のレベルを指定するオフセット周波数 (Hz)
+    #[arg(long)]
+    phase_noise_offset: Option<f64>,
+    /// 複素IQの代わりに実信号（I成分のみ）を出力する
+    #[arg(long)]
+    real: bool,
+    /// 出力ファイル（拡張子 .csv / .bin / .npy / .mat / .wav / .sigmf-data で形式を選択）
+    #[arg(long)]
+    out: Option<std::path::PathBuf>,
+}
 
-    // === 変調設定 ===
-    /// 変調方式
-    mod_type: ModulationType,
+impl Cli {
+    /// 指定された引数だけをアプリケーションのパラメータに上書き
+    ///
+    /// # 引数
+    /// * `app` - パラメータを適用する対象のアプリケーション
+    fn apply_to_app(&self, app: &mut MyApp) {
+        let set = |target: &mut f64, value: Option<f64>| {
+            if let Some(value) = value {
+                *target = value;
+            }
+        };
+        set(&mut app.frequency, self.freq);
+        set(&mut app.initial_phase_deg, self.initial_phase);
+        set(&mut app.sample_rate, self.sample_rate);
+        set(&mut app.amplitude, self.amplitude);
+        set(&mut app.impedance, self.impedance);
+        if let Some(dbm) = self.amplitude_dbm {
+            app.amplitude = dbm_to_amplitude(dbm, app.impedance);
+            app.amplitude_unit = AmplitudeUnit::Dbm;
+        }
+        // 変調周波数は変調方式ごとに別のフィールドで保持している
+        set(&mut app.am_mod_freq, self.mod_freq);
+        set(&mut app.fm_mod_freq, self.mod_freq);
+        set(&mut app.pulse_freq, self.mod_freq);
+        set(&mut app.am_mod_index, self.am_index);
+        if let Some(waveform) = self.am_waveform {
+            app.am_waveform = waveform;
+        }
+        set(&mut app.fm_deviation, self.fm_deviation);
+        set(&mut app.pm_mod_index, self.pm_index);
+        set(&mut app.pulse_duty_cycle, self.duty_cycle);
+        set(&mut app.pulse_edge_time, self.pulse_edge_time);
+        set(&mut app.multitone_spacing, self.tone_spacing);
+        set(&mut app.tone_spacing, self.two_tone_spacing);
+        set(&mut app.symbol_rate, self.symbol_rate);
+        set(&mut app.ask_low_level, self.ask_low_level);
+        set(&mut app.fsk_deviation, self.fsk_deviation);
+        set(&mut app.chirp_bandwidth, self.chirp_bandwidth);
+        set(&mut app.chirp_duration, self.chirp_duration);
+        set(&mut app.sweep_start, self.sweep_start);
+        set(&mut app.sweep_stop, self.sweep_stop);
+        set(&mut app.sweep_time, self.sweep_time);
+        if let Some(mode) = self.sweep_mode {
+            app.sweep_mode = mode;
+        }
+        if let Some(dwell) = self.sweep_dwell {
+            app.sweep_dwell = dwell;
+        }
+        set(&mut app.hop_dwell, self.hop_dwell);
+        set(&mut app.hop_bandwidth, self.hop_bandwidth);
+        set(&mut app.hop_step, self.hop_step);
+        if let Some(set) = &self.hop_list {
+            app.hop_set = set.clone();
+        }
+        if self.hop_reset_phase {
+            app.hop_reset_phase = true;
+        }
+        set(&mut app.iq_gain_imbalance_db, self.iq_gain_imbalance);
+        set(&mut app.iq_phase_error_deg, self.iq_phase_error);
+        set(&mut app.dc_offset_i, self.dc_offset_i);
+        set(&mut app.dc_offset_q, self.dc_offset_q);
+        set(&mut app.phase_noise_rms_deg, self.phase_noise);
+        set(&mut app.phase_noise_offset_hz, self.phase_noise_offset);
+        if let Some(num_samples) = self.num_samples {
+            app.num_samples = num_samples;
+        }
+        if let Some(mod_type) = self.mod_type {
+            app.mod_type = mod_type;
+        }
+        if let Some(tones) = self.tones {
+            app.multitone_count = tones;
+        }
+        if let Some(phase) = self.multitone_phase {
+            app.multitone_phase = phase;
+        }
+        if let Some(spec) = &self.tone_list {
+            app.multitone_spec = spec.clone();
+        }
+        if let Some(kind) = self.tone_window {
+            app.multitone_amplitude_window = kind;
+        }
+        if let Some(start) = self.notch_start {
+            app.notch_start_tone = start;
+        }
+        if let Some(width) = self.notch_width {
+            app.notch_width_tones = width;
+        }
+        if let Some(seed) = self.seed {
+            app.seed = seed;
+        }
+        if let Some(sideband) = self.sideband {
+            app.ssb_sideband = sideband;
+        }
+        if let Some(prbs) = self.prbs {
+            app.prbs_order = prbs;
+        }
+        if let Some(order) = self.psk_order {
+            app.psk_order = order;
+        }
+        if let Some(bt) = self.gfsk_bt {
+            app.fsk_gaussian = true;
+            app.fsk_bt = bt;
+        }
+        if let Some(rolloff) = self.rrc_rolloff {
+            app.pulse_shaping = true;
+            app.rrc_rolloff = rolloff;
+        }
+        if let Some(span) = self.rrc_span {
+            app.rrc_span = span;
+        }
+        if self.real {
+            app.output_mode = OutputMode::Real;
+        }
+        if let Some(snr) = self.snr {
+            app.add_noise = true;
+            app.snr_db = snr;
+        }
+        if let Some(level) = self.phase_noise_dbc {
+            app.phase_noise_rms_deg =
+                phase_noise_rms_from_dbc(level, app.phase_noise_offset_hz, app.sample_rate);
+        }
+        if let Some(kind) = self.amp_profile {
+            app.amplitude_profile.kind = kind;
+        }
+        set(&mut app.amplitude_profile.start_db, self.profile_start);
+        set(&mut app.amplitude_profile.stop_db, self.profile_stop);
+        if let Some(ramp) = self.profile_ramp {
+            app.amplitude_profile.ramp_samples = ramp;
+        }
+        if let Some(steps) = self.profile_steps {
+            app.amplitude_profile.steps = steps;
+        }
+        if let Some(dwell) = self.profile_dwell {
+            app.amplitude_profile.dwell_samples = dwell;
+        }
+        if let Some(fft) = self.ofdm_fft {
+            app.ofdm.num_subcarriers = fft;
+        }
+        if let Some(used) = self.ofdm_used {
+            app.ofdm.active_subcarriers = used;
+        }
+        if let Some(cp) = self.ofdm_cp {
+            app.ofdm.cp_len = cp;
+        }
+        if let Some(order) = self.ofdm_qam {
+            app.ofdm.qam_order = order;
+        }
+        if let Some(period) = self.burst_period {
+            app.burst_enabled = true;
+            app.burst.period_samples = period;
+        }
+        if let Some(on) = self.burst_on {
+            app.burst.on_samples = on;
+        }
+        if let Some(ramp) = self.burst_ramp {
+            app.burst.ramp_samples = ramp;
+        }
+        if let Some(doppler) = self.doppler {
+            app.channel_enabled = true;
+            app.channel.doppler_hz = doppler;
+        }
+        if let Some(taps) = &self.channel_taps {
+            app.channel_enabled = true;
+            app.channel.taps = taps.clone();
+        }
+        if let Some(bits) = self.quantization_bits {
+            app.quantization_enabled = true;
+            app.quantization_bits = bits;
+        }
+        if let Some(level) = self.clip_level {
+            app.clip_enabled = true;
+            app.clip_level = level;
+        }
+        if let Some(model) = self.clip_model {
+            app.clip_enabled = true;
+            app.clip_model = model;
+        }
+        set(&mut app.clip_gain, self.clip_gain);
+        if self.saleh {
+            app.saleh_enabled = true;
+        }
+    }
+}
 
-    // === AM変調パラメータ ===
-    /// AM変調周波数 (Hz)
-    am_mod_freq: f64,
-    /// AM変調指数 (0-1)
-    am_mod_index: f64,
+/// コマンドラインで指定された変調方式の名前を解析
+///
+/// # 引数
+/// * `name` - 変調方式の名前（大文字小文字は区別しない）
+///
+/// # 戻り値
+/// 対応する変調方式、未知の名前の場合はエラーメッセージ
+fn parse_mod_type(name: &str) -> Result<ModulationType, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "cw" => Ok(ModulationType::CW),
+        "am" => Ok(ModulationType::AM),
+        "fm" => Ok(ModulationType::FM),
+        "pm" => Ok(ModulationType::PM),
+        "pulse" => Ok(ModulationType::Pulse),
+        "multitone" => Ok(ModulationType::Multitone),
+        "two-tone" | "twotone" => Ok(ModulationType::TwoTone),
+        "bpsk" => Ok(ModulationType::BPSK),
+        "qpsk" => Ok(ModulationType::QPSK),
+        "psk" | "mpsk" => Ok(ModulationType::PSK),
+        "qam16" | "16qam" => Ok(ModulationType::QAM16),
+        "ask" | "ook" => Ok(ModulationType::ASK),
+        "fsk" => Ok(ModulationType::FSK),
+        "msk" => Ok(ModulationType::MSK),
+        "chirp" | "lfm" => Ok(ModulationType::Chirp),
+        "sweep" => Ok(ModulationType::Sweep),
+        "noise" | "awgn" => Ok(ModulationType::Noise),
+        "ssb" => Ok(ModulationType::SSB),
+        "hopping" | "fhss" => Ok(ModulationType::Hopping),
+        "ofdm" => Ok(ModulationType::OFDM),
+        _ => Err(format!("unknown modulation type: {}", name)),
+    }
+}
 
-    // === FM変調パラメータ ===
-    /// FM変調周波数 (Hz)
-    fm_mod_freq: f64,
-    /// FM周波数偏移 (Hz)
-    fm_deviation: f64,
+/// コマンドラインで指定されたマルチトーンの初期位相設定を解析
+fn parse_multitone_phase(name: &str) -> Result<MultitonePhase, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "zero" => Ok(MultitonePhase::Zero),
+        "random" => Ok(MultitonePhase::Random),
+        "schroeder" => Ok(MultitonePhase::Schroeder),
+        "newman" => Ok(MultitonePhase::Newman),
+        _ => Err(format!("unknown multitone phase: {}", name)),
+    }
+}
 
-    // === PM変調パラメータ ===
-    /// PM変調指数 (Beta)
-    pm_mod_index: f64,
+/// コマンドラインで指定されたクリップのモデルを解析
+fn parse_saturation_model(name: &str) -> Result<SaturationModel, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "hard" | "clip" => Ok(SaturationModel::Hard),
+        "tanh" | "soft" => Ok(SaturationModel::Tanh),
+        _ => Err(format!("unknown clip model: {}", name)),
+    }
+}
 
-    // === パルス変調パラメータ ===
-    /// パルス周波数 (Hz)
-    pulse_freq: f64,
-    /// パルスデューティサイクル (0-1)
-    pulse_duty_cycle: f64,
+/// コマンドラインで指定された掃引の方法を解析
+fn parse_sweep_mode(name: &str) -> Result<SweepMode, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "continuous" => Ok(SweepMode::Continuous),
+        "linear" | "linear-step" => Ok(SweepMode::LinearStep),
+        "log" | "log-step" => Ok(SweepMode::LogStep),
+        _ => Err(format!("unknown sweep mode: {}", name)),
+    }
+}
 
-    // === マルチトーンパラメータ ===
-    /// トーン数
-    multitone_count: usize,
-    /// トーン間隔 (Hz)
-    multitone_spacing: f64,
-    /// 初期位相設定
-    multitone_phase: MultitonePhase,
-    /// ランダム位相生成用シード
-    seed: u64,
+/// コマンドラインで指定されたAMの変調波形を解析
+fn parse_am_waveform(name: &str) -> Result<AmWaveform, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "sine" | "sin" => Ok(AmWaveform::Sine),
+        "triangle" => Ok(AmWaveform::Triangle),
+        "square" => Ok(AmWaveform::Square),
+        "sawtooth" | "saw" => Ok(AmWaveform::Sawtooth),
+        _ => Err(format!("unknown AM waveform: {}", name)),
+    }
+}
 
-    // === 表示設定 ===
-    /// 時間軸の単位（秒/サンプル数）
-    time_domain_unit: TimeDomainUnit,
-    /// 時間領域プロット表示フラグ
-    show_time_domain: bool,
-    /// 周波数領域プロット表示フラグ
-    show_freq_domain: bool,
+/// コマンドラインで指定された振幅プロファイルの種類を解析
+fn parse_amplitude_profile(name: &str) -> Result<AmplitudeProfileKind, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "constant" | "none" => Ok(AmplitudeProfileKind::Constant),
+        "ramp" => Ok(AmplitudeProfileKind::Ramp),
+        "staircase" | "steps" => Ok(AmplitudeProfileKind::Staircase),
+        _ => Err(format!("unknown amplitude profile: {}", name)),
+    }
+}
 
-    // === プロット制御用の内部状態 ===
-    /// 前回の時間軸単位（単位変更検出用）
-    last_time_domain_unit: TimeDomainUnit,
-    /// 前回のプロット範囲
-    last_plot_bounds: Option<egui_plot::PlotBounds>,
-    /// 強制的に設定するプロット範囲（単位変更時に使用）
-    forced_plot_bounds: Option<egui_plot::PlotBounds>,
+/// コマンドラインで指定されたSSBの側波帯を解析
+fn parse_sideband(name: &str) -> Result<Sideband, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "usb" | "upper" => Ok(Sideband::Upper),
+        "lsb" | "lower" => Ok(Sideband::Lower),
+        _ => Err(format!("unknown sideband: {}", name)),
+    }
 }
 
-/// スペクトラム表示のスケール設定
-#[derive(PartialEq, Serialize, Deserialize)]
-enum SpectrumScale {
-    /// 線形スケール
-    Linear,
-    /// デシベル（dB）スケール
-    Decibel,
+/// コマンドラインで指定された窓関数の名前を解析
+fn parse_window_function(name: &str) -> Result<WindowFunction, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "rectangular" | "rect" | "none" => Ok(WindowFunction::Rectangular),
+        "triangular" | "triangle" => Ok(WindowFunction::Triangular),
+        "hann" => Ok(WindowFunction::Hann),
+        "hamming" => Ok(WindowFunction::Hamming),
+        "blackman" => Ok(WindowFunction::Blackman),
+        "blackman-harris" | "blackmanharris" => Ok(WindowFunction::BlackmanHarris),
+        "flattop" | "flat-top" => Ok(WindowFunction::FlatTop),
+        "tukey" => Ok(WindowFunction::Tukey(DEFAULT_TUKEY_RATIO)),
+        other => match other.strip_prefix("tukey:").map(str::parse::<f64>) {
+            Some(Ok(ratio)) if (0.0..=1.0).contains(&ratio) => Ok(WindowFunction::Tukey(ratio)),
+            Some(_) => Err(format!("invalid Tukey taper ratio: {}", name)),
+            None => Err(format!("unknown window function: {}", name)),
+        },
+    }
 }
 
-/// 時間軸の単位設定
-#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Copy)]
-enum TimeDomainUnit {
-    /// 秒単位で表示
-    Seconds,
-    /// サンプル数単位で表示
-    Samples,
+/// 窓関数の選択欄を作成
+///
+/// テューキー窓を選択している場合のみテーパー比の入力欄を表示します。
+fn window_function_combo(ui: &mut egui::Ui, id: &str, value: &mut WindowFunction) {
+    egui::ComboBox::new(id, "")
+        .selected_text(format!("{:?}", value))
+        .show_ui(ui, |ui| {
+            for kind in WindowFunction::ALL {
+                // テューキー窓は現在のテーパー比を保ったまま選択できるようにする
+                let kind = match (kind, *value) {
+                    (WindowFunction::Tukey(_), WindowFunction::Tukey(ratio)) => {
+                        WindowFunction::Tukey(ratio)
+                    }
+                    _ => kind,
+                };
+                ui.selectable_value(value, kind, format!("{:?}", kind));
+            }
+        });
+    if let WindowFunction::Tukey(ratio) = value {
+        ui.add(egui::DragValue::new(ratio).speed(0.01).range(0.0..=1.0))
+            .on_hover_text("Tukey taper ratio (0 = rectangular, 1 = Hann)");
+    }
 }
 
-/// アプリケーションパラメータの保存/復元用構造体
+/// コマンドラインで指定されたマルチトーンのトーンリストを解析
 ///
-/// すべてのユーザー設定可能なパラメータを含み、JSON形式でシリアライズ可能です。
-#[derive(Serialize, Deserialize)]
-struct AppParams {
-    frequency: f64,
-    amplitude: f64,
-    sample_rate: f64,
-    num_samples: usize,
-    spectrum_scale: SpectrumScale,
-    mod_type: ModulationType,
-    am_mod_freq: f64,
-    am_mod_index: f64,
-    fm_mod_freq: f64,
-    fm_deviation: f64,
-    pm_mod_index: f64,
-    pulse_freq: f64,
-    pulse_duty_cycle: f64,
-    multitone_count: usize,
-    multitone_spacing: f64,
-    multitone_phase: MultitonePhase,
-    seed: u64,
-    time_domain_unit: TimeDomainUnit,
-    show_time_domain: bool,
-    show_freq_domain: bool,
+/// 「周波数[:振幅]」をカンマで区切ったリストを受け付けます。振幅の省略時は1.0です。
+fn parse_tone_list(list: &str) -> Result<MultitoneSpec, String> {
+    let tones = list
+        .split(',')
+        .map(|item| {
+            let (freq, amplitude) = item.split_once(':').unwrap_or((item, "1.0"));
+            let parse = |v: &str| {
+                v.trim()
+                    .parse::<f64>()
+                    .map_err(|_| format!("invalid tone: {}", item))
+            };
+            Ok(Tone {
+                freq: parse(freq)?,
+                amplitude: parse(amplitude)?,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(MultitoneSpec::Explicit(tones))
 }
 
-impl AppParams {
-    /// MyAppからパラメータを抽出してAppParamsを生成
-    ///
-    /// # 引数
-    /// * `app` - 現在のアプリケーション状態
-    ///
-    /// # 戻り値
-    /// シリアライズ可能なパラメータ構造体
-    fn from_app(app: &MyApp) -> Self {
-        Self {
-            frequency: app.frequency,
-            amplitude: app.amplitude,
-            sample_rate: app.sample_rate,
-            num_samples: app.num_samples,
-            spectrum_scale: match app.spectrum_scale {
-                SpectrumScale::Linear => SpectrumScale::Linear,
-                SpectrumScale::Decibel => SpectrumScale::Decibel,
-            },
-            mod_type: app.mod_type,
-            am_mod_freq: app.am_mod_freq,
-            am_mod_index: app.am_mod_index,
-            fm_mod_freq: app.fm_mod_freq,
-            fm_deviation: app.fm_deviation,
-            pm_mod_index: app.pm_mod_index,
-            pulse_freq: app.pulse_freq,
-            pulse_duty_cycle: app.pulse_duty_cycle,
-            multitone_count: app.multitone_count,
-            multitone_spacing: app.multitone_spacing,
-            multitone_phase: app.multitone_phase,
-            seed: app.seed,
-            time_domain_unit: match app.time_domain_unit {
-                TimeDomainUnit::Seconds => TimeDomainUnit::Seconds,
-                TimeDomainUnit::Samples => TimeDomainUnit::Samples,
-            },
-            show_time_domain: app.show_time_domain,
-            show_freq_domain: app.show_freq_domain,
-        }
+/// コマンドラインで指定されたチャネルの遅延タップを解析
+///
+/// 「遅延サンプル数:利得」をカンマで区切ったリストを受け付けます。
+fn parse_channel_taps(list: &str) -> Result<Vec<(usize, f64)>, String> {
+    list.split(',')
+        .map(|item| {
+            let invalid = || format!("invalid channel tap: {}", item);
+            let (delay, gain) = item.split_once(':').ok_or_else(invalid)?;
+            let delay = delay.trim().parse::<usize>().map_err(|_| invalid())?;
+            let gain = gain.trim().parse::<f64>().map_err(|_| invalid())?;
+            Ok((delay, gain))
+        })
+        .collect()
+}
+
+/// コマンドラインで指定された周波数ホッピングの周波数リストを解析
+fn parse_hop_list(list: &str) -> Result<HopSet, String> {
+    let freqs = list
+        .split(',')
+        .map(|item| {
+            item.trim()
+                .parse::<f64>()
+                .map_err(|_| format!("invalid hop frequency: {}", item))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(HopSet::List(freqs))
+}
+
+/// コマンドラインで指定されたPRBSの次数を解析
+fn parse_prbs_order(name: &str) -> Result<PrbsOrder, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "prbs9" | "9" => Ok(PrbsOrder::Prbs9),
+        "prbs15" | "15" => Ok(PrbsOrder::Prbs15),
+        _ => Err(format!("unknown PRBS order: {}", name)),
     }
+}
 
-    /// 保存されたパラメータをMyAppに適用
-    ///
-    /// # 引数
-    /// * `app` - パラメータを適用する対象のアプリケーション
-    fn apply_to_app(self, app: &mut MyApp) {
-        app.frequency = self.frequency;
-        app.amplitude = self.amplitude;
-        app.sample_rate = self.sample_rate;
-        app.num_samples = self.num_samples;
-        app.spectrum_scale = match self.spectrum_scale {
-            SpectrumScale::Linear => SpectrumScale::Linear,
-            SpectrumScale::Decibel => SpectrumScale::Decibel,
-        };
-        app.mod_type = self.mod_type;
-        app.am_mod_freq = self.am_mod_freq;
-        app.am_mod_index = self.am_mod_index;
-        app.fm_mod_freq = self.fm_mod_freq;
-        app.fm_deviation = self.fm_deviation;
-        app.pm_mod_index = self.pm_mod_index;
-        app.pulse_freq = self.pulse_freq;
-        app.pulse_duty_cycle = self.pulse_duty_cycle;
-        app.multitone_count = self.multitone_count;
-        app.multitone_spacing = self.multitone_spacing;
-        app.multitone_phase = self.multitone_phase;
-        app.seed = self.seed;
-        app.time_domain_unit = match self.time_domain_unit {
-            TimeDomainUnit::Seconds => TimeDomainUnit::Seconds,
-            TimeDomainUnit::Samples => TimeDomainUnit::Samples,
-        };
-        app.show_time_domain = self.show_time_domain;
-        app.show_freq_domain = self.show_freq_domain;
+/// コマンドラインで指定されたPSKの多値数を解析
+fn parse_psk_order(value: &str) -> Result<usize, String> {
+    match value {
+        "2" => Ok(2),
+        "4" => Ok(4),
+        "8" => Ok(8),
+        _ => Err(format!("PSK order must be 2, 4 or 8: {}", value)),
     }
 }
 
-impl Default for MyApp {
-    /// MyAppのデフォルト値を設定
-    ///
-    /// 起動時のデフォルトパラメータ：
-    /// - 搬送波周波数: 1kHz
-    /// - 振幅: 1.0
-    /// - サンプリングレート: 100kHz
-    /// - サンプル数: 1000
-    /// - 変調方式: CW（無変調）
-    fn default() -> Self {
-        Self {
-            frequency: 1000.0,
-            amplitude: 1.0,
-            sample_rate: 100000.0,
-            fft_planner: FftPlanner::new(),
-            num_samples: 1000,
-            spectrum_scale: SpectrumScale::Decibel,
-            mod_type: ModulationType::CW,
-            am_mod_freq: 100.0,
-            am_mod_index: 0.5,
-            fm_mod_freq: 100.0,
-            fm_deviation: 1000.0,
-            pm_mod_index: 1.0,
-            pulse_freq: 1000.0,
-            pulse_duty_cycle: 0.5,
-            multitone_count: 10,
-            multitone_spacing: 1000.0,
-            multitone_phase: MultitonePhase::Random,
-            seed: 0,
-            time_domain_unit: TimeDomainUnit::Seconds,
-            show_time_domain: true,
-            show_freq_domain: true,
-            last_time_domain_unit: TimeDomainUnit::Seconds,
-            last_plot_bounds: None,
-            forced_plot_bounds: None,
+/// ヘッドレスモードで確認用に表示する、使用したパラメータの要約
+///
+/// 変調方式・搬送波周波数・サンプリングレートに加え、選択中の変調方式で使用する
+/// パラメータと、有効な場合は雑音のSNRを`名前: 値`の形式で並べます。
+fn describe_params(params: &SignalParams) -> String {
+    let p = params;
+    let field = |name: &str, value: &dyn std::fmt::Debug| format!("{}: {:?}", name, value);
+    let mut fields = vec![
+        field("mod_type", &p.mod_type),
+        field("frequency", &p.frequency),
+        field("sample_rate", &p.sample_rate),
+    ];
+    fields.extend(match p.mod_type {
+        ModulationType::CW => vec![],
+        ModulationType::AM => vec![
+            field("mod_freq", &p.mod_freq),
+            field("mod_strength", &p.mod_strength),
+            field("am_waveform", &p.am_waveform),
+        ],
+        ModulationType::FM | ModulationType::PM => vec![
+            field("mod_freq", &p.mod_freq),
+            field("mod_strength", &p.mod_strength),
+        ],
+        ModulationType::Pulse => vec![
+            field("mod_freq", &p.mod_freq),
+            field("mod_strength", &p.mod_strength),
+            field("pulse_edge_time", &p.pulse_edge_time),
+        ],
+        ModulationType::Multitone => vec![
+            field("multitone_spec", &p.multitone_spec),
+            field("multitone_count", &p.multitone_count),
+            field("multitone_spacing", &p.multitone_spacing),
+            field("multitone_phase", &p.multitone_phase),
+            field("notch_start_tone", &p.notch_start_tone),
+            field("notch_width_tones", &p.notch_width_tones),
+            field("multitone_amplitude_window", &p.multitone_amplitude_window),
+        ],
+        ModulationType::TwoTone => vec![field("tone_spacing", &p.tone_spacing)],
+        ModulationType::SSB => vec![
+            field("mod_freq", &p.mod_freq),
+            field("ssb_sideband", &p.ssb_sideband),
+        ],
+        ModulationType::FSK | ModulationType::MSK => vec![
+            field("symbol_rate", &p.symbol_rate),
+            field("prbs_order", &p.prbs_order),
+            field("fsk_deviation", &p.fsk_deviation),
+            field("fsk_gaussian", &p.fsk_gaussian),
+            field("fsk_bt", &p.fsk_bt),
+        ],
+        ModulationType::BPSK
+        | ModulationType::QPSK
+        | ModulationType::QAM16
+        | ModulationType::ASK
+        | ModulationType::PSK => {
+            let mut digital = vec![
+                field("symbol_rate", &p.symbol_rate),
+                field("prbs_order", &p.prbs_order),
+                field("pulse_shaping", &p.pulse_shaping),
+                field("rrc_rolloff", &p.rrc_rolloff),
+                field("rrc_span", &p.rrc_span),
+            ];
+            match p.mod_type {
+                ModulationType::ASK => digital.push(field("ask_low_level", &p.ask_low_level)),
+                ModulationType::PSK => digital.push(field("psk_order", &p.psk_order)),
+                _ => {}
+            }
+            digital
         }
+        ModulationType::Chirp => vec![
+            field("chirp_bandwidth", &p.chirp_bandwidth),
+            field("chirp_duration", &p.chirp_duration),
+        ],
+        ModulationType::Sweep => vec![
+            field("sweep_start", &p.sweep_start),
+            field("sweep_stop", &p.sweep_stop),
+            field("sweep_time", &p.sweep_time),
+            field("sweep_mode", &p.sweep_mode),
+            field("sweep_dwell", &p.sweep_dwell),
+        ],
+        ModulationType::Noise => vec![field("seed", &p.seed)],
+        ModulationType::Hopping => vec![
+            field("hop_set", &p.hop_set),
+            field("hop_bandwidth", &p.hop_bandwidth),
+            field("hop_step", &p.hop_step),
+            field("hop_dwell", &p.hop_dwell),
+            field("hop_reset_phase", &p.hop_reset_phase),
+        ],
+        ModulationType::OFDM => vec![field("ofdm", &p.ofdm), field("seed", &p.seed)],
+    });
+    if p.add_noise {
+        fields.push(field("snr_db", &p.snr_db));
     }
+    fields.join(", ")
 }
 
-impl eframe::App for MyApp {
-    /// アプリケーションのUIを更新
+/// GUIを起動せずに信号を生成してファイルに出力
+///
+/// # 引数
+/// * `cli` - コマンドライン引数
+///
+/// # 戻り値
+/// プロセスの終了コード（成功時は0）
+fn run_headless(cli: &Cli) -> i32 {
+    let Some(out) = &cli.out else {
+        eprintln!("--out is required in headless mode");
+        return 2;
+    };
+
+    // GUIの初期値をもとに、指定されたパラメータだけを上書きする
+    let mut app = MyApp::default();
+    cli.apply_to_app(&mut app);
+    // GUIの保存値と同じ範囲チェックを行い、生成できないパラメータではエラー終了
+    if let Err(e) = AppParams::from_app(&app).validate() {
+        eprintln!("Invalid parameters: {}", e);
+        return 2;
+    }
+
+    let params = app.signal_params();
+    eprintln!(
+        "Generating {} samples (amplitude {}) with {}",
+        app.num_samples,
+        app.amplitude,
+        describe_params(&params)
+    );
+
+    // CSV/バイナリはチャンクごとに生成して書き込み、その他の形式は全サンプルを生成して出力
+    let streaming = out
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("csv") || e.eq_ignore_ascii_case("bin"));
+    let result = if streaming {
+        export_streaming(
+            out,
+            &params,
+            app.num_samples,
+            app.amplitude,
+            app.output_mode,
+            app.output_impairments(),
+        )
+    } else {
+        let mut samples = scale_samples(
+            &SignalGenerator::new().generate_block(&params, app.num_samples),
+            app.amplitude,
+            app.output_mode,
+        );
+        app.output_impairments().apply(&mut samples);
+        export_by_extension(out, &samples, &params, app.output_mode)
+    };
+
+    match result {
+        Ok(()) => {
+            eprintln!("Exported {} samples to {:?}", app.num_samples, out);
+            0
+        }
+        Err(e) => {
+            eprintln!("Failed to export: {}", e);
+            1
+        }
+    }
+}
+
+/// ストリーミングエクスポートで一度に生成・書き込みするサンプル数
+const EXPORT_CHUNK_SIZE: usize = 65536;
+
+/// 信号を一定サイズのチャンクごとに生成しながらCSV/バイナリファイルに出力
+///
+/// 全サンプルをメモリに保持せず、`EXPORT_CHUNK_SIZE`サンプルずつ生成して書き込むため、
+/// 長い信号でもメモリ使用量は1チャンク分に抑えられます。
+/// 雑音付加時の雑音電力はチャンクごとの信号電力から決まります。
+///
+/// # 引数
+/// * `path` - 出力先ファイルパス（拡張子 .csv / .bin）
+/// * `params` - 信号生成パラメータ
+/// * `num_samples` - 生成するサンプル数
+/// * `amplitude` - 信号振幅
+/// * `mode` - 出力信号の形式（複素IQ/実信号）
+/// * `impairments` - 振幅適用後に加える出力段の不完全性
+///
+/// # 戻り値
+/// 成功時はOk(())、失敗時または未対応の拡張子の場合はエラー
+fn export_streaming(
+    path: &std::path::Path,
+    params: &SignalParams,
+    num_samples: usize,
+    amplitude: f64,
+    mode: OutputMode,
+    impairments: OutputImpairments,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::Write;
+    let mut generator = SignalGenerator::new();
+    let chunks = (0..num_samples)
+        .step_by(EXPORT_CHUNK_SIZE)
+        .map(move |start| {
+            let len = EXPORT_CHUNK_SIZE.min(num_samples - start);
+            let raw = generator.generate_block(params, len);
+            let mut chunk = scale_samples(&raw, amplitude, mode);
+            impairments.apply(&mut chunk);
+            (start, chunk)
+        });
+
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "csv" => {
+            let mut wtr = csv::WriterBuilder::new()
+                .has_headers(false)
+                .from_path(path)?;
+            for (start, chunk) in chunks {
+                write_csv_rows(&mut wtr, &chunk, start, None, params.sample_rate, mode)?;
+            }
+            wtr.flush()?;
+        }
+        "bin" => {
+            // 1チャンク分のバイト列をまとめて書き込めるバッファサイズ
+            let capacity = EXPORT_CHUNK_SIZE * mode.channels() * BinFormat::F32.bytes_per_value();
+            let mut writer =
+                std::io::BufWriter::with_capacity(capacity, std::fs::File::create(path)?);
+            let mut clipped = 0;
+            for (_, chunk) in chunks {
+                clipped += write_bin_values(&mut writer, &chunk, BinFormat::F32, 1.0, mode)?;
+            }
+            warn_clipped(clipped, num_samples * mode.channels());
+            writer.flush()?;
+        }
+        _ => return Err(format!("streaming export is not supported: {:?}", path).into()),
+    }
+    Ok(())
+}
+
+/// 出力ファイルの拡張子に応じたエクスポート関数でサンプルを出力
+///
+/// # 引数
+/// * `path` - 出力先ファイルパス
+/// * `samples` - エクスポートする複素数サンプル配列
+/// * `params` - 信号生成パラメータ
+///
+/// # 戻り値
+/// 成功時はOk(())、失敗時または未対応の拡張子の場合はエラー
+fn export_by_extension(
+    path: &std::path::Path,
+    samples: &[Complex<f64>],
+    params: &SignalParams,
+    mode: OutputMode,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "csv" => export_to_csv(path, samples, None, params.sample_rate, mode)?,
+        "bin" => export_to_bin(path, samples, BinFormat::F32, 1.0, mode)?,
+        "npy" => export_to_npy(path, samples)?,
+        "mat" => export_to_mat(path, samples, params.sample_rate)?,
+        "wav" => export_to_wav(
+            path,
+            samples,
+            params.sample_rate,
+            hound::SampleFormat::Float,
+            mode,
+        )?,
+        "sigmf-data" | "sigmf-meta" => export_to_sigmf(path, samples, params, BinFormat::F32, 1.0)?,
+        _ => return Err(format!("unsupported output format: {:?}", path).into()),
+    }
+    Ok(())
+}
+
+/// SI接頭辞（k、M、G）付きの数値を解析
+///
+/// 「1.024M」「10 kHz」「2.4GHz」のような入力を受け付けます。末尾の「Hz」は省略できます。
+///
+/// # 戻り値
+/// 解析した値。数値として解釈できない場合はNone
+fn parse_si_value(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let text = text
+        .strip_suffix("Hz")
+        .or_else(|| text.strip_suffix("hz"))
+        .unwrap_or(text)
+        .trim_end();
+    let (number, multiplier) = match text.chars().last()? {
+        'k' | 'K' => (&text[..text.len() - 1], 1e3),
+        'M' => (&text[..text.len() - 1], 1e6),
+        'G' => (&text[..text.len() - 1], 1e9),
+        _ => (text, 1.0),
+    };
+    number.trim().parse::<f64>().ok().map(|v| v * multiplier)
+}
+
+/// 周波数などの主要な数値の入力欄を作成
+///
+/// ドラッグと矢印キー（↑/↓）では`step`刻みで値が変化し、
+/// クリックして直接入力する際は`parse_si_value`の形式も受け付けます。
+fn freq_drag_value(value: &mut f64, step: f64) -> egui::DragValue<'_> {
+    egui::DragValue::new(value)
+        .speed(step)
+        .custom_parser(parse_si_value)
+}
+
+/// 窓関数のENBWとスカラッピング損失の表示に使用する窓の長さ
+const WINDOW_METRICS_LEN: usize = 4096;
+
+/// 前回終了時のパラメータをeframeの保存領域に保存するキー
+const APP_PARAMS_KEY: &str = "app_params";
+
+/// TCPストリーミングで一度に生成・送信するサンプル数
+const STREAM_BLOCK_SIZE: usize = 4096;
+
+/// TCPストリーミングで送信する信号の設定（生成パラメータ・振幅・出力形式・出力段の不完全性）
+type StreamSettings = (SignalParams, f64, OutputMode, OutputImpairments);
+
+/// TCPストリーミングのデフォルトの待ち受けアドレス（ローカルホストのみ）
+const DEFAULT_STREAM_BIND: &str = "127.0.0.1";
+
+/// TCPストリーミングの接続状態
+#[derive(Clone, Debug, PartialEq)]
+enum StreamStatus {
+    /// 接続を待ち受け中
+    Listening,
+    /// クライアントに送信中
+    Connected(SocketAddr),
+    /// クライアントへの送信が終了し、次の接続を待ち受け中
+    Closed(SocketAddr, String),
+    /// 接続の受け付けに失敗して待ち受けを終了
+    Failed(String),
+}
+
+impl std::fmt::Display for StreamStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamStatus::Listening => write!(f, "waiting for connection"),
+            StreamStatus::Connected(addr) => write!(f, "streaming to {}", addr),
+            StreamStatus::Closed(addr, e) => {
+                write!(
+                    f,
+                    "stream to {} closed: {} (waiting for connection)",
+                    addr, e
+                )
+            }
+            StreamStatus::Failed(e) => write!(f, "failed to accept connection: {}", e),
+        }
+    }
+}
+
+/// 生成したIQをTCPで連続送信するストリーミングサーバー
+///
+/// 指定したアドレスとポートで接続を待ち受け、接続したクライアントに32ビット浮動小数点の
+/// インターリーブIQ（`export_to_bin`のF32形式と同じ）をサンプリングレートに合わせて送り続けます。
+/// 生成器はブロック間で保持するため位相は連続し、クライアントが切断すると次の接続を待ちます。
+struct TcpStreamer {
+    /// 待ち受けているアドレスとポート番号
+    addr: SocketAddr,
+    /// 送信する信号の設定（GUIの変更をブロックごとに反映）
+    settings: Arc<Mutex<StreamSettings>>,
+    /// 送信スレッドが更新する接続状態
+    status: Arc<Mutex<StreamStatus>>,
+    /// 送信スレッドへの停止要求
+    stop: Arc<AtomicBool>,
+    /// 送信スレッド
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl TcpStreamer {
+    /// 指定したアドレスとポートで待ち受けを開始
     ///
-    /// フレームごとに呼び出され、UI描画と状態更新を行います。
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // === トップパネル：制御UI ===
-        egui::TopBottomPanel::top("controls_panel").show(ctx, |ui| {
-            // パラメータの保存/復元ボタン
-            ui.horizontal(|ui| {
-                // パラメータ保存
-                if ui.button("Save Parameters").clicked() {
-                    if let Some(path) = rfd::FileDialog::new()
-                        .add_filter("JSON", &["json"])
-                        .set_file_name("params.json")
-                        .save_file()
-                    {
-                        let params = AppParams::from_app(self);
-                        if let Ok(json) = serde_json::to_string_pretty(&params) {
-                            if let Err(e) = std::fs::write(&path, json) {
-                                eprintln!("Failed to save parameters: {}", e);
-                            }
-                        }
-                    }
-                }
-                // パラメータ復元
-                if ui.button("Recall Parameters").clicked() {
-                    if let Some(path) = rfd::FileDialog::new()
-                        .add_filter("JSON", &["json"])
-                        .pick_file()
-                    {
-                        if let Ok(json) = std::fs::read_to_string(&path) {
-                            if let Ok(params) = serde_json::from_str::<AppParams>(&json) {
-                                params.apply_to_app(self);
-                            } else {
-                                eprintln!("Failed to parse parameters");
-                            }
-                        } else {
-                            eprintln!("Failed to read parameters file");
-                        }
-                    }
+    /// # 引数
+    /// * `bind` - 待ち受けるアドレス（他のホストから接続する場合は"0.0.0.0"など）
+    /// * `port` - 待ち受けるポート番号（0の場合は空いているポートを自動で選択）
+    /// * `settings` - 送信する信号の設定
+    ///
+    /// # 戻り値
+    /// 成功時はストリーミングサーバー、アドレスやポートを使用できない場合はエラー
+    fn start(bind: &str, port: u16, settings: StreamSettings) -> std::io::Result<Self> {
+        let listener = TcpListener::bind((bind.trim(), port))?;
+        // 停止要求を確認できるよう、接続待ちはノンブロッキングで行う
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let settings = Arc::new(Mutex::new(settings));
+        let status = Arc::new(Mutex::new(StreamStatus::Listening));
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let settings = Arc::clone(&settings);
+            let status = Arc::clone(&status);
+            let stop = Arc::clone(&stop);
+            std::thread::spawn(move || serve_stream(&listener, &settings, &status, &stop))
+        };
+        Ok(Self {
+            addr,
+            settings,
+            status,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// 送信する信号の設定を更新（次のブロックから反映）
+    fn update(&self, settings: StreamSettings) {
+        *self.settings.lock().unwrap() = settings;
+    }
+
+    /// 現在の接続状態
+    fn status(&self) -> StreamStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// 送信を停止し、送信スレッドの終了を待つ
+    fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for TcpStreamer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// 停止要求があるまで接続を待ち受け、接続したクライアントに順に信号を送信
+///
+/// 接続状態は`status`に書き込み、GUIがStopボタンの横に表示します。
+fn serve_stream(
+    listener: &TcpListener,
+    settings: &Mutex<StreamSettings>,
+    status: &Mutex<StreamStatus>,
+    stop: &AtomicBool,
+) {
+    let mut generator = SignalGenerator::new();
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, addr)) => {
+                *status.lock().unwrap() = StreamStatus::Connected(addr);
+                if let Err(e) = stream_to_client(stream, &mut generator, settings, stop) {
+                    *status.lock().unwrap() = StreamStatus::Closed(addr, e.to_string());
+                } else {
+                    *status.lock().unwrap() = StreamStatus::Listening;
                 }
-            });
-            ui.separator();
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            Err(e) => {
+                *status.lock().unwrap() = StreamStatus::Failed(e.to_string());
+                return;
+            }
+        }
+    }
+}
 
-            // === 共通パラメータセクション ===
-            ui.heading("Common Parameters");
+/// 1つのクライアントに、停止要求があるか切断されるまでブロックごとに信号を送信
+///
+/// # 引数
+/// * `stream` - 接続したクライアント
+/// * `generator` - 送信に使用する生成器（接続をまたいで位相を保持）
+/// * `settings` - 送信する信号の設定
+/// * `stop` - 停止要求
+///
+/// # 戻り値
+/// 停止要求で終了した場合はOk(())、切断や送信の失敗ではエラー
+fn stream_to_client(
+    mut stream: std::net::TcpStream,
+    generator: &mut SignalGenerator,
+    settings: &Mutex<StreamSettings>,
+    stop: &AtomicBool,
+) -> std::io::Result<()> {
+    use std::io::{ErrorKind, Write};
+    stream.set_nonblocking(false)?;
+    // クライアントが受信を止めていても停止要求を確認できるよう、書き込みにタイムアウトを設ける
+    stream.set_write_timeout(Some(Duration::from_millis(100)))?;
 
-            // 周波数設定
-            ui.horizontal(|ui| {
-                ui.label("Frequency (Hz):");
-                ui.add(
-                    egui::DragValue::new(&mut self.frequency)
-                        .speed(10.0)
-                        .range(0.0..=10000000000.0),
-                );
-            });
+    let start = Instant::now();
+    let mut sent = 0.0;
+    let mut buffer = Vec::new();
+    while !stop.load(Ordering::Relaxed) {
+        let (params, amplitude, mode, impairments) = settings.lock().unwrap().clone();
+        let raw = generator.generate_block(&params, STREAM_BLOCK_SIZE);
+        let mut block = scale_samples(&raw, amplitude, mode);
+        impairments.apply(&mut block);
+        buffer.clear();
+        write_bin_values(&mut buffer, &block, BinFormat::F32, 1.0, mode)?;
 
-            // 振幅設定
-            ui.horizontal(|ui| {
-                ui.label("Amplitude:");
-                ui.add(
-                    egui::DragValue::new(&mut self.amplitude)
-                        .speed(0.01)
-                        .range(0.0..=1000000.0),
-                );
-            });
+        // タイムアウトしても途中まで書いた位置から続けて、サンプルの区切りを保つ
+        let mut written = 0;
+        while written < buffer.len() {
+            if stop.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+            match stream.write(&buffer[written..]) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(n) => written += n,
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted
+                    ) => {}
+                Err(e) => return Err(e),
+            }
+        }
 
-            // サンプリングレート設定
-            ui.horizontal(|ui| {
-                ui.label("Sample Rate (Hz):");
-                ui.add(
-                    egui::DragValue::new(&mut self.sample_rate)
-                        .speed(100.0)
-                        .range(1000.0..=1000000000.0),
-                );
-            });
+        // サンプリングレートより速く送らないよう、送信済みのサンプルの時刻まで待つ
+        sent += STREAM_BLOCK_SIZE as f64 / params.sample_rate;
+        while !stop.load(Ordering::Relaxed) {
+            let ahead = sent - start.elapsed().as_secs_f64();
+            if ahead <= 0.0 {
+                break;
+            }
+            std::thread::sleep(Duration::from_secs_f64(ahead.min(0.05)));
+        }
+    }
+    Ok(())
+}
 
-            // サンプル数設定
-            ui.horizontal(|ui| {
-                ui.label("Num Samples:");
-                ui.add(
-                    egui::DragValue::new(&mut self.num_samples)
-                        .speed(10.0)
-                        .range(1..=1000000),
-                );
-            });
+/// バックグラウンドで生成するブロックの要求
+struct GenerationRequest {
+    /// 要求の通し番号（結果との対応付けに使用）
+    id: u64,
+    /// 信号生成パラメータ
+    params: SignalParams,
+    /// 生成するサンプル数
+    num_samples: usize,
+    /// 生成器をリセットしてから生成するかどうか（falseでは前のブロックの続きを生成）
+    reset: bool,
+    /// 生成したブロックに対して行う振幅の適用と解析
+    analysis: BlockAnalysis,
+}
 
-            // 表示切替チェックボックス
-            ui.horizontal(|ui| {
-                ui.checkbox(&mut self.show_time_domain, "Show Time Domain");
-                ui.checkbox(&mut self.show_freq_domain, "Show Freq Domain");
-            });
+/// 生成したブロックに対してワーカーで行う、振幅の適用と解析の設定
+#[derive(Clone)]
+struct BlockAnalysis {
+    /// 振幅・出力形式・出力段の不完全性
+    scaling: (f64, OutputMode, OutputImpairments),
+    /// スペクトラムと測定値の設定（周波数領域プロットを表示しない場合はNone）
+    spectrum: Option<(SpectrumSettings, MeasurementSettings)>,
+    /// スペクトログラムの設定（表示しない場合はNone）
+    spectrogram: Option<SpectrogramSettings>,
+}
 
-            ui.separator();
+/// ワーカーで生成・解析したブロック
+struct GeneratedBlock {
+    /// 生成したサンプル（振幅適用前）
+    raw: Vec<Complex<f64>>,
+    /// 振幅・出力形式・出力段の不完全性を適用したサンプル
+    samples: Vec<Complex<f64>>,
+    /// `samples`の計算に使用した振幅・出力形式・出力段の不完全性
+    scaling: (f64, OutputMode, OutputImpairments),
+    /// スペクトラムと測定値（要求されなかった場合はNone）
+    spectrum: Option<SpectrumCache>,
+    /// スペクトログラムの設定と画像（要求されなかった場合はNone）
+    spectrogram: Option<(SpectrogramSettings, Option<egui::ColorImage>)>,
+}
 
-            // === 変調設定セクション ===
-            ui.heading("Modulation");
+impl GeneratedBlock {
+    /// 生成したブロックに振幅などを適用し、要求された解析を行う
+    ///
+    /// # 引数
+    /// * `raw` - 生成したサンプル（振幅適用前）
+    /// * `analysis` - 振幅の適用と解析の設定
+    /// * `planner` - FFTプランナー
+    fn analyze(
+        raw: Vec<Complex<f64>>,
+        analysis: BlockAnalysis,
+        planner: &mut FftPlanner<f64>,
+    ) -> Self {
+        let (amplitude, mode, impairments) = analysis.scaling;
+        let mut samples = scale_samples(&raw, amplitude, mode);
+        impairments.apply(&mut samples);
+        let spectrum = analysis.spectrum.map(|(settings, measurement)| {
+            SpectrumCache::compute(&samples, settings, measurement, planner)
+        });
+        let spectrogram = analysis
+            .spectrogram
+            .map(|settings| (settings, spectrogram_image(&samples, &settings)));
+        Self {
+            raw,
+            samples,
+            scaling: analysis.scaling,
+            spectrum,
+            spectrogram,
+        }
+    }
+}
 
-            // 変調タイプ選択
-            ui.horizontal(|ui| {
-                ui.label("Type:");
-                ui.radio_value(&mut self.mod_type, ModulationType::CW, "CW");
-                ui.radio_value(&mut self.mod_type, ModulationType::AM, "AM");
-                ui.radio_value(&mut self.mod_type, ModulationType::FM, "FM");
-                ui.radio_value(&mut self.mod_type, ModulationType::PM, "PM");
-                ui.radio_value(&mut self.mod_type, ModulationType::Pulse, "Pulse");
-                ui.radio_value(&mut self.mod_type, ModulationType::Multitone, "Multitone");
+/// 信号のブロックを別スレッドで生成・解析するワーカー
+///
+/// サンプル数が多い場合や重い変調方式でもUIが固まらないよう、生成器をワーカースレッドで保持し、
+/// UIスレッドからの要求に応じて生成したブロックをチャネルで返します。
+/// 振幅の適用と、表示中のスペクトラム・測定値・スペクトログラムの計算もワーカーで行うため、
+/// UIスレッドで計算し直すのは表示設定を変更したときだけです。
+/// 生成中に複数の要求が届いた場合は最新の要求だけを処理し、完了するたびに再描画を要求します。
+struct GenerationWorker {
+    /// 生成の要求の送信側（破棄するとワーカースレッドが終了する）
+    requests: Option<mpsc::Sender<GenerationRequest>>,
+    /// 生成したブロックの受信側（要求の通し番号と生成・解析したブロック）
+    results: mpsc::Receiver<(u64, GeneratedBlock)>,
+    /// 最後に送信した要求の通し番号
+    requested_id: u64,
+    /// 最後に受け取った結果の要求の通し番号
+    received_id: u64,
+    /// ワーカースレッド
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl GenerationWorker {
+    /// ワーカースレッドを開始
+    ///
+    /// # 引数
+    /// * `ctx` - 生成の完了時に再描画を要求するコンテキスト
+    fn start(ctx: egui::Context) -> Self {
+        let (request_tx, request_rx) = mpsc::channel();
+        let (result_tx, result_rx) = mpsc::channel();
+        let thread =
+            std::thread::spawn(move || generate_in_background(&request_rx, &result_tx, &ctx));
+        Self {
+            requests: Some(request_tx),
+            results: result_rx,
+            requested_id: 0,
+            received_id: 0,
+            thread: Some(thread),
+        }
+    }
+
+    /// ブロックの生成を要求
+    ///
+    /// # 引数
+    /// * `params` - 信号生成パラメータ
+    /// * `num_samples` - 生成するサンプル数
+    /// * `reset` - 生成器をリセットしてから生成するかどうか
+    /// * `analysis` - 生成したブロックに対して行う振幅の適用と解析
+    fn request(
+        &mut self,
+        params: SignalParams,
+        num_samples: usize,
+        reset: bool,
+        analysis: BlockAnalysis,
+    ) {
+        self.requested_id += 1;
+        if let Some(requests) = &self.requests {
+            let _ = requests.send(GenerationRequest {
+                id: self.requested_id,
+                params,
+                num_samples,
+                reset,
+                analysis,
+            });
+        }
+    }
+
+    /// 最新の要求のブロックが完了していれば取り出す（ブロックしない）
+    ///
+    /// それより前の要求の結果は、現在の設定（サンプリングレートやサンプル数）と
+    /// 合わないため破棄します。
+    ///
+    /// # 戻り値
+    /// 最新の要求のブロックがまだ完了していない場合はNone
+    fn try_take(&mut self) -> Option<GeneratedBlock> {
+        let (id, block) = self.results.try_iter().last()?;
+        self.received_id = id;
+        (id == self.requested_id).then_some(block)
+    }
+
+    /// 最新の要求のブロックが完了するまで待って取り出す
+    ///
+    /// # 戻り値
+    /// 最新の要求のブロック。要求がない場合やワーカーが終了している場合はNone
+    fn wait(&mut self) -> Option<GeneratedBlock> {
+        while self.is_busy() {
+            let (id, block) = self.results.recv().ok()?;
+            self.received_id = id;
+            if !self.is_busy() {
+                return Some(block);
+            }
+        }
+        None
+    }
+
+    /// 最新の要求のブロックを生成中かどうか
+    fn is_busy(&self) -> bool {
+        self.received_id < self.requested_id
+    }
+}
+
+impl Drop for GenerationWorker {
+    fn drop(&mut self) {
+        self.requests = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// 要求の送信側が破棄されるまで、要求に応じてブロックを生成・解析して返す
+fn generate_in_background(
+    requests: &mpsc::Receiver<GenerationRequest>,
+    results: &mpsc::Sender<(u64, GeneratedBlock)>,
+    ctx: &egui::Context,
+) {
+    // 生成器はブロック間で保持し、"Next Block"では位相を途切れさせずに続きを生成する
+    let mut generator = SignalGenerator::new();
+    let mut planner = FftPlanner::new();
+    while let Ok(mut request) = requests.recv() {
+        // 生成中に溜まった要求は最新のものだけ処理する（途中にリセットの要求があればリセットする）
+        let mut reset = request.reset;
+        for next in requests.try_iter() {
+            reset |= next.reset;
+            request = next;
+        }
+        if reset {
+            generator.reset();
+        }
+        let raw = generator.generate_block(&request.params, request.num_samples);
+        let block = GeneratedBlock::analyze(raw, request.analysis, &mut planner);
+        if results.send((request.id, block)).is_err() {
+            return;
+        }
+        ctx.request_repaint();
+    }
+}
+
+/// アプリケーションのエントリーポイント
+///
+/// eframeフレームワークを使用してGUIアプリケーションを起動します。
+/// ウィンドウサイズは1200x800ピクセルで初期化されます。
+/// `--headless`または`--out`が指定された場合はGUIを起動せず、信号をファイルに出力して終了します。
+fn main() -> eframe::Result<()> {
+    let cli = Cli::parse();
+    if cli.headless || cli.out.is_some() {
+        std::process::exit(run_headless(&cli));
+    }
+
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size([1200.0, 800.0])
+            .with_icon(load_icon()),
+        ..Default::default()
+    };
+    eframe::run_native("IVSG", options, Box::new(|cc| Ok(Box::new(MyApp::new(cc)))))
+}
+
+/// IVSGアプリケーションのメイン構造体
+///
+/// 信号生成パラメータ、UI状態、プロット設定などを保持します。
+struct MyApp {
+    // === 基本パラメータ ===
+    /// 搬送波周波数 (Hz)
+    frequency: f64,
+    /// 搬送波の初期位相 (度)
+    initial_phase_deg: f64,
+    /// 周波数・サンプリングレートなどの入力欄を矢印キーやドラッグで変化させる刻み (Hz)
+    freq_step: f64,
+    /// 信号振幅（線形、常にこの値を基準に保持）
+    amplitude: f64,
+    /// 振幅の表示・入力単位
+    amplitude_unit: AmplitudeUnit,
+    /// dBm換算で想定する負荷インピーダンス (Ω)
+    impedance: f64,
+    /// サンプリングレート (Hz)
+    sample_rate: f64,
+
+    // === 内部状態 ===
+    /// FFT計算用のプランナー（FFT長ごとにプランを保持して再利用）
+    fft_planner: FftPlanner<f64>,
+    /// 信号を生成するワーカー（最初のフレームで開始し、位相状態を保持）
+    worker: Option<GenerationWorker>,
+    /// 前回ブロックの生成を要求したときのパラメータ（変更検出用）
+    last_params: Option<SignalParams>,
+    /// 前回ブロックの生成を要求したときのサンプル数（変更検出用）
+    last_num_samples: usize,
+    /// 最新の要求のブロックをワーカーで生成中かどうか（完了するまで前回のブロックを表示）
+    generating: bool,
+    /// 生成済みのサンプルブロック（振幅適用前）
+    raw_samples: Vec<Complex<f64>>,
+    /// 振幅を適用したサンプルブロック（表示とエクスポートで共有するキャッシュ）
+    samples: Arc<Vec<Complex<f64>>>,
+    /// `samples`の計算に使用した振幅・出力形式・出力段の不完全性（変更検出用）
+    samples_scaling: Option<(f64, OutputMode, OutputImpairments)>,
+    /// 周波数領域プロットの点列のキャッシュ（`samples`の変更時に破棄）
+    spectrum_cache: Option<SpectrumCache>,
+    /// スペクトログラムの設定と画像のキャッシュ（`samples`の変更時に破棄）
+    spectrogram_cache: Option<(SpectrogramSettings, Option<egui::ColorImage>)>,
+    /// マルチトーンのゼロ位相/Schroeder位相のPAPR比較結果と、その計算に使用したパラメータ
+    multitone_papr_cache: Option<(SignalParams, (f64, f64))>,
+    /// このフレームでサンプルまたはスペクトラムを再計算したかどうか
+    recomputed: bool,
+    /// 次のブロックの生成が要求されたかどうか
+    next_block_requested: bool,
+    /// 読み込んだファイルの名前（Someの間は信号を生成せず、読み込んだサンプルを表示）
+    imported: Option<String>,
+    /// 読み込むBINファイルのサンプル形式
+    import_format: BinFormat,
+    /// 直前の読み込みで発生したエラー（UIに表示）
+    import_error: Option<String>,
+    /// 生成するサンプル数
+    num_samples: usize,
+    /// 白色ガウス雑音を付加するかどうか
+    add_noise: bool,
+    /// 雑音付加時の信号対雑音比 (dB)
+    snr_db: f64,
+    /// IQ不平衡：利得不平衡 (dB)
+    iq_gain_imbalance_db: f64,
+    /// IQ不平衡：直交位相誤差 (度)
+    iq_phase_error_deg: f64,
+    /// DCオフセット（I成分）
+    dc_offset_i: f64,
+    /// DCオフセット（Q成分）
+    dc_offset_q: f64,
+    /// 位相雑音：1サンプルあたりの位相の標準偏差 (度)
+    phase_noise_rms_deg: f64,
+    /// 位相雑音のレベル (dBc/Hz) を表示・入力するオフセット周波数 (Hz)
+    phase_noise_offset_hz: f64,
+    /// 変調方式によらず信号の振幅を時間とともに変化させるプロファイル
+    amplitude_profile: AmplitudeProfile,
+    /// 変調後の信号にバーストのゲートを掛けるかどうか
+    burst_enabled: bool,
+    /// バーストの周期・オン時間・立ち上がり時間 (サンプル)
+    burst: BurstConfig,
+    /// 送信信号にチャネル（マルチパス・ドップラーシフト）を適用するかどうか
+    channel_enabled: bool,
+    /// チャネルのドップラーシフトと遅延タップ
+    channel: ChannelConfig,
+    /// OFDMのサブキャリア数・サイクリックプレフィックス長・サブキャリアの変調
+    ofdm: OfdmConfig,
+    /// 振幅適用後にDACのビット深度で量子化するかどうか
+    quantization_enabled: bool,
+    /// 量子化ビット数
+    quantization_bits: u8,
+    /// 振幅適用後にサンプルの振幅をクリップするかどうか
+    clip_enabled: bool,
+    /// クリップする振幅（フルスケール1.0に対する線形値）
+    clip_level: f64,
+    /// クリップのモデル（ハードクリップ/tanh）
+    clip_model: SaturationModel,
+    /// tanhクリップの小振幅での利得
+    clip_gain: f64,
+    /// 振幅適用後にSalehモデルの非線形増幅器を通すかどうか
+    saleh_enabled: bool,
+    /// Salehモデルの増幅器のパラメータ
+    saleh: SalehParams,
+    /// スペクトラム表示のスケール（線形/dB）
+    spectrum_scale: SpectrumScale,
+    /// FFT前に適用する窓関数
+    window_function: WindowFunction,
+    /// スペクトラム計算時にFFT長を2のべき乗までゼロ詰めするかどうか
+    zero_pad_fft: bool,
+    /// 単一FFTの代わりにWelch法で平均化したパワースペクトル密度を表示するかどうか
+    welch_averaging: bool,
+    /// Welch法のセグメント長
+    welch_segment_len: usize,
+    /// Welch法の隣接セグメント間の重なりサンプル数
+    welch_overlap: usize,
+    /// スペクトラムの各ビンの最大値を保持して表示するかどうか
+    max_hold: bool,
+    /// スペクトラムを正の周波数のみ対数周波数軸で表示するかどうか
+    log_freq: bool,
+    /// dBスペクトラムの基準レベル（絶対/ピーク）
+    reference_level: ReferenceLevel,
+    /// dB表示のノイズフロア (dB) - これより小さい値はこの値で表示
+    db_floor: f64,
+    /// 最大値保持トレース（各ビンの電力の最大値、線形）
+    max_hold_trace: Vec<f64>,
+    /// 比較用に保存した参照スペクトラム（保存時の `[周波数, 振幅]` の点列）
+    reference_trace: Vec<[f64; 2]>,
+    /// 参照スペクトラムを保存したときの表示スケールとPSD表示の有無
+    reference_scale: (SpectrumScale, bool),
+    /// 周波数領域プロット上のマーカー（最大2つ、2つ目は1つ目との差分を表示）
+    markers: Vec<Marker>,
+    /// 占有帯域幅に含める電力の割合 (%)
+    obw_percent: f64,
+    /// ACPR測定のチャネル帯域幅 (Hz)
+    acp_channel_bw: f64,
+    /// ACPR測定の主チャネルと隣接チャネルの間隔 (Hz)
+    acp_channel_offset: f64,
+
+    // === 変調設定 ===
+    /// 変調方式
+    mod_type: ModulationType,
+
+    // === AM変調パラメータ ===
+    /// AM変調周波数 (Hz)
+    am_mod_freq: f64,
+    /// AM変調指数 (0-1)
+    am_mod_index: f64,
+    /// AMの変調波形
+    am_waveform: AmWaveform,
+
+    // === FM変調パラメータ ===
+    /// FM変調周波数 (Hz)
+    fm_mod_freq: f64,
+    /// FMのピーク周波数偏移 (Hz) - 瞬時周波数は f_c ± Δf の範囲で変化する
+    fm_deviation: f64,
+
+    // === PM変調パラメータ ===
+    /// PM変調指数 (Beta)
+    pm_mod_index: f64,
+
+    // === SSB変調パラメータ ===
+    /// 残す側波帯（変調周波数はAMと共通）
+    ssb_sideband: Sideband,
+
+    // === パルス変調パラメータ ===
+    /// パルス周波数 (Hz)
+    pulse_freq: f64,
+    /// パルスデューティサイクル (0-1)
+    pulse_duty_cycle: f64,
+    /// パルスの立ち上がり/立ち下がり時間 (秒)
+    pulse_edge_time: f64,
+
+    // === マルチトーンパラメータ ===
+    /// トーン数
+    multitone_count: usize,
+    /// トーン間隔 (Hz)
+    multitone_spacing: f64,
+    /// 初期位相設定
+    multitone_phase: MultitonePhase,
+    /// トーン配置（等間隔/任意のリスト）
+    multitone_spec: MultitoneSpec,
+    /// 各トーンの振幅に掛ける窓関数（トーン配列の端ほど振幅を下げる）
+    multitone_amplitude_window: WindowFunction,
+    /// NPR測定用ノッチの先頭のトーン番号
+    notch_start_tone: usize,
+    /// NPR測定用ノッチで取り除くトーン数（0でノッチなし）
+    notch_width_tones: usize,
+    /// ランダム位相生成用シード
+    seed: u64,
+
+    // === 2トーンパラメータ ===
+    /// トーン間隔 (Hz)
+    tone_spacing: f64,
+
+    // === デジタル変調パラメータ ===
+    /// シンボルレート (シンボル/秒)
+    symbol_rate: f64,
+    /// PRBSの次数
+    prbs_order: PrbsOrder,
+    /// ASKのビット0の振幅 (0-1)
+    ask_low_level: f64,
+    /// PSKの多値数 (2/4/8)
+    psk_order: usize,
+    /// FSKの周波数偏移 (Hz)
+    fsk_deviation: f64,
+    /// FSK/MSKにガウスフィルタを適用するかどうか（GFSK/GMSK）
+    fsk_gaussian: bool,
+    /// GFSK/GMSKのBT積
+    fsk_bt: f64,
+    /// シンボル列をルートレイズドコサインフィルタで整形するかどうか（FSK/MSKを除く）
+    pulse_shaping: bool,
+    /// RRCフィルタのロールオフ率 α
+    rrc_rolloff: f64,
+    /// RRCフィルタの長さ (シンボル)
+    rrc_span: usize,
+
+    // === チャープパラメータ ===
+    /// 掃引帯域幅 (Hz)
+    chirp_bandwidth: f64,
+    /// 掃引時間 (秒)
+    chirp_duration: f64,
+
+    // === 周波数掃引パラメータ ===
+    /// 開始周波数 (Hz)
+    sweep_start: f64,
+    /// 終了周波数 (Hz)
+    sweep_stop: f64,
+    /// 掃引時間 (秒)
+    sweep_time: f64,
+    /// 連続掃引/ステップ掃引（線形・対数）
+    sweep_mode: SweepMode,
+    /// ステップ掃引のドウェル時間 (サンプル)
+    sweep_dwell: usize,
+
+    // === 周波数ホッピングパラメータ ===
+    /// ホップ先の決め方（帯域内ランダム/リスト）
+    hop_set: HopSet,
+    /// ホップする帯域幅 (Hz)
+    hop_bandwidth: f64,
+    /// チャネル間隔 (Hz)
+    hop_step: f64,
+    /// 1ホップあたりの滞在時間 (秒)
+    hop_dwell: f64,
+    /// ホップごとに位相を0に戻すかどうか
+    hop_reset_phase: bool,
+
+    // === 表示設定 ===
+    /// 時間軸の単位（秒/サンプル数）
+    time_domain_unit: TimeDomainUnit,
+    /// 時間領域プロット表示フラグ
+    show_time_domain: bool,
+    /// 時間領域プロットの表示形式（波形/コンスタレーション/ベクトルスコープ）
+    time_view: TimeView,
+    /// コンスタレーションで連続するサンプルを線で結んで軌跡を表示するかどうか
+    constellation_trajectory: bool,
+    /// 周波数領域プロット表示フラグ
+    show_freq_domain: bool,
+    /// スペクトログラム表示フラグ
+    show_spectrogram: bool,
+    /// スペクトログラムのFFTサイズ
+    stft_size: usize,
+    /// スペクトログラムのフレーム間の重なりサンプル数
+    stft_overlap: usize,
+
+    // === プロット制御用の内部状態 ===
+    /// 前回の時間軸単位（単位変更検出用）
+    last_time_domain_unit: TimeDomainUnit,
+    /// 前回のプロット範囲
+    last_plot_bounds: Option<egui_plot::PlotBounds>,
+    /// 強制的に設定するプロット範囲（単位変更時に使用）
+    forced_plot_bounds: Option<egui_plot::PlotBounds>,
+    /// スペクトログラム描画用のテクスチャ（`spectrogram_cache`の変更時に破棄して作り直す）
+    spectrogram_texture: Option<egui::TextureHandle>,
+
+    // === エクスポート設定 ===
+    /// 出力信号の形式（複素IQ/実信号）- 表示とエクスポートの両方に適用
+    output_mode: OutputMode,
+    /// CSVエクスポートに時間（またはサンプル番号）の列を含めるかどうか
+    csv_time_column: bool,
+    /// WAVエクスポートのサンプル形式（32ビット浮動小数点/16ビット整数）
+    wav_format: hound::SampleFormat,
+    /// バイナリエクスポートのサンプル形式
+    bin_format: BinFormat,
+    /// バイナリエクスポートで振幅1.0に対応させる整数値（整数形式のみ）
+    bin_full_scale: f64,
+    /// バイナリエクスポート時にSigMFメタデータ（.sigmf-meta）も出力するかどうか
+    bin_sigmf_meta: bool,
+    /// TCPストリーミングで待ち受けるアドレス
+    stream_bind: String,
+    /// TCPストリーミングで待ち受けるポート番号
+    stream_port: u16,
+    /// 実行中のTCPストリーミング（停止中はNone）
+    streamer: Option<TcpStreamer>,
+    /// TCPストリーミングを開始できなかった場合のエラーメッセージ
+    stream_error: Option<String>,
+    /// 再生中の音声出力（停止中はNone）
+    #[cfg(feature = "audio")]
+    audio_player: Option<audio::AudioPlayer>,
+    /// 音声の再生を開始できなかった場合や停止した理由のメッセージ
+    #[cfg(feature = "audio")]
+    audio_error: Option<String>,
+}
+
+/// スペクトラム表示のスケール設定
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum SpectrumScale {
+    /// 線形スケール
+    Linear,
+    /// デシベル（dB）スケール
+    Decibel,
+}
+
+/// dB表示のノイズフロアのデフォルト値 (dB)
+const DEFAULT_DB_FLOOR: f64 = -120.0;
+
+impl SpectrumScale {
+    /// 正規化した振幅を表示スケールの値に変換（dBでは`floor`でクランプ）
+    fn apply(self, mag: f64, floor: f64) -> f64 {
+        match self {
+            SpectrumScale::Linear => mag,
+            SpectrumScale::Decibel => (20.0 * mag.log10()).max(floor),
+        }
+    }
+
+    /// ビンの電力を表示スケールの値に変換
+    ///
+    /// dBでは`floor`でクランプします。
+    /// `psd_factor`を指定した場合は電力をパワースペクトル密度 (/Hz) に換算して表示します
+    /// （dBでは-200dB/Hzでクランプ）。
+    fn apply_power(self, power: f64, psd_factor: Option<f64>, floor: f64) -> f64 {
+        match (psd_factor, self) {
+            (None, _) => self.apply(power.sqrt(), floor),
+            (Some(factor), SpectrumScale::Linear) => power * factor,
+            (Some(factor), SpectrumScale::Decibel) => (10.0 * (power * factor).log10()).max(-200.0),
+        }
+    }
+}
+
+/// dBスペクトラムの基準レベル
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+enum ReferenceLevel {
+    /// 振幅1.0のトーンを0dB（dBFS）とする絶対レベル
+    Absolute,
+    /// 最大のビンを0dBとする相対レベル
+    Peak,
+}
+
+/// 周波数領域プロット上のマーカー
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Marker {
+    /// マーカー位置のビンの周波数 (Hz)
+    freq: f64,
+    /// マーカー位置のビンの振幅（表示スケールの単位）
+    mag: f64,
+}
+
+/// スペクトラムの計算に使用する表示設定
+///
+/// ワーカースレッドでもUIスレッドと同じスペクトラムを計算できるよう、
+/// `MyApp`から計算に必要な設定だけを取り出したものです。
+#[derive(Clone, Copy, PartialEq)]
+struct SpectrumSettings {
+    /// サンプリングレート (Hz)
+    sample_rate: f64,
+    /// 窓関数
+    window_function: WindowFunction,
+    /// 表示スケール
+    scale: SpectrumScale,
+    /// 基準レベルとdB表示のノイズフロア
+    reference: (ReferenceLevel, f64),
+    /// FFT長を2のべき乗にゼロ詰めするかどうか
+    zero_pad: bool,
+    /// Welch法で平均化する場合のセグメント長と重なり
+    averaging: Option<(usize, usize)>,
+}
+
+impl SpectrumSettings {
+    /// 窓関数を適用したFFTで周波数領域プロットの点列を計算
+    ///
+    /// FFTプランはプランナーがFFT長ごとに保持して再利用します。
+    /// ゼロ詰めが有効な場合は、窓関数を掛けたサンプルの後ろに0を追加して
+    /// FFT長を2のべき乗に揃えます（時間領域のブロックやエクスポートには影響しません）。
+    ///
+    /// # 引数
+    /// * `samples` - 振幅適用済みのサンプル配列
+    /// * `planner` - FFTプランナー
+    ///
+    /// # 戻り値
+    /// 周波数順に並べた `[周波数, 振幅]` の点列と、各ビンの線形電力
+    /// （基準レベルがピークの場合は最大のビンを1に正規化した電力）
+    fn compute(
+        &self,
+        samples: &[Complex<f64>],
+        planner: &mut FftPlanner<f64>,
+    ) -> (Vec<[f64; 2]>, Vec<f64>) {
+        let num_samples = samples.len();
+        let (freqs, mut power) = self.compute_power(samples, planner);
+
+        // 基準レベルがピークの場合は最大のビンが0dB（線形では1）になるよう正規化
+        if self.reference.0 == ReferenceLevel::Peak {
+            let peak = power.iter().cloned().fold(0.0, f64::max);
+            if peak > 0.0 {
+                power.iter_mut().for_each(|p| *p /= peak);
+            }
+        }
+
+        // スケール変換（線形またはdB、PSD表示では/Hzに換算）
+        let psd_factor = self.display_psd_factor(num_samples);
+        let fft_points = freqs
+            .iter()
+            .zip(&power)
+            .map(|(&freq, &p)| {
+                [
+                    freq,
+                    self.scale.apply_power(p, psd_factor, self.reference.1),
+                ]
+            })
+            .collect();
+        (fft_points, power)
+    }
+
+    /// 窓関数を適用したFFT（またはWelch法）で各ビンの周波数と線形電力を計算
+    ///
+    /// # 引数
+    /// * `samples` - 振幅適用済みのサンプル配列
+    /// * `planner` - FFTプランナー
+    ///
+    /// # 戻り値
+    /// 周波数順に並べた各ビンの周波数 (Hz) と電力
+    fn compute_power(
+        &self,
+        samples: &[Complex<f64>],
+        planner: &mut FftPlanner<f64>,
+    ) -> (Vec<f64>, Vec<f64>) {
+        let num_samples = samples.len();
+
+        // Welch法で平均化したPSD（ゼロ詰めはせず、セグメント長がFFT長になる）
+        if let (Some(psd_factor), Some((seg_len, overlap))) =
+            (self.psd_factor(num_samples), self.averaging)
+        {
+            let psd = welch_psd(samples, seg_len, overlap, self.window_function);
+            let freqs = (0..seg_len)
+                .map(|i| (i as f64 - seg_len as f64 / 2.0) * self.sample_rate / seg_len as f64)
+                .collect();
+            // 単一FFTと同じくトーンの電力がそのまま読めるビンの電力に換算
+            let power = psd
+                .iter()
+                .map(|density| density / (psd_factor * self.sample_rate))
+                .collect();
+            return (freqs, power);
+        }
+
+        let fft_len = if self.zero_pad {
+            num_samples.next_power_of_two()
+        } else {
+            num_samples
+        };
+
+        // 窓関数を適用したコピーに対してFFTを実行してスペクトラムを計算
+        let coeffs = window(self.window_function, num_samples);
+        let mut spectrum: Vec<Complex<f64>> =
+            samples.iter().zip(&coeffs).map(|(s, w)| s * w).collect();
+        spectrum.resize(fft_len, Complex::new(0.0, 0.0));
+        planner.plan_fft_forward(fft_len).process(&mut spectrum);
+
+        // 窓関数のコヒーレントゲイン（元のサンプル数分の係数の総和）で正規化し、
+        // ゼロ詰めの有無にかかわらずトーンの振幅を保つ
+        let window_sum: f64 = coeffs.iter().sum();
+
+        // FFT結果をシフトして周波数軸を中心に配置
+        let mut freqs: Vec<f64> = Vec::with_capacity(fft_len);
+        let mut power: Vec<f64> = Vec::with_capacity(fft_len);
+        for i in 0..fft_len {
+            let idx = (i + fft_len / 2) % fft_len;
+
+            // 周波数を計算（負の周波数を含む）
+            freqs.push((i as f64 - fft_len as f64 / 2.0) * self.sample_rate / fft_len as f64);
+
+            // 振幅を計算して正規化
+            let mag = spectrum[idx].norm() / window_sum;
+            power.push(mag * mag);
+        }
+        (freqs, power)
+    }
+
+    /// 表示に使用するPSD (/Hz) への換算係数
+    ///
+    /// 基準レベルがピークの場合はピークに対する相対値で表示するため換算しません。
+    fn display_psd_factor(&self, num_samples: usize) -> Option<f64> {
+        self.psd_factor(num_samples)
+            .filter(|_| self.reference.0 == ReferenceLevel::Absolute)
+    }
+
+    /// Welch法で平均化する場合の、ビンの電力からPSD (/Hz) への換算係数
+    ///
+    /// 窓関数のコヒーレントゲインで正規化したビンの電力を、窓関数の電力と
+    /// サンプリングレートで正規化した密度に換算します。
+    ///
+    /// # 引数
+    /// * `num_samples` - 解析するサンプル数
+    ///
+    /// # 戻り値
+    /// 換算係数。平均化しない場合やセグメントが1つも取れない場合はNone
+    fn psd_factor(&self, num_samples: usize) -> Option<f64> {
+        let (seg_len, overlap) = self.averaging?;
+        if overlap >= seg_len || num_samples < seg_len {
+            return None;
+        }
+        let coeffs = window(self.window_function, seg_len);
+        let coherent = coeffs.iter().sum::<f64>().powi(2);
+        let incoherent: f64 = coeffs.iter().map(|w| w * w).sum();
+        Some(coherent / (incoherent * self.sample_rate))
+    }
+}
+
+/// 周波数領域プロットに表示する測定値の設定
+#[derive(Clone, PartialEq, Debug)]
+struct MeasurementSettings {
+    /// 占有帯域幅に含める電力の割合 (0.0〜1.0)
+    obw_fraction: f64,
+    /// ACPRの主チャネルの中心周波数 (Hz)
+    channel_center: f64,
+    /// ACPRのチャネル帯域幅 (Hz)
+    channel_bw: f64,
+    /// ACPRの主チャネルと隣接チャネルの中心周波数の差 (Hz)
+    channel_offset: f64,
+    /// NPRを測定する (残したトーン, ノッチ内のトーン) の周波数 (Hz)（測定しない場合はNone）
+    npr_tones: Option<(Vec<f64>, Vec<f64>)>,
+}
+
+/// 周波数領域プロットに表示する測定値
+#[derive(Clone, Debug)]
+struct Measurements {
+    /// 占有帯域幅の (下端, 上端, 幅) (Hz)
+    obw: (f64, f64, f64),
+    /// THD（比）とSINAD (dB)（DCを除く最大ピークを基本波とする）
+    distortion: (f64, f64),
+    /// SFDR (dB) と最悪スプリアスのビン
+    sfdr: (f64, usize),
+    /// ACPR (下側, 上側) (dBc)（搬送波周波数を主チャネルの中心とする）
+    acpr: Option<(f64, f64)>,
+    /// NPR (dB)
+    npr: Option<f64>,
+}
+
+impl Measurements {
+    /// 各ビンの電力から測定値を計算
+    ///
+    /// # 引数
+    /// * `power` - 周波数順に並べた（fftshift済みの）各ビンの電力
+    /// * `spectrum` - 電力の計算に使用した設定（サンプリングレートとNPRの下限に使用）
+    /// * `settings` - 測定値の設定
+    fn compute(power: &[f64], spectrum: &SpectrumSettings, settings: &MeasurementSettings) -> Self {
+        let sample_rate = spectrum.sample_rate;
+        let distortion = fundamental_bin(power).map_or((0.0, f64::NEG_INFINITY), |fundamental| {
+            (
+                thd(power, fundamental, DEFAULT_THD_HARMONICS),
+                sinad(power, fundamental),
+            )
+        });
+        Self {
+            obw: occupied_bandwidth(power, sample_rate, settings.obw_fraction),
+            distortion,
+            sfdr: sfdr(power),
+            acpr: acpr(
+                power,
+                sample_rate,
+                settings.channel_center,
+                settings.channel_bw,
+                settings.channel_offset,
+            ),
+            npr: settings.npr_tones.as_ref().and_then(|(kept, notched)| {
+                npr(power, sample_rate, kept, notched, spectrum.reference.1)
+            }),
+        }
+    }
+}
+
+/// 周波数領域プロットの計算結果のキャッシュ
+///
+/// FFTは窓関数と表示スケールにも依存するため、計算に使用した設定とともに保持します。
+struct SpectrumCache {
+    /// 計算に使用した設定
+    settings: SpectrumSettings,
+    /// プロット用の点列 `[周波数, 振幅]`
+    points: Arc<Vec<[f64; 2]>>,
+    /// 周波数順に並べた各ビンの電力（線形、帯域幅・ACPRの測定に使用）
+    power: Vec<f64>,
+    /// 測定値の設定と、その設定で計算した測定値
+    measurements: (MeasurementSettings, Measurements),
+    /// 最大値保持のトレースに反映済みかどうか
+    max_hold_applied: bool,
+}
+
+impl SpectrumCache {
+    /// スペクトラムと測定値を計算
+    ///
+    /// # 引数
+    /// * `samples` - 振幅適用済みのサンプル配列
+    /// * `settings` - スペクトラムの設定
+    /// * `measurement` - 測定値の設定
+    /// * `planner` - FFTプランナー
+    fn compute(
+        samples: &[Complex<f64>],
+        settings: SpectrumSettings,
+        measurement: MeasurementSettings,
+        planner: &mut FftPlanner<f64>,
+    ) -> Self {
+        let (points, power) = settings.compute(samples, planner);
+        let measurements = Measurements::compute(&power, &settings, &measurement);
+        Self {
+            settings,
+            points: Arc::new(points),
+            power,
+            measurements: (measurement, measurements),
+            max_hold_applied: false,
+        }
+    }
+
+    /// 測定値の設定が変わった場合のみ測定値を計算し直す
+    fn update_measurements(&mut self, measurement: &MeasurementSettings) {
+        if self.measurements.0 != *measurement {
+            let measurements = Measurements::compute(&self.power, &self.settings, measurement);
+            self.measurements = (measurement.clone(), measurements);
+        }
+    }
+}
+
+/// スペクトログラムの計算に使用する設定
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct SpectrogramSettings {
+    /// 短時間FFTのサイズ
+    size: usize,
+    /// フレーム間の重なりサンプル数
+    overlap: usize,
+    /// 窓関数
+    window_function: WindowFunction,
+    /// 色を割り当てる範囲の下限 (dB)
+    db_floor: f64,
+}
+
+/// スペクトログラムの画像を計算
+///
+/// 短時間FFTの振幅をdBに変換し、ノイズフロア〜0dBを色に割り当てます。
+/// 横方向が時間（フレーム）、縦方向が周波数で、画像の上端が最も高い周波数になります。
+///
+/// # 戻り値
+/// スペクトログラムの画像。フレームが1つも取れない場合はNone
+fn spectrogram_image(
+    samples: &[Complex<f64>],
+    settings: &SpectrogramSettings,
+) -> Option<egui::ColorImage> {
+    let frames = stft(
+        samples,
+        settings.size,
+        settings.overlap,
+        settings.window_function,
+    );
+    if frames.is_empty() {
+        return None;
+    }
+    let width = frames.len();
+    let height = settings.size;
+    let mut pixels = Vec::with_capacity(width * height);
+    for row in 0..height {
+        // 画像の上端が最も高い周波数になるように行を反転
+        let bin = height - 1 - row;
+        for frame in &frames {
+            let db = (20.0 * frame[bin].log10()).max(settings.db_floor);
+            pixels.push(colormap(1.0 - db / settings.db_floor));
+        }
+    }
+    Some(egui::ColorImage {
+        size: [width, height],
+        pixels,
+    })
+}
+
+/// 出力信号の形式（複素IQ/実信号）
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+enum OutputMode {
+    /// I/Qの2成分を持つ複素ベースバンド信号
+    Complex,
+    /// I成分のみの実信号（スペクトラムはエルミート対称になる）
+    Real,
+}
+
+impl OutputMode {
+    /// 1サンプルあたりの値（チャンネル）の数を返す
+    fn channels(self) -> usize {
+        match self {
+            OutputMode::Complex => 2,
+            OutputMode::Real => 1,
+        }
+    }
+}
+
+/// バイナリエクスポートのサンプル形式
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum BinFormat {
+    /// 32ビット浮動小数点（I/Q各4バイト）
+    F32,
+    /// 64ビット浮動小数点（I/Q各8バイト）
+    F64,
+    /// 16ビット符号付き整数（I/Q各2バイト）
+    I16,
+    /// 8ビット符号付き整数（I/Q各1バイト）
+    I8,
+}
+
+impl BinFormat {
+    /// I/Qの各成分1つあたりのバイト数
+    fn bytes_per_value(self) -> usize {
+        match self {
+            BinFormat::F32 => 4,
+            BinFormat::F64 => 8,
+            BinFormat::I16 => 2,
+            BinFormat::I8 => 1,
+        }
+    }
+
+    /// 整数形式で表現できる最大値（浮動小数点形式では1.0）
+    fn max_value(self) -> f64 {
+        match self {
+            BinFormat::F32 | BinFormat::F64 => 1.0,
+            BinFormat::I16 => i16::MAX as f64,
+            BinFormat::I8 => i8::MAX as f64,
+        }
+    }
+
+    /// SigMFの`core:datatype`表記（複素リトルエンディアン）
+    fn sigmf_datatype(self) -> &'static str {
+        match self {
+            BinFormat::F32 => "cf32_le",
+            BinFormat::F64 => "cf64_le",
+            BinFormat::I16 => "ci16_le",
+            BinFormat::I8 => "ci8",
+        }
+    }
+}
+
+/// 振幅の表示・入力単位
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Copy)]
+enum AmplitudeUnit {
+    /// 線形（振幅1.0がフルスケール）
+    Linear,
+    /// フルスケール（振幅1.0）を0dBとするdB
+    Dbfs,
+    /// 負荷（デフォルト50Ω）に供給する電力 (dBm) - 振幅1.0を1V（ピーク）とみなす
+    Dbm,
+}
+
+/// dBm換算で想定する負荷インピーダンスのデフォルト値 (Ω)
+const DEFAULT_IMPEDANCE: f64 = 50.0;
+
+/// チャネルの遅延タップ数の上限
+const MAX_CHANNEL_TAPS: usize = 8;
+
+/// dB単位で表示する振幅の下限（振幅0の表示に使用）
+const MIN_AMPLITUDE_DB: f64 = -200.0;
+
+/// dBmの電力を線形の振幅に変換
+///
+/// 振幅を搬送波のピーク電圧 (V) とみなし、インピーダンス`z`の負荷に供給する
+/// 正弦波の電力 P = V²/(2R) から換算します（50Ωでは10dBmで振幅1.0）。
+///
+/// # 引数
+/// * `dbm` - 電力 (dBm)
+/// * `z` - 負荷インピーダンス (Ω)
+///
+/// # 戻り値
+/// 線形の振幅（ピーク電圧）
+fn dbm_to_amplitude(dbm: f64, z: f64) -> f64 {
+    let watts = 1e-3 * 10.0_f64.powf(dbm / 10.0);
+    (2.0 * z * watts).sqrt()
+}
+
+/// 線形の振幅をdBmの電力に変換（`dbm_to_amplitude`の逆変換）
+///
+/// 振幅0は`MIN_AMPLITUDE_DB`として表します。
+fn amplitude_to_dbm(amplitude: f64, z: f64) -> f64 {
+    let watts = amplitude * amplitude / (2.0 * z);
+    (10.0 * (watts / 1e-3).log10()).max(MIN_AMPLITUDE_DB)
+}
+
+/// 指定した単位の値を線形の振幅に変換
+///
+/// # 引数
+/// * `value` - 指定した単位での値
+/// * `unit` - 値の単位
+/// * `impedance` - dBm換算で想定する負荷インピーダンス (Ω)
+///
+/// # 戻り値
+/// 線形の振幅
+fn linear_from_unit(value: f64, unit: AmplitudeUnit, impedance: f64) -> f64 {
+    match unit {
+        AmplitudeUnit::Linear => value,
+        AmplitudeUnit::Dbfs => 10.0_f64.powf(value / 20.0),
+        AmplitudeUnit::Dbm => dbm_to_amplitude(value, impedance),
+    }
+}
+
+/// 線形の振幅を指定した単位の値に変換（`linear_from_unit`の逆変換）
+///
+/// dB単位では振幅0を`MIN_AMPLITUDE_DB`として表します。
+fn unit_from_linear(amplitude: f64, unit: AmplitudeUnit, impedance: f64) -> f64 {
+    match unit {
+        AmplitudeUnit::Linear => amplitude,
+        AmplitudeUnit::Dbfs => (20.0 * amplitude.log10()).max(MIN_AMPLITUDE_DB),
+        AmplitudeUnit::Dbm => amplitude_to_dbm(amplitude, impedance),
+    }
+}
+
+/// 時間領域プロットの表示形式
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Copy)]
+enum TimeView {
+    /// I/Q成分の時間波形
+    Waveform,
+    /// I/Q平面上の散布図
+    Constellation,
+    /// 搬送波の回転を取り除いた振幅・位相の軌跡（ベクトルスコープ）
+    Polar,
+}
+
+/// 時間軸の単位設定
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Copy)]
+enum TimeDomainUnit {
+    /// 秒単位で表示
+    Seconds,
+    /// サンプル数単位で表示
+    Samples,
+}
+
+/// アプリケーションパラメータの保存/復元用構造体
+///
+/// すべてのユーザー設定可能なパラメータを含み、JSON形式でシリアライズ可能です。
+/// ファイルに存在しないフィールドはデフォルト値で補い、未知のフィールドは無視します。
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct AppParams {
+    frequency: f64,
+    initial_phase_deg: f64,
+    freq_step: f64,
+    amplitude: f64,
+    amplitude_unit: AmplitudeUnit,
+    impedance: f64,
+    sample_rate: f64,
+    num_samples: usize,
+    add_noise: bool,
+    snr_db: f64,
+    iq_gain_imbalance_db: f64,
+    iq_phase_error_deg: f64,
+    dc_offset_i: f64,
+    dc_offset_q: f64,
+    phase_noise_rms_deg: f64,
+    phase_noise_offset_hz: f64,
+    amplitude_profile: AmplitudeProfile,
+    burst_enabled: bool,
+    burst: BurstConfig,
+    channel_enabled: bool,
+    channel: ChannelConfig,
+    ofdm: OfdmConfig,
+    quantization_enabled: bool,
+    quantization_bits: u8,
+    clip_enabled: bool,
+    clip_level: f64,
+    clip_model: SaturationModel,
+    clip_gain: f64,
+    saleh_enabled: bool,
+    saleh: SalehParams,
+    spectrum_scale: SpectrumScale,
+    window_function: WindowFunction,
+    zero_pad_fft: bool,
+    welch_averaging: bool,
+    welch_segment_len: usize,
+    welch_overlap: usize,
+    max_hold: bool,
+    log_freq: bool,
+    reference_level: ReferenceLevel,
+    db_floor: f64,
+    obw_percent: f64,
+    acp_channel_bw: f64,
+    acp_channel_offset: f64,
+    mod_type: ModulationType,
+    am_mod_freq: f64,
+    am_mod_index: f64,
+    am_waveform: AmWaveform,
+    fm_mod_freq: f64,
+    fm_deviation: f64,
+    pm_mod_index: f64,
+    ssb_sideband: Sideband,
+    pulse_freq: f64,
+    pulse_duty_cycle: f64,
+    pulse_edge_time: f64,
+    multitone_count: usize,
+    multitone_spacing: f64,
+    multitone_phase: MultitonePhase,
+    multitone_spec: MultitoneSpec,
+    multitone_amplitude_window: WindowFunction,
+    notch_start_tone: usize,
+    notch_width_tones: usize,
+    seed: u64,
+    tone_spacing: f64,
+    symbol_rate: f64,
+    prbs_order: PrbsOrder,
+    ask_low_level: f64,
+    psk_order: usize,
+    fsk_deviation: f64,
+    fsk_gaussian: bool,
+    fsk_bt: f64,
+    pulse_shaping: bool,
+    rrc_rolloff: f64,
+    rrc_span: usize,
+    chirp_bandwidth: f64,
+    chirp_duration: f64,
+    sweep_start: f64,
+    sweep_stop: f64,
+    sweep_time: f64,
+    sweep_mode: SweepMode,
+    sweep_dwell: usize,
+    hop_set: HopSet,
+    hop_bandwidth: f64,
+    hop_step: f64,
+    hop_dwell: f64,
+    hop_reset_phase: bool,
+    time_domain_unit: TimeDomainUnit,
+    show_time_domain: bool,
+    time_view: TimeView,
+    constellation_trajectory: bool,
+    show_freq_domain: bool,
+    show_spectrogram: bool,
+    stft_size: usize,
+    stft_overlap: usize,
+    output_mode: OutputMode,
+}
+
+impl Default for AppParams {
+    fn default() -> Self {
+        Self::from_app(&MyApp::default())
+    }
+}
+
+impl AppParams {
+    /// MyAppからパラメータを抽出してAppParamsを生成
+    ///
+    /// # 引数
+    /// * `app` - 現在のアプリケーション状態
+    ///
+    /// # 戻り値
+    /// シリアライズ可能なパラメータ構造体
+    fn from_app(app: &MyApp) -> Self {
+        Self {
+            frequency: app.frequency,
+            initial_phase_deg: app.initial_phase_deg,
+            freq_step: app.freq_step,
+            amplitude: app.amplitude,
+            amplitude_unit: app.amplitude_unit,
+            impedance: app.impedance,
+            sample_rate: app.sample_rate,
+            num_samples: app.num_samples,
+            add_noise: app.add_noise,
+            snr_db: app.snr_db,
+            iq_gain_imbalance_db: app.iq_gain_imbalance_db,
+            iq_phase_error_deg: app.iq_phase_error_deg,
+            dc_offset_i: app.dc_offset_i,
+            dc_offset_q: app.dc_offset_q,
+            phase_noise_rms_deg: app.phase_noise_rms_deg,
+            phase_noise_offset_hz: app.phase_noise_offset_hz,
+            amplitude_profile: app.amplitude_profile,
+            burst_enabled: app.burst_enabled,
+            burst: app.burst,
+            ofdm: app.ofdm,
+            channel_enabled: app.channel_enabled,
+            channel: app.channel.clone(),
+            quantization_enabled: app.quantization_enabled,
+            quantization_bits: app.quantization_bits,
+            clip_enabled: app.clip_enabled,
+            clip_level: app.clip_level,
+            clip_model: app.clip_model,
+            clip_gain: app.clip_gain,
+            saleh_enabled: app.saleh_enabled,
+            saleh: app.saleh,
+            spectrum_scale: match app.spectrum_scale {
+                SpectrumScale::Linear => SpectrumScale::Linear,
+                SpectrumScale::Decibel => SpectrumScale::Decibel,
+            },
+            window_function: app.window_function,
+            zero_pad_fft: app.zero_pad_fft,
+            welch_averaging: app.welch_averaging,
+            welch_segment_len: app.welch_segment_len,
+            welch_overlap: app.welch_overlap,
+            max_hold: app.max_hold,
+            log_freq: app.log_freq,
+            reference_level: app.reference_level,
+            db_floor: app.db_floor,
+            obw_percent: app.obw_percent,
+            acp_channel_bw: app.acp_channel_bw,
+            acp_channel_offset: app.acp_channel_offset,
+            mod_type: app.mod_type,
+            am_mod_freq: app.am_mod_freq,
+            am_mod_index: app.am_mod_index,
+            am_waveform: app.am_waveform,
+            fm_mod_freq: app.fm_mod_freq,
+            fm_deviation: app.fm_deviation,
+            pm_mod_index: app.pm_mod_index,
+            ssb_sideband: app.ssb_sideband,
+            pulse_freq: app.pulse_freq,
+            pulse_duty_cycle: app.pulse_duty_cycle,
+            pulse_edge_time: app.pulse_edge_time,
+            multitone_count: app.multitone_count,
+            multitone_spacing: app.multitone_spacing,
+            multitone_phase: app.multitone_phase,
+            multitone_spec: app.multitone_spec.clone(),
+            multitone_amplitude_window: app.multitone_amplitude_window,
+            notch_start_tone: app.notch_start_tone,
+            notch_width_tones: app.notch_width_tones,
+            seed: app.seed,
+            tone_spacing: app.tone_spacing,
+            symbol_rate: app.symbol_rate,
+            prbs_order: app.prbs_order,
+            ask_low_level: app.ask_low_level,
+            psk_order: app.psk_order,
+            fsk_deviation: app.fsk_deviation,
+            fsk_gaussian: app.fsk_gaussian,
+            fsk_bt: app.fsk_bt,
+            pulse_shaping: app.pulse_shaping,
+            rrc_rolloff: app.rrc_rolloff,
+            rrc_span: app.rrc_span,
+            chirp_bandwidth: app.chirp_bandwidth,
+            chirp_duration: app.chirp_duration,
+            sweep_start: app.sweep_start,
+            sweep_stop: app.sweep_stop,
+            sweep_time: app.sweep_time,
+            sweep_mode: app.sweep_mode,
+            sweep_dwell: app.sweep_dwell,
+            hop_set: app.hop_set.clone(),
+            hop_bandwidth: app.hop_bandwidth,
+            hop_step: app.hop_step,
+            hop_dwell: app.hop_dwell,
+            hop_reset_phase: app.hop_reset_phase,
+            time_domain_unit: match app.time_domain_unit {
+                TimeDomainUnit::Seconds => TimeDomainUnit::Seconds,
+                TimeDomainUnit::Samples => TimeDomainUnit::Samples,
+            },
+            show_time_domain: app.show_time_domain,
+            time_view: app.time_view,
+            constellation_trajectory: app.constellation_trajectory,
+            show_freq_domain: app.show_freq_domain,
+            show_spectrogram: app.show_spectrogram,
+            stft_size: app.stft_size,
+            stft_overlap: app.stft_overlap,
+            output_mode: app.output_mode,
+        }
+    }
+
+    /// 読み込んだパラメータの値域を検証
+    ///
+    /// 破損したファイルや手で編集したファイルから、アプリが不正な状態
+    /// （サンプリングレート0など）に陥らないよう、UIで設定可能な範囲と
+    /// 同じ制約を確認します。
+    ///
+    /// # 戻り値
+    /// すべて有効ならOk(())、そうでなければ最初に見つかった問題の説明
+    fn validate(&self) -> Result<(), String> {
+        let check = |ok: bool, name: &str| {
+            if ok {
+                Ok(())
+            } else {
+                Err(format!("invalid {}", name))
+            }
+        };
+        let finite = [
+            self.frequency,
+            self.amplitude,
+            self.freq_step,
+            self.initial_phase_deg,
+            self.impedance,
+            self.amplitude_profile.start_db,
+            self.amplitude_profile.stop_db,
+            self.sample_rate,
+            self.snr_db,
+            self.iq_gain_imbalance_db,
+            self.iq_phase_error_deg,
+            self.dc_offset_i,
+            self.dc_offset_q,
+            self.phase_noise_rms_deg,
+            self.phase_noise_offset_hz,
+            self.am_mod_freq,
+            self.am_mod_index,
+            self.fm_mod_freq,
+            self.fm_deviation,
+            self.pm_mod_index,
+            self.pulse_freq,
+            self.pulse_duty_cycle,
+            self.pulse_edge_time,
+            self.multitone_spacing,
+            self.tone_spacing,
+            self.symbol_rate,
+            self.ask_low_level,
+            self.fsk_deviation,
+            self.fsk_bt,
+            self.rrc_rolloff,
+            self.chirp_bandwidth,
+            self.chirp_duration,
+            self.sweep_start,
+            self.sweep_stop,
+            self.sweep_time,
+            self.hop_bandwidth,
+            self.hop_step,
+            self.hop_dwell,
+            self.acp_channel_bw,
+            self.acp_channel_offset,
+            self.clip_level,
+            self.clip_gain,
+            self.obw_percent,
+            self.db_floor,
+            self.saleh.alpha_a,
+            self.saleh.beta_a,
+            self.saleh.alpha_phi,
+            self.saleh.beta_phi,
+        ];
+        check(finite.iter().all(|v| v.is_finite()), "non-finite value")?;
+        check(self.frequency >= 0.0, "frequency")?;
+        check(self.freq_step > 0.0, "freq_step")?;
+        check(self.amplitude >= 0.0, "amplitude")?;
+        check(self.impedance > 0.0, "impedance")?;
+        check(self.phase_noise_rms_deg >= 0.0, "phase_noise_rms_deg")?;
+        check(self.phase_noise_offset_hz > 0.0, "phase_noise_offset_hz")?;
+        check(
+            self.obw_percent > 0.0 && self.obw_percent <= 100.0,
+            "obw_percent",
+        )?;
+        check((-300.0..=-10.0).contains(&self.db_floor), "db_floor")?;
+        check(
+            [self.window_function, self.multitone_amplitude_window]
+                .iter()
+                .all(|kind| match kind {
+                    WindowFunction::Tukey(ratio) => (0.0..=1.0).contains(ratio),
+                    _ => true,
+                }),
+            "tukey_ratio",
+        )?;
+        check(
+            self.burst.period_samples >= 1 && self.burst.on_samples <= self.burst.period_samples,
+            "burst",
+        )?;
+        check(
+            self.ofdm.num_subcarriers >= 2
+                && (1..=self.ofdm.num_subcarriers).contains(&self.ofdm.active_subcarriers)
+                && self.ofdm.cp_len <= self.ofdm.num_subcarriers
+                && matches!(self.ofdm.qam_order, 4 | 16),
+            "ofdm",
+        )?;
+        check(
+            self.amplitude_profile.ramp_samples >= 1
+                && self.amplitude_profile.steps >= 1
+                && self.amplitude_profile.dwell_samples >= 1,
+            "amplitude_profile",
+        )?;
+        check(
+            self.channel.doppler_hz.is_finite()
+                && self.channel.doppler_hz.abs() <= self.sample_rate / 2.0,
+            "channel.doppler_hz",
+        )?;
+        check(
+            (1..=MAX_CHANNEL_TAPS).contains(&self.channel.taps.len())
+                && self
+                    .channel
+                    .taps
+                    .iter()
+                    .all(|&(delay, gain)| delay <= 1_000_000 && gain.is_finite()),
+            "channel.taps",
+        )?;
+        check(
+            (1..=32).contains(&self.quantization_bits),
+            "quantization_bits",
+        )?;
+        check(self.clip_level > 0.0, "clip_level")?;
+        check(self.clip_gain > 0.0, "clip_gain")?;
+        check(
+            self.saleh.beta_a >= 0.0 && self.saleh.beta_phi >= 0.0,
+            "saleh",
+        )?;
+        check((1000.0..=1e9).contains(&self.sample_rate), "sample_rate")?;
+        check((1..=1_000_000).contains(&self.num_samples), "num_samples")?;
+        check(
+            (0.0..=1.0).contains(&self.pulse_duty_cycle),
+            "pulse_duty_cycle",
+        )?;
+        check(self.pulse_edge_time >= 0.0, "pulse_edge_time")?;
+        check((1..=100).contains(&self.multitone_count), "multitone_count")?;
+        if let MultitoneSpec::Explicit(tones) = &self.multitone_spec {
+            check((1..=100).contains(&tones.len()), "multitone_spec")?;
+            check(
+                tones
+                    .iter()
+                    .all(|t| t.freq.is_finite() && t.amplitude.is_finite() && t.amplitude >= 0.0),
+                "multitone_spec",
+            )?;
+        }
+        // シンボル整形フィルタ（RRC/ガウス）の係数が上限を超えない範囲
+        let span = shaping_filter_span(
+            self.mod_type,
+            self.pulse_shaping,
+            self.rrc_span,
+            self.fsk_gaussian,
+        );
+        check(
+            self.symbol_rate >= min_symbol_rate(self.sample_rate, span)
+                && self.symbol_rate <= self.sample_rate,
+            "symbol_rate",
+        )?;
+        check((0.0..=1.0).contains(&self.ask_low_level), "ask_low_level")?;
+        check([2, 4, 8].contains(&self.psk_order), "psk_order")?;
+        check(self.fsk_bt > 0.0, "fsk_bt")?;
+        check((0.0..=1.0).contains(&self.rrc_rolloff), "rrc_rolloff")?;
+        check((1..=64).contains(&self.rrc_span), "rrc_span")?;
+        check(self.chirp_duration > 0.0, "chirp_duration")?;
+        check(self.sweep_time > 0.0, "sweep_time")?;
+        check(self.sweep_dwell >= 1, "sweep_dwell")?;
+        check(self.hop_bandwidth >= 0.0, "hop_bandwidth")?;
+        check(self.hop_step > 0.0, "hop_step")?;
+        check(self.hop_dwell > 0.0, "hop_dwell")?;
+        if let HopSet::List(freqs) = &self.hop_set {
+            check(
+                !freqs.is_empty() && freqs.iter().all(|f| f.is_finite()),
+                "hop_set",
+            )?;
+        }
+        check((8..=8192).contains(&self.stft_size), "stft_size")?;
+        check(self.stft_overlap < self.stft_size, "stft_overlap")?;
+        check(
+            (16..=65536).contains(&self.welch_segment_len),
+            "welch_segment_len",
+        )?;
+        check(self.welch_overlap < self.welch_segment_len, "welch_overlap")?;
+        Ok(())
+    }
+
+    /// 保存されたパラメータをMyAppに適用
+    ///
+    /// # 引数
+    /// * `app` - パラメータを適用する対象のアプリケーション
+    fn apply_to_app(self, app: &mut MyApp) {
+        app.frequency = self.frequency;
+        app.initial_phase_deg = self.initial_phase_deg;
+        app.freq_step = self.freq_step;
+        app.amplitude = self.amplitude;
+        app.amplitude_unit = self.amplitude_unit;
+        app.impedance = self.impedance;
+        app.sample_rate = self.sample_rate;
+        app.num_samples = self.num_samples;
+        app.add_noise = self.add_noise;
+        app.snr_db = self.snr_db;
+        app.iq_gain_imbalance_db = self.iq_gain_imbalance_db;
+        app.iq_phase_error_deg = self.iq_phase_error_deg;
+        app.dc_offset_i = self.dc_offset_i;
+        app.dc_offset_q = self.dc_offset_q;
+        app.phase_noise_rms_deg = self.phase_noise_rms_deg;
+        app.phase_noise_offset_hz = self.phase_noise_offset_hz;
+        app.amplitude_profile = self.amplitude_profile;
+        app.burst_enabled = self.burst_enabled;
+        app.burst = self.burst;
+        app.ofdm = self.ofdm;
+        app.channel_enabled = self.channel_enabled;
+        app.channel = self.channel.clone();
+        app.quantization_enabled = self.quantization_enabled;
+        app.quantization_bits = self.quantization_bits;
+        app.clip_enabled = self.clip_enabled;
+        app.clip_level = self.clip_level;
+        app.clip_model = self.clip_model;
+        app.clip_gain = self.clip_gain;
+        app.saleh_enabled = self.saleh_enabled;
+        app.saleh = self.saleh;
+        app.spectrum_scale = match self.spectrum_scale {
+            SpectrumScale::Linear => SpectrumScale::Linear,
+            SpectrumScale::Decibel => SpectrumScale::Decibel,
+        };
+        app.window_function = self.window_function;
+        app.zero_pad_fft = self.zero_pad_fft;
+        app.welch_averaging = self.welch_averaging;
+        app.welch_segment_len = self.welch_segment_len;
+        app.welch_overlap = self.welch_overlap;
+        app.max_hold = self.max_hold;
+        app.log_freq = self.log_freq;
+        app.reference_level = self.reference_level;
+        app.db_floor = self.db_floor;
+        app.obw_percent = self.obw_percent;
+        app.acp_channel_bw = self.acp_channel_bw;
+        app.acp_channel_offset = self.acp_channel_offset;
+        app.mod_type = self.mod_type;
+        app.am_mod_freq = self.am_mod_freq;
+        app.am_mod_index = self.am_mod_index;
+        app.am_waveform = self.am_waveform;
+        app.fm_mod_freq = self.fm_mod_freq;
+        app.fm_deviation = self.fm_deviation;
+        app.pm_mod_index = self.pm_mod_index;
+        app.ssb_sideband = self.ssb_sideband;
+        app.pulse_freq = self.pulse_freq;
+        app.pulse_duty_cycle = self.pulse_duty_cycle;
+        app.pulse_edge_time = self.pulse_edge_time;
+        app.multitone_count = self.multitone_count;
+        app.multitone_spacing = self.multitone_spacing;
+        app.multitone_phase = self.multitone_phase;
+        app.multitone_spec = self.multitone_spec;
+        app.multitone_amplitude_window = self.multitone_amplitude_window;
+        app.notch_start_tone = self.notch_start_tone;
+        app.notch_width_tones = self.notch_width_tones;
+        app.seed = self.seed;
+        app.tone_spacing = self.tone_spacing;
+        app.symbol_rate = self.symbol_rate;
+        app.prbs_order = self.prbs_order;
+        app.ask_low_level = self.ask_low_level;
+        app.psk_order = self.psk_order;
+        app.fsk_deviation = self.fsk_deviation;
+        app.fsk_gaussian = self.fsk_gaussian;
+        app.fsk_bt = self.fsk_bt;
+        app.pulse_shaping = self.pulse_shaping;
+        app.rrc_rolloff = self.rrc_rolloff;
+        app.rrc_span = self.rrc_span;
+        app.chirp_bandwidth = self.chirp_bandwidth;
+        app.chirp_duration = self.chirp_duration;
+        app.sweep_start = self.sweep_start;
+        app.sweep_stop = self.sweep_stop;
+        app.sweep_time = self.sweep_time;
+        app.sweep_mode = self.sweep_mode;
+        app.sweep_dwell = self.sweep_dwell;
+        app.hop_set = self.hop_set.clone();
+        app.hop_bandwidth = self.hop_bandwidth;
+        app.hop_step = self.hop_step;
+        app.hop_dwell = self.hop_dwell;
+        app.hop_reset_phase = self.hop_reset_phase;
+        app.time_domain_unit = match self.time_domain_unit {
+            TimeDomainUnit::Seconds => TimeDomainUnit::Seconds,
+            TimeDomainUnit::Samples => TimeDomainUnit::Samples,
+        };
+        app.show_time_domain = self.show_time_domain;
+        app.time_view = self.time_view;
+        app.constellation_trajectory = self.constellation_trajectory;
+        app.show_freq_domain = self.show_freq_domain;
+        app.show_spectrogram = self.show_spectrogram;
+        app.stft_size = self.stft_size;
+        app.stft_overlap = self.stft_overlap;
+        app.output_mode = self.output_mode;
+    }
+}
+
+impl Default for MyApp {
+    /// MyAppのデフォルト値を設定
+    ///
+    /// 起動時のデフォルトパラメータ：
+    /// - 搬送波周波数: 1kHz
+    /// - 振幅: 1.0
+    /// - サンプリングレート: 100kHz
+    /// - サンプル数: 1000
+    /// - 変調方式: CW（無変調）
+    fn default() -> Self {
+        Self {
+            frequency: 1000.0,
+            initial_phase_deg: 0.0,
+            freq_step: 10.0,
+            amplitude: 1.0,
+            amplitude_unit: AmplitudeUnit::Linear,
+            impedance: DEFAULT_IMPEDANCE,
+            sample_rate: 100000.0,
+            fft_planner: FftPlanner::new(),
+            worker: None,
+            last_params: None,
+            last_num_samples: 0,
+            generating: false,
+            raw_samples: Vec::new(),
+            samples: Arc::new(Vec::new()),
+            samples_scaling: None,
+            spectrum_cache: None,
+            spectrogram_cache: None,
+            multitone_papr_cache: None,
+            recomputed: false,
+            next_block_requested: false,
+            imported: None,
+            import_format: BinFormat::F32,
+            import_error: None,
+            num_samples: 1000,
+            add_noise: false,
+            snr_db: 20.0,
+            iq_gain_imbalance_db: 0.0,
+            iq_phase_error_deg: 0.0,
+            dc_offset_i: 0.0,
+            dc_offset_q: 0.0,
+            phase_noise_rms_deg: 0.0,
+            phase_noise_offset_hz: 1000.0,
+            amplitude_profile: AmplitudeProfile::default(),
+            burst_enabled: false,
+            burst: BurstConfig::default(),
+            ofdm: OfdmConfig::default(),
+            channel_enabled: false,
+            channel: ChannelConfig::default(),
+            quantization_enabled: false,
+            quantization_bits: 12,
+            clip_enabled: false,
+            clip_level: 0.8,
+            clip_model: SaturationModel::Hard,
+            clip_gain: 1.0,
+            saleh_enabled: false,
+            saleh: SalehParams::default(),
+            spectrum_scale: SpectrumScale::Decibel,
+            window_function: WindowFunction::Rectangular,
+            zero_pad_fft: false,
+            welch_averaging: false,
+            welch_segment_len: 1024,
+            welch_overlap: 512,
+            max_hold: false,
+            log_freq: false,
+            reference_level: ReferenceLevel::Absolute,
+            db_floor: DEFAULT_DB_FLOOR,
+            max_hold_trace: Vec::new(),
+            reference_trace: Vec::new(),
+            reference_scale: (SpectrumScale::Decibel, false),
+            markers: Vec::new(),
+            acp_channel_bw: 1000.0,
+            acp_channel_offset: 2000.0,
+            obw_percent: 99.0,
+            mod_type: ModulationType::CW,
+            am_mod_freq: 100.0,
+            am_mod_index: 0.5,
+            am_waveform: AmWaveform::Sine,
+            fm_mod_freq: 100.0,
+            fm_deviation: 1000.0,
+            pm_mod_index: 1.0,
+            ssb_sideband: Sideband::Upper,
+            pulse_freq: 1000.0,
+            pulse_duty_cycle: 0.5,
+            pulse_edge_time: 0.0,
+            multitone_count: 10,
+            multitone_spacing: 1000.0,
+            multitone_phase: MultitonePhase::Random,
+            multitone_spec: MultitoneSpec::Uniform,
+            multitone_amplitude_window: WindowFunction::Rectangular,
+            notch_start_tone: 0,
+            notch_width_tones: 0,
+            seed: 0,
+            tone_spacing: 1000.0,
+            symbol_rate: 1000.0,
+            prbs_order: PrbsOrder::Prbs9,
+            ask_low_level: 0.0,
+            psk_order: 4,
+            fsk_deviation: 1000.0,
+            fsk_gaussian: false,
+            fsk_bt: 0.5,
+            pulse_shaping: false,
+            rrc_rolloff: 0.35,
+            rrc_span: 8,
+            chirp_bandwidth: 10000.0,
+            chirp_duration: 0.01,
+            sweep_start: 1000.0,
+            sweep_stop: 10000.0,
+            sweep_time: 0.01,
+            sweep_mode: SweepMode::Continuous,
+            sweep_dwell: 100,
+            hop_set: HopSet::Band,
+            hop_bandwidth: 10000.0,
+            hop_step: 1000.0,
+            hop_dwell: 0.001,
+            hop_reset_phase: false,
+            time_domain_unit: TimeDomainUnit::Seconds,
+            show_time_domain: true,
+            time_view: TimeView::Waveform,
+            constellation_trajectory: false,
+            show_freq_domain: true,
+            show_spectrogram: false,
+            stft_size: 128,
+            stft_overlap: 64,
+            last_time_domain_unit: TimeDomainUnit::Seconds,
+            last_plot_bounds: None,
+            forced_plot_bounds: None,
+            spectrogram_texture: None,
+            output_mode: OutputMode::Complex,
+            csv_time_column: false,
+            wav_format: hound::SampleFormat::Float,
+            bin_format: BinFormat::F32,
+            bin_full_scale: BinFormat::I16.max_value(),
+            bin_sigmf_meta: false,
+            stream_bind: DEFAULT_STREAM_BIND.to_string(),
+            stream_port: 1234,
+            streamer: None,
+            stream_error: None,
+            #[cfg(feature = "audio")]
+            audio_player: None,
+            #[cfg(feature = "audio")]
+            audio_error: None,
+        }
+    }
+}
+
+impl MyApp {
+    /// 前回終了時のパラメータを復元してアプリケーションを作成
+    ///
+    /// FFTプランやキャッシュなど保存しない状態はデフォルト値から作り直します。
+    fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        Self::from_storage(cc.storage)
+    }
+
+    /// 保存領域のパラメータを適用したアプリケーションを作成
+    ///
+    /// 保存されたパラメータがない場合や不正な場合はデフォルト値を使用します。
+    ///
+    /// # 引数
+    /// * `storage` - eframeの保存領域（永続化が使えない場合はNone）
+    fn from_storage(storage: Option<&dyn eframe::Storage>) -> Self {
+        let mut app = Self::default();
+        if let Some(params) =
+            storage.and_then(|storage| eframe::get_value::<AppParams>(storage, APP_PARAMS_KEY))
+        {
+            match params.validate() {
+                Ok(()) => params.apply_to_app(&mut app),
+                Err(e) => eprintln!("Ignored saved parameters: {}", e),
+            }
+        }
+        app
+    }
+
+    /// スペクトラムの計算に使用する現在の表示設定
+    fn spectrum_settings(&self) -> SpectrumSettings {
+        SpectrumSettings {
+            sample_rate: self.sample_rate,
+            window_function: self.window_function,
+            scale: self.spectrum_scale,
+            reference: (self.reference_level, self.db_floor),
+            zero_pad: self.zero_pad_fft,
+            averaging: self
+                .welch_averaging
+                .then_some((self.welch_segment_len, self.welch_overlap)),
+        }
+    }
+
+    /// 現在の表示設定でスペクトラムを計算（`SpectrumSettings::compute`を参照）
+    fn compute_spectrum(&mut self, samples: &[Complex<f64>]) -> (Vec<[f64; 2]>, Vec<f64>) {
+        self.spectrum_settings()
+            .compute(samples, &mut self.fft_planner)
+    }
+
+    /// 現在の表示設定でのPSD (/Hz) への換算係数（`SpectrumSettings::display_psd_factor`を参照）
+    fn display_psd_factor(&self, num_samples: usize) -> Option<f64> {
+        self.spectrum_settings().display_psd_factor(num_samples)
+    }
+
+    /// 周波数領域プロットに表示する測定値の現在の設定
+    fn measurement_settings(&self) -> MeasurementSettings {
+        // NPRはノッチを設定したマルチトーンのみ、ノッチ内と残したトーンの周波数で測定
+        let npr_tones = (self.mod_type == ModulationType::Multitone && self.notch_width_tones > 0)
+            .then(|| {
+                let freqs: Vec<f64> = self
+                    .signal_params()
+                    .multitone_tones()
+                    .iter()
+                    .map(|tone| tone.freq)
+                    .collect();
+                let start = self.notch_start_tone.min(freqs.len());
+                let end = (start + self.notch_width_tones).min(freqs.len());
+                let kept: Vec<f64> = freqs[..start]
+                    .iter()
+                    .chain(&freqs[end..])
+                    .copied()
+                    .collect();
+                (kept, freqs[start..end].to_vec())
+            });
+        MeasurementSettings {
+            obw_fraction: self.obw_percent / 100.0,
+            channel_center: self.frequency,
+            channel_bw: self.acp_channel_bw,
+            channel_offset: self.acp_channel_offset,
+            npr_tones,
+        }
+    }
+
+    /// スペクトログラムの計算に使用する現在の表示設定
+    fn spectrogram_settings(&self) -> SpectrogramSettings {
+        SpectrogramSettings {
+            size: self.stft_size,
+            overlap: self.stft_overlap.min(self.stft_size - 1),
+            window_function: self.window_function,
+            db_floor: self.db_floor,
+        }
+    }
+
+    /// ワーカーで生成したブロックに対して行う、現在の設定での振幅の適用と解析
+    fn block_analysis(&self) -> BlockAnalysis {
+        BlockAnalysis {
+            scaling: (self.amplitude, self.output_mode, self.output_impairments()),
+            spectrum: self
+                .show_freq_domain
+                .then(|| (self.spectrum_settings(), self.measurement_settings())),
+            spectrogram: self.show_spectrogram.then(|| self.spectrogram_settings()),
+        }
+    }
+
+    /// 音声再生の操作欄を表示
+    ///
+    /// 再生中は信号の設定をプレーヤーに反映し、サンプリングレートが変わった場合は再生を止めます。
+    /// サンプリングレートが再生できる範囲外の場合は再生ボタンを無効にします。
+    #[cfg(feature = "audio")]
+    fn audio_ui(&mut self, ui: &mut egui::Ui, settings: StreamSettings) {
+        let sample_rate = settings.0.sample_rate;
+        if let Some(player) = &self.audio_player {
+            if player.sample_rate != sample_rate {
+                self.audio_player = None;
+                self.audio_error = Some("Playback stopped: sample rate changed".to_string());
+            }
+        }
+        ui.horizontal(|ui| {
+            if let Some(player) = &self.audio_player {
+                player.update(settings);
+                ui.label(format!("Playing audio at {} Hz", player.sample_rate));
+                if ui.button("Stop").clicked() {
+                    self.audio_player = None;
+                }
+                return;
+            }
+            let playable = audio::AUDIO_SAMPLE_RATE_RANGE.contains(&sample_rate);
+            if ui
+                .add_enabled(playable, egui::Button::new("Play"))
+                .clicked()
+            {
+                match audio::AudioPlayer::start(settings) {
+                    Ok(player) => {
+                        self.audio_player = Some(player);
+                        self.audio_error = None;
+                    }
+                    Err(e) => self.audio_error = Some(e),
+                }
+            }
+            if !playable {
+                ui.colored_label(
+                    egui::Color32::YELLOW,
+                    format!(
+                        "Sample rate must be {}–{} Hz to play audio",
+                        audio::AUDIO_SAMPLE_RATE_RANGE.start(),
+                        audio::AUDIO_SAMPLE_RATE_RANGE.end()
+                    ),
+                );
+            } else if let Some(error) = &self.audio_error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        });
+    }
+
+    /// 現在のマルチトーン設定でのゼロ位相とSchroeder位相のPAPRを計算
+    ///
+    /// 初期位相設定以外のパラメータが変わったときのみ再計算します。
+    /// 計算に使うブロック長は最大65536サンプルに制限します。
+    ///
+    /// # 戻り値
+    /// (ゼロ位相のPAPR, Schroeder位相のPAPR) (dB)
+    fn multitone_papr_comparison(&mut self) -> (f64, f64) {
+        let key = SignalParams {
+            multitone_phase: MultitonePhase::Zero,
+            ..self.signal_params()
+        };
+        if let Some((cached_key, result)) = &self.multitone_papr_cache {
+            if *cached_key == key {
+                return *result;
+            }
+        }
+
+        let len = self.num_samples.min(65536);
+        let papr = |phase: MultitonePhase| {
+            let params = SignalParams {
+                multitone_phase: phase,
+                ..key.clone()
+            };
+            papr_db(&SignalGenerator::new().generate_block(&params, len))
+        };
+        let result = (papr(MultitonePhase::Zero), papr(MultitonePhase::Schroeder));
+        self.multitone_papr_cache = Some((key, result));
+        result
+    }
+
+    /// 現在のUI設定から出力段の不完全性を構築
+    fn output_impairments(&self) -> OutputImpairments {
+        OutputImpairments {
+            quantization_bits: self.quantization_enabled.then_some(self.quantization_bits),
+            clip: self
+                .clip_enabled
+                .then_some((self.clip_model, self.clip_level, self.clip_gain)),
+            saleh: self.saleh_enabled.then_some(self.saleh),
+        }
+    }
+
+    /// 現在のUI設定から信号生成パラメータを構築
+    ///
+    /// 変調タイプに応じて、変調周波数と変調強度に対応するフィールドを選択します。
+    ///
+    /// # 戻り値
+    /// 信号生成パラメータ
+    fn signal_params(&self) -> SignalParams {
+        // 変調タイプに応じて変調パラメータを設定
+        let (mod_freq, mod_strength) = match self.mod_type {
+            ModulationType::CW => (0.0, 0.0),
+            ModulationType::AM => (self.am_mod_freq, self.am_mod_index),
+            ModulationType::FM => (self.fm_mod_freq, self.fm_deviation),
+            ModulationType::PM => (self.am_mod_freq, self.pm_mod_index),
+            ModulationType::SSB => (self.am_mod_freq, 0.0),
+            ModulationType::Pulse => (self.pulse_freq, self.pulse_duty_cycle),
+            ModulationType::Multitone
+            | ModulationType::TwoTone
+            | ModulationType::BPSK
+            | ModulationType::QPSK
+            | ModulationType::QAM16
+            | ModulationType::ASK
+            | ModulationType::FSK
+            | ModulationType::MSK
+            | ModulationType::PSK
+            | ModulationType::Chirp
+            | ModulationType::Sweep
+            | ModulationType::Hopping
+            | ModulationType::OFDM
+            | ModulationType::Noise => (0.0, 0.0),
+        };
+
+        // 信号生成パラメータを構築
+        SignalParams {
+            frequency: self.frequency,
+            initial_phase_deg: self.initial_phase_deg,
+            sample_rate: self.sample_rate,
+            mod_type: self.mod_type,
+            mod_freq,
+            mod_strength,
+            am_waveform: self.am_waveform,
+            multitone_count: self.multitone_count,
+            multitone_spacing: self.multitone_spacing,
+            multitone_phase: self.multitone_phase,
+            multitone_spec: self.multitone_spec.clone(),
+            multitone_amplitude_window: self.multitone_amplitude_window,
+            notch_start_tone: self.notch_start_tone,
+            notch_width_tones: self.notch_width_tones,
+            tone_spacing: self.tone_spacing,
+            seed: self.seed,
+            ssb_sideband: self.ssb_sideband,
+            symbol_rate: self.symbol_rate,
+            prbs_order: self.prbs_order,
+            ask_low_level: self.ask_low_level,
+            pulse_edge_time: self.pulse_edge_time,
+            psk_order: self.psk_order,
+            fsk_deviation: self.fsk_deviation,
+            fsk_gaussian: self.fsk_gaussian,
+            fsk_bt: self.fsk_bt,
+            pulse_shaping: self.pulse_shaping,
+            rrc_rolloff: self.rrc_rolloff,
+            rrc_span: self.rrc_span,
+            chirp_bandwidth: self.chirp_bandwidth,
+            chirp_duration: self.chirp_duration,
+            sweep_start: self.sweep_start,
+            sweep_stop: self.sweep_stop,
+            sweep_time: self.sweep_time,
+            sweep_mode: self.sweep_mode,
+            sweep_dwell: self.sweep_dwell,
+            hop_set: self.hop_set.clone(),
+            hop_bandwidth: self.hop_bandwidth,
+            hop_step: self.hop_step,
+            hop_dwell: self.hop_dwell,
+            hop_reset_phase: self.hop_reset_phase,
+            add_noise: self.add_noise,
+            snr_db: self.snr_db,
+            iq_gain_imbalance_db: self.iq_gain_imbalance_db,
+            iq_phase_error_deg: self.iq_phase_error_deg,
+            dc_offset_i: self.dc_offset_i,
+            dc_offset_q: self.dc_offset_q,
+            phase_noise_rms_deg: self.phase_noise_rms_deg,
+            amplitude_profile: self.amplitude_profile,
+            burst_enabled: self.burst_enabled,
+            burst: self.burst,
+            ofdm: self.ofdm,
+            channel_enabled: self.channel_enabled,
+            channel: self.channel.clone(),
+        }
+    }
+}
+
+impl eframe::App for MyApp {
+    /// 終了時などに現在のパラメータを保存（次回起動時に復元）
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, APP_PARAMS_KEY, &AppParams::from_app(self));
+    }
+
+    /// アプリケーションのUIを更新
+    ///
+    /// フレームごとに呼び出され、UI描画と状態更新を行います。
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // === トップパネル：制御UI ===
+        egui::TopBottomPanel::top("controls_panel").show(ctx, |ui| {
+            // パラメータの保存/復元ボタン
+            ui.horizontal(|ui| {
+                // パラメータ保存
+                if ui.button("Save Parameters").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("JSON", &["json"])
+                        .set_file_name("params.json")
+                        .save_file()
+                    {
+                        let params = AppParams::from_app(self);
+                        if let Ok(json) = serde_json::to_string_pretty(&params) {
+                            if let Err(e) = std::fs::write(&path, json) {
+                                eprintln!("Failed to save parameters: {}", e);
+                            }
+                        }
+                    }
+                }
+                // パラメータ復元
+                if ui.button("Recall Parameters").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("JSON", &["json"])
+                        .pick_file()
+                    {
+                        if let Ok(json) = std::fs::read_to_string(&path) {
+                            if let Ok(params) = serde_json::from_str::<AppParams>(&json) {
+                                match params.validate() {
+                                    Ok(()) => params.apply_to_app(self),
+                                    Err(e) => eprintln!("Rejected parameters file: {}", e),
+                                }
+                            } else {
+                                eprintln!("Failed to parse parameters");
+                            }
+                        } else {
+                            eprintln!("Failed to read parameters file");
+                        }
+                    }
+                }
+                ui.separator();
+
+                // 記録済みのCSV/BINファイルを読み込んで解析
+                if ui.button("Import").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("CSV / Binary", &["csv", "bin"])
+                        .pick_file()
+                    {
+                        let is_bin = path
+                            .extension()
+                            .is_some_and(|ext| ext.eq_ignore_ascii_case("bin"));
+                        let result = if is_bin {
+                            import_bin(&path, self.import_format)
+                        } else {
+                            import_csv(&path)
+                        };
+                        match result {
+                            Ok(data) => {
+                                self.num_samples = data.len();
+                                self.raw_samples = data;
+                                self.samples_scaling = None;
+                                self.imported = Some(
+                                    path.file_name()
+                                        .map(|n| n.to_string_lossy().into_owned())
+                                        .unwrap_or_default(),
+                                );
+                                self.import_error = None;
+                            }
+                            Err(e) => self.import_error = Some(e),
+                        }
+                    }
+                }
+                ui.label("BIN Format:");
+                egui::ComboBox::new("import_format", "")
+                    .selected_text(format!("{:?}", self.import_format))
+                    .show_ui(ui, |ui| {
+                        for format in [
+                            BinFormat::F32,
+                            BinFormat::F64,
+                            BinFormat::I16,
+                            BinFormat::I8,
+                        ] {
+                            ui.selectable_value(
+                                &mut self.import_format,
+                                format,
+                                format!("{:?}", format),
+                            );
+                        }
+                    });
+                if let Some(name) = &self.imported {
+                    ui.label(format!(
+                        "Imported: {} ({} samples)",
+                        name,
+                        self.raw_samples.len()
+                    ));
+                    // 読み込んだデータの周波数軸・時間軸に使用するサンプリングレート
+                    ui.label("Sample Rate (Hz):");
+                    ui.add(
+                        freq_drag_value(&mut self.sample_rate, self.freq_step)
+                            .range(1000.0..=1000000000.0),
+                    );
+                    if ui.button("Back to Generator").clicked() {
+                        self.imported = None;
+                        self.last_params = None;
+                    }
+                }
+                if let Some(e) = &self.import_error {
+                    ui.colored_label(egui::Color32::RED, format!("Import failed: {}", e));
+                }
+            });
+            ui.separator();
+
+            // 読み込んだデータの表示中は生成用のパラメータを無効化
+            if self.imported.is_some() {
+                ui.disable();
+            }
+
+            // === 共通パラメータセクション ===
+            ui.heading("Common Parameters");
+
+            // 周波数設定
+            ui.horizontal(|ui| {
+                ui.label("Frequency (Hz):");
+                // 周波数掃引では開始/終了周波数を使用するため搬送波周波数は無効
+                // クリックして「1.024M」のように直接入力でき、矢印キーではStep刻みで変化する
+                ui.add_enabled_ui(self.mod_type != ModulationType::Sweep, |ui| {
+                    ui.add(
+                        freq_drag_value(&mut self.frequency, self.freq_step)
+                            .range(0.0..=10000000000.0),
+                    );
+                    // 桁を素早く変える
+                    if ui.small_button("×10").clicked() {
+                        self.frequency = (self.frequency * 10.0).min(10000000000.0);
+                    }
+                    if ui.small_button("÷10").clicked() {
+                        self.frequency /= 10.0;
+                    }
+                });
+                ui.label("Step:");
+                ui.add(
+                    egui::DragValue::new(&mut self.freq_step)
+                        .speed(1.0)
+                        .range(0.001..=1e9)
+                        .custom_parser(parse_si_value),
+                )
+                .on_hover_text("Arrow-key and drag step for frequency fields (Hz)");
+                ui.separator();
+                // 複数回の生成・エクスポートで位相をそろえるための開始位相
+                ui.label("Initial Phase:");
+                ui.add(
+                    egui::DragValue::new(&mut self.initial_phase_deg)
+                        .speed(1.0)
+                        .range(-360.0..=360.0)
+                        .suffix("°"),
+                );
+            });
+
+            // 振幅設定（線形の値を保持し、選択した単位に換算して表示・入力する）
+            ui.horizontal(|ui| {
+                ui.label("Amplitude:");
+                let mut value =
+                    unit_from_linear(self.amplitude, self.amplitude_unit, self.impedance);
+                let drag = match self.amplitude_unit {
+                    AmplitudeUnit::Linear => egui::DragValue::new(&mut value)
+                        .speed(0.01)
+                        .range(0.0..=1000000.0),
+                    AmplitudeUnit::Dbfs => egui::DragValue::new(&mut value)
+                        .speed(0.1)
+                        .range(MIN_AMPLITUDE_DB..=120.0)
+                        .suffix(" dBFS"),
+                    AmplitudeUnit::Dbm => egui::DragValue::new(&mut value)
+                        .speed(0.1)
+                        .range(MIN_AMPLITUDE_DB..=130.0)
+                        .suffix(" dBm"),
+                };
+                if ui.add(drag).changed() {
+                    self.amplitude = linear_from_unit(value, self.amplitude_unit, self.impedance);
+                }
+                egui::ComboBox::new("amplitude_unit", "")
+                    .selected_text(match self.amplitude_unit {
+                        AmplitudeUnit::Linear => "Linear",
+                        AmplitudeUnit::Dbfs => "dBFS",
+                        AmplitudeUnit::Dbm => "dBm",
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut self.amplitude_unit,
+                            AmplitudeUnit::Linear,
+                            "Linear",
+                        );
+                        ui.selectable_value(&mut self.amplitude_unit, AmplitudeUnit::Dbfs, "dBFS");
+                        ui.selectable_value(&mut self.amplitude_unit, AmplitudeUnit::Dbm, "dBm");
+                    });
+                // dBmでは基準インピーダンスを選択し、もう一方の単位での値を併記する
+                if self.amplitude_unit == AmplitudeUnit::Dbm {
+                    ui.label("into");
+                    ui.add(
+                        egui::DragValue::new(&mut self.impedance)
+                            .speed(1.0)
+                            .range(1e-3..=1e6)
+                            .suffix(" Ω"),
+                    );
+                    ui.label(format!("= {:.4} (linear)", self.amplitude));
+                } else {
+                    ui.label(format!(
+                        "= {:.2} dBm ({} Ω)",
+                        amplitude_to_dbm(self.amplitude, self.impedance),
+                        self.impedance
+                    ));
+                }
+            });
+
+            // サンプリングレート設定
+            ui.horizontal(|ui| {
+                ui.label("Sample Rate (Hz):");
+                ui.add(
+                    freq_drag_value(&mut self.sample_rate, self.freq_step)
+                        .range(1000.0..=1000000000.0),
+                );
+            });
+
+            // サンプル数設定
+            ui.horizontal(|ui| {
+                ui.label("Num Samples:");
+                ui.add(
+                    egui::DragValue::new(&mut self.num_samples)
+                        .speed(10.0)
+                        .range(1..=1000000),
+                );
+                // 位相を保持したまま、表示中のブロックに続くサンプルを生成
+                if ui
+                    .button("Next Block")
+                    .on_hover_text("Continue generation from the end of the current block")
+                    .clicked()
+                {
+                    self.next_block_requested = true;
+                }
+            });
+
+            // 雑音付加設定
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.add_noise, "Add Noise");
+                ui.add_enabled_ui(self.add_noise, |ui| {
+                    ui.label("SNR (dB):");
+                    ui.add(
+                        egui::DragValue::new(&mut self.snr_db)
+                            .speed(0.1)
+                            .range(-50.0..=200.0),
+                    );
+                });
+            });
+
+            // 送信機の不完全性（インペアメント）設定
+            ui.group(|ui| {
+                ui.label("Impairments");
+                ui.horizontal(|ui| {
+                    ui.label("IQ Gain Imbalance (dB):");
+                    ui.add(
+                        egui::DragValue::new(&mut self.iq_gain_imbalance_db)
+                            .speed(0.01)
+                            .range(-6.0..=6.0),
+                    );
+                    ui.label("IQ Phase Error (deg):");
+                    ui.add(
+                        egui::DragValue::new(&mut self.iq_phase_error_deg)
+                            .speed(0.1)
+                            .range(-45.0..=45.0),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.label("DC Offset I:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.dc_offset_i)
+                            .speed(0.001)
+                            .range(-1.0..=1.0),
+                    );
+                    ui.label("DC Offset Q:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.dc_offset_q)
+                            .speed(0.001)
+                            .range(-1.0..=1.0),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Phase Noise (deg RMS/sample):");
+                    ui.add(
+                        egui::DragValue::new(&mut self.phase_noise_rms_deg)
+                            .speed(0.01)
+                            .range(0.0..=30.0),
+                    );
+                    // 同じ位相雑音をオフセット周波数でのレベル (dBc/Hz) として表示・入力
+                    ui.label("=");
+                    let mut level = phase_noise_dbc_from_rms(
+                        self.phase_noise_rms_deg,
+                        self.phase_noise_offset_hz,
+                        self.sample_rate,
+                    )
+                    .max(-200.0);
+                    let drag = egui::DragValue::new(&mut level)
+                        .speed(0.5)
+                        .range(-200.0..=0.0)
+                        .suffix(" dBc/Hz");
+                    if ui.add(drag).changed() {
+                        self.phase_noise_rms_deg = phase_noise_rms_from_dbc(
+                            level,
+                            self.phase_noise_offset_hz,
+                            self.sample_rate,
+                        )
+                        .min(30.0);
+                    }
+                    ui.label("@");
+                    ui.add(
+                        egui::DragValue::new(&mut self.phase_noise_offset_hz)
+                            .speed(10.0)
+                            .range(1.0..=self.sample_rate / 2.0)
+                            .suffix(" Hz"),
+                    );
+                });
+            });
+
+            // 振幅プロファイル - AGCの追従・整定の測定用に、変調方式によらず振幅を時間変化させる
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Amplitude Profile:");
+                    let profile = &mut self.amplitude_profile;
+                    for (kind, label) in [
+                        (AmplitudeProfileKind::Constant, "Constant"),
+                        (AmplitudeProfileKind::Ramp, "Ramp"),
+                        (AmplitudeProfileKind::Staircase, "Staircase"),
+                    ] {
+                        ui.selectable_value(&mut profile.kind, kind, label);
+                    }
+                    if profile.kind == AmplitudeProfileKind::Constant {
+                        return;
+                    }
+                    ui.separator();
+                    ui.label("Start:");
+                    ui.add(
+                        egui::DragValue::new(&mut profile.start_db)
+                            .speed(0.5)
+                            .range(-200.0..=20.0)
+                            .suffix(" dB"),
+                    );
+                    ui.label("Stop:");
+                    ui.add(
+                        egui::DragValue::new(&mut profile.stop_db)
+                            .speed(0.5)
+                            .range(-200.0..=20.0)
+                            .suffix(" dB"),
+                    );
+                    if profile.kind == AmplitudeProfileKind::Ramp {
+                        ui.label("Length (samples):");
+                        ui.add(
+                            egui::DragValue::new(&mut profile.ramp_samples).range(1..=100_000_000),
+                        );
+                    } else {
+                        ui.label("Steps:");
+                        ui.add(egui::DragValue::new(&mut profile.steps).range(1..=1000));
+                        ui.label("Dwell (samples):");
+                        ui.add(
+                            egui::DragValue::new(&mut profile.dwell_samples).range(1..=100_000_000),
+                        );
+                    }
+                });
+            });
+
+            // バースト（ゲーティング）設定 - 変調方式によらず変調後の信号に適用
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.burst_enabled, "Enable Burst");
+                if self.burst_enabled {
+                    ui.label("Period (samples):");
+                    ui.add(
+                        egui::DragValue::new(&mut self.burst.period_samples).range(1..=1_000_000),
+                    );
+                    ui.label("On (samples):");
+                    ui.add(
+                        egui::DragValue::new(&mut self.burst.on_samples)
+                            .range(0..=self.burst.period_samples),
+                    );
+                    ui.label("Ramp (samples):");
+                    ui.add(
+                        egui::DragValue::new(&mut self.burst.ramp_samples)
+                            .range(0..=self.burst.period_samples),
+                    );
+                }
+            });
+
+            // 伝搬路（チャネル）設定 - マルチパスの遅延タップとドップラーシフト
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.channel_enabled, "Channel");
+                    ui.add_enabled_ui(self.channel_enabled, |ui| {
+                        ui.label("Doppler (Hz):");
+                        ui.add(
+                            egui::DragValue::new(&mut self.channel.doppler_hz)
+                                .speed(1.0)
+                                .range(-self.sample_rate / 2.0..=self.sample_rate / 2.0),
+                        );
+                    });
+                });
+                if self.channel_enabled {
+                    let taps = &mut self.channel.taps;
+                    let mut removed = None;
+                    egui::Grid::new("channel_taps").show(ui, |ui| {
+                        ui.label("Delay (samples)");
+                        ui.label("Gain");
+                        ui.end_row();
+                        let removable = taps.len() > 1;
+                        for (k, (delay, gain)) in taps.iter_mut().enumerate() {
+                            ui.add(egui::DragValue::new(delay).range(0..=1_000_000));
+                            ui.add(egui::DragValue::new(gain).speed(0.01).range(-10.0..=10.0));
+                            if ui.add_enabled(removable, egui::Button::new("−")).clicked() {
+                                removed = Some(k);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                    if let Some(k) = removed {
+                        taps.remove(k);
+                    }
+                    if ui
+                        .add_enabled(taps.len() < MAX_CHANNEL_TAPS, egui::Button::new("Add Tap"))
+                        .clicked()
+                    {
+                        let delay = taps.last().map_or(0, |&(delay, _)| delay + 1);
+                        taps.push((delay, 0.5));
+                    }
+                }
+            });
+
+            // DACの量子化設定 - 振幅適用後のI/Qをフルスケール±1で量子化
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.quantization_enabled, "Quantize");
+                ui.add_enabled_ui(self.quantization_enabled, |ui| {
+                    ui.label("Bits:");
+                    ui.add(egui::DragValue::new(&mut self.quantization_bits).range(4..=16));
+                });
+                ui.separator();
+                // 電力増幅器の飽和 - 位相を保ったまま振幅を制限し、帯域外にスペクトル再成長を生じさせる
+                ui.checkbox(&mut self.clip_enabled, "Clip");
+                ui.add_enabled_ui(self.clip_enabled, |ui| {
+                    ui.radio_value(&mut self.clip_model, SaturationModel::Hard, "Hard");
+                    ui.radio_value(&mut self.clip_model, SaturationModel::Tanh, "Tanh");
+                    ui.label("Level:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.clip_level)
+                            .speed(0.01)
+                            .range(0.01..=10.0),
+                    );
+                    ui.add_enabled_ui(self.clip_model == SaturationModel::Tanh, |ui| {
+                        ui.label("Gain:");
+                        ui.add(
+                            egui::DragValue::new(&mut self.clip_gain)
+                                .speed(0.01)
+                                .range(0.01..=100.0),
+                        );
+                    });
+                });
+            });
+
+            // 増幅器モデル（Salehモデル：AM/AM・AM/PMによる緩やかな飽和）
+            ui.group(|ui| {
+                ui.label("Amplifier Model");
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.saleh_enabled, "Saleh");
+                    ui.add_enabled_ui(self.saleh_enabled, |ui| {
+                        for (label, value) in [
+                            ("αa:", &mut self.saleh.alpha_a),
+                            ("βa:", &mut self.saleh.beta_a),
+                            ("αφ:", &mut self.saleh.alpha_phi),
+                            ("βφ:", &mut self.saleh.beta_phi),
+                        ] {
+                            ui.label(label);
+                            ui.add(egui::DragValue::new(value).speed(0.01).range(0.0..=100.0));
+                        }
+                        if ui.button("Reset").clicked() {
+                            self.saleh = SalehParams::default();
+                        }
+                    });
+                });
+            });
+
+            // 表示切替チェックボックス
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.show_time_domain, "Show Time Domain");
+                ui.checkbox(&mut self.show_freq_domain, "Show Freq Domain");
+                ui.checkbox(&mut self.show_spectrogram, "Show Spectrogram");
+                ui.separator();
+                let mut real = self.output_mode == OutputMode::Real;
+                if ui
+                    .checkbox(&mut real, "Real output")
+                    .on_hover_text("Plot and export only the real (I) part of the signal")
+                    .changed()
+                {
+                    self.output_mode = if real {
+                        OutputMode::Real
+                    } else {
+                        OutputMode::Complex
+                    };
+                }
+            });
+
+            ui.separator();
+
+            // === 変調設定セクション ===
+            ui.heading("Modulation");
+
+            // 変調タイプ選択
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Type:");
+                ui.radio_value(&mut self.mod_type, ModulationType::CW, "CW");
+                ui.radio_value(&mut self.mod_type, ModulationType::AM, "AM");
+                ui.radio_value(&mut self.mod_type, ModulationType::FM, "FM");
+                ui.radio_value(&mut self.mod_type, ModulationType::PM, "PM");
+                ui.radio_value(&mut self.mod_type, ModulationType::SSB, "SSB");
+                ui.radio_value(&mut self.mod_type, ModulationType::Pulse, "Pulse");
+                ui.radio_value(&mut self.mod_type, ModulationType::Multitone, "Multitone");
+                ui.radio_value(&mut self.mod_type, ModulationType::TwoTone, "Two-Tone");
+                ui.radio_value(&mut self.mod_type, ModulationType::BPSK, "BPSK");
+                ui.radio_value(&mut self.mod_type, ModulationType::QPSK, "QPSK");
+                ui.radio_value(&mut self.mod_type, ModulationType::PSK, "M-PSK");
+                ui.radio_value(&mut self.mod_type, ModulationType::QAM16, "16QAM");
+                ui.radio_value(&mut self.mod_type, ModulationType::ASK, "ASK");
+                ui.radio_value(&mut self.mod_type, ModulationType::FSK, "FSK");
+                ui.radio_value(&mut self.mod_type, ModulationType::MSK, "MSK");
+                ui.radio_value(&mut self.mod_type, ModulationType::Chirp, "Chirp");
+                ui.radio_value(&mut self.mod_type, ModulationType::Sweep, "Sweep");
+                ui.radio_value(&mut self.mod_type, ModulationType::Hopping, "Hopping");
+                ui.radio_value(&mut self.mod_type, ModulationType::OFDM, "OFDM");
+                ui.radio_value(&mut self.mod_type, ModulationType::Noise, "Noise");
             });
 
             // 変調タイプ別のパラメータ設定
-            if self.mod_type != ModulationType::CW {
-                if self.mod_type == ModulationType::Multitone {
+            match self.mod_type {
+                ModulationType::CW => {}
+                ModulationType::Multitone => {
                     // マルチトーン固有のパラメータ
                     ui.horizontal(|ui| {
-                        ui.label("Count:");
-                        ui.add(egui::DragValue::new(&mut self.multitone_count).range(1..=100));
+                        ui.label("Placement:");
+                        let uniform = self.multitone_spec == MultitoneSpec::Uniform;
+                        if ui.selectable_label(uniform, "Uniform").clicked() {
+                            self.multitone_spec = MultitoneSpec::Uniform;
+                        }
+                        // 任意配置に切り替えたときは現在の等間隔配置のトーン（ノッチ・窓の適用前）から始める
+                        if ui.selectable_label(!uniform, "Explicit").clicked() && uniform {
+                            let params = SignalParams {
+                                notch_width_tones: 0,
+                                multitone_amplitude_window: WindowFunction::Rectangular,
+                                ..self.signal_params()
+                            };
+                            self.multitone_spec = MultitoneSpec::Explicit(params.multitone_tones());
+                        }
+                    });
+                    let spacing = self.multitone_spacing;
+                    if let MultitoneSpec::Explicit(tones) = &mut self.multitone_spec {
+                        // トーンごとの周波数と振幅の編集テーブル
+                        let mut removed = None;
+                        egui::Grid::new("multitone_tones").show(ui, |ui| {
+                            ui.label("Freq (Hz)");
+                            ui.label("Amplitude");
+                            ui.end_row();
+                            let removable = tones.len() > 1;
+                            for (k, tone) in tones.iter_mut().enumerate() {
+                                ui.add(egui::DragValue::new(&mut tone.freq).speed(10.0));
+                                ui.add(
+                                    egui::DragValue::new(&mut tone.amplitude)
+                                        .speed(0.01)
+                                        .range(0.0..=1.0),
+                                );
+                                if ui.add_enabled(removable, egui::Button::new("−")).clicked() {
+                                    removed = Some(k);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                        if let Some(k) = removed {
+                            tones.remove(k);
+                        }
+                        if ui
+                            .add_enabled(tones.len() < 100, egui::Button::new("Add Tone"))
+                            .clicked()
+                        {
+                            let freq = tones.last().map_or(0.0, |t| t.freq + spacing);
+                            tones.push(Tone {
+                                freq,
+                                amplitude: 1.0,
+                            });
+                        }
+                    } else {
+                        ui.horizontal(|ui| {
+                            ui.label("Count:");
+                            ui.add(egui::DragValue::new(&mut self.multitone_count).range(1..=100));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Spacing (Hz):");
+                            ui.add(egui::DragValue::new(&mut self.multitone_spacing).speed(10.0));
+                        });
+                    }
+                    // NPR測定用ノッチ（連続したトーンを取り除く）
+                    ui.horizontal(|ui| {
+                        let count = self.signal_params().multitone_tone_count();
+                        ui.label("Notch Start:");
+                        ui.add(
+                            egui::DragValue::new(&mut self.notch_start_tone)
+                                .range(0..=count.saturating_sub(1)),
+                        );
+                        ui.label("Notch Width:");
+                        ui.add(egui::DragValue::new(&mut self.notch_width_tones).range(0..=count));
+                    });
+                    // トーン配列全体にわたる振幅のテーパー（合成スペクトラムの包絡線を整形）
+                    ui.horizontal(|ui| {
+                        ui.label("Amplitude Window:");
+                        window_function_combo(
+                            ui,
+                            "multitone_amplitude_window",
+                            &mut self.multitone_amplitude_window,
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Initial Phase:");
+                        egui::ComboBox::new("multitone_phase", "")
+                            .selected_text(format!("{:?}", self.multitone_phase))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(
+                                    &mut self.multitone_phase,
+                                    MultitonePhase::Zero,
+                                    "Zero",
+                                );
+                                ui.selectable_value(
+                                    &mut self.multitone_phase,
+                                    MultitonePhase::Random,
+                                    "Random",
+                                );
+                                ui.selectable_value(
+                                    &mut self.multitone_phase,
+                                    MultitonePhase::Schroeder,
+                                    "Schroeder",
+                                );
+                                ui.selectable_value(
+                                    &mut self.multitone_phase,
+                                    MultitonePhase::Newman,
+                                    "Newman",
+                                );
+                            });
+                    });
+                    if self.multitone_phase == MultitonePhase::Random {
+                        ui.horizontal(|ui| {
+                            ui.label("Seed:");
+                            ui.add(egui::DragValue::new(&mut self.seed));
+                        });
+                    }
+                    // 同じトーン構成でのゼロ位相とSchroeder位相のPAPRを比較表示
+                    let (zero, schroeder) = self.multitone_papr_comparison();
+                    ui.label(format!(
+                        "PAPR  Zero: {:.2} dB   Schroeder: {:.2} dB",
+                        zero, schroeder
+                    ));
+                }
+                ModulationType::BPSK
+                | ModulationType::QPSK
+                | ModulationType::QAM16
+                | ModulationType::ASK
+                | ModulationType::FSK
+                | ModulationType::MSK
+                | ModulationType::PSK => {
+                    // デジタル変調固有のパラメータ
+                    // シンボル整形フィルタの係数が上限を超えないよう、シンボルレートの下限を決める
+                    let span = shaping_filter_span(
+                        self.mod_type,
+                        self.pulse_shaping,
+                        self.rrc_span,
+                        self.fsk_gaussian,
+                    );
+                    let min_rate = min_symbol_rate(self.sample_rate, span);
+                    ui.horizontal(|ui| {
+                        ui.label("Symbol Rate (sym/s):");
+                        ui.add(
+                            egui::DragValue::new(&mut self.symbol_rate)
+                                .speed(10.0)
+                                .range(min_rate..=self.sample_rate),
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("PRBS:");
+                        egui::ComboBox::new("prbs_order", "")
+                            .selected_text(format!("{:?}", self.prbs_order))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(
+                                    &mut self.prbs_order,
+                                    PrbsOrder::Prbs9,
+                                    "Prbs9",
+                                );
+                                ui.selectable_value(
+                                    &mut self.prbs_order,
+                                    PrbsOrder::Prbs15,
+                                    "Prbs15",
+                                );
+                            });
+                        ui.label("Seed:");
+                        ui.add(egui::DragValue::new(&mut self.seed));
+                    });
+                    if self.mod_type == ModulationType::ASK {
+                        ui.horizontal(|ui| {
+                            ui.label("Low Level (0-1):");
+                            ui.add(
+                                egui::DragValue::new(&mut self.ask_low_level)
+                                    .speed(0.01)
+                                    .range(0.0..=1.0),
+                            );
+                        });
+                    }
+                    if self.mod_type == ModulationType::PSK {
+                        ui.horizontal(|ui| {
+                            ui.label("Order:");
+                            for order in [2, 4, 8] {
+                                ui.radio_value(&mut self.psk_order, order, order.to_string());
+                            }
+                        });
+                    }
+                    if matches!(self.mod_type, ModulationType::FSK | ModulationType::MSK) {
+                        ui.horizontal(|ui| {
+                            if self.mod_type == ModulationType::MSK {
+                                // MSKの偏移はシンボルレートで決まる（変調指数0.5）
+                                ui.label(format!(
+                                    "Deviation: {:.1} Hz (h = 0.5)",
+                                    self.symbol_rate / 4.0
+                                ));
+                                ui.checkbox(&mut self.fsk_gaussian, "Gaussian (GMSK)");
+                            } else {
+                                ui.label("Deviation (Hz):");
+                                ui.add(
+                                    egui::DragValue::new(&mut self.fsk_deviation)
+                                        .speed(10.0)
+                                        .range(0.0..=self.sample_rate / 2.0),
+                                );
+                                ui.checkbox(&mut self.fsk_gaussian, "Gaussian (GFSK)");
+                            }
+                            ui.add_enabled_ui(self.fsk_gaussian, |ui| {
+                                ui.label("BT:");
+                                ui.add(
+                                    egui::DragValue::new(&mut self.fsk_bt)
+                                        .speed(0.01)
+                                        .range(0.1..=2.0),
+                                );
+                            });
+                        });
+                    } else {
+                        // 矩形ホールドの代わりにRRCフィルタでシンボルを整形（帯域を制限）
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.pulse_shaping, "RRC Pulse Shaping");
+                            ui.add_enabled_ui(self.pulse_shaping, |ui| {
+                                ui.label("Roll-off (α):");
+                                ui.add(
+                                    egui::DragValue::new(&mut self.rrc_rolloff)
+                                        .speed(0.01)
+                                        .range(0.0..=1.0),
+                                );
+                                ui.label("Span (symbols):");
+                                ui.add(egui::DragValue::new(&mut self.rrc_span).range(1..=64));
+                            });
+                        });
+                    }
+                }
+                ModulationType::TwoTone => {
+                    // 2トーン固有のパラメータ（周波数は2トーンの中心）
+                    ui.horizontal(|ui| {
+                        ui.label("Tone Spacing (Hz):");
+                        ui.add(
+                            egui::DragValue::new(&mut self.tone_spacing)
+                                .speed(10.0)
+                                .range(0.0..=self.sample_rate),
+                        );
+                    });
+                }
+                ModulationType::Sweep => {
+                    // 周波数掃引固有のパラメータ
+                    ui.horizontal(|ui| {
+                        ui.label("Start (Hz):");
+                        ui.add(egui::DragValue::new(&mut self.sweep_start).speed(10.0));
+                        ui.label("Stop (Hz):");
+                        ui.add(egui::DragValue::new(&mut self.sweep_stop).speed(10.0));
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Sweep Time (s):");
+                        ui.add(
+                            egui::DragValue::new(&mut self.sweep_time)
+                                .speed(0.0001)
+                                .range(1e-6..=1000.0),
+                        );
+                    });
+                    // 連続掃引、またはドウェル時間ごとのステップ掃引（最大値保持と組み合わせて周波数特性を測定）
+                    ui.horizontal(|ui| {
+                        ui.label("Mode:");
+                        ui.radio_value(&mut self.sweep_mode, SweepMode::Continuous, "Continuous");
+                        ui.radio_value(&mut self.sweep_mode, SweepMode::LinearStep, "Linear Steps");
+                        ui.radio_value(&mut self.sweep_mode, SweepMode::LogStep, "Log Steps");
+                        ui.add_enabled_ui(self.sweep_mode != SweepMode::Continuous, |ui| {
+                            ui.label("Dwell (samples):");
+                            ui.add(
+                                egui::DragValue::new(&mut self.sweep_dwell).range(1..=1_000_000),
+                            );
+                        });
+                    });
+                }
+                ModulationType::Hopping => {
+                    // 周波数ホッピング固有のパラメータ
+                    ui.horizontal(|ui| {
+                        ui.label("Hop Set:");
+                        let band = self.hop_set == HopSet::Band;
+                        if ui.selectable_label(band, "Band").clicked() {
+                            self.hop_set = HopSet::Band;
+                        }
+                        // リストに切り替えたときは帯域の両端と中心から始める
+                        if ui.selectable_label(!band, "List").clicked() && band {
+                            let half = self.hop_bandwidth / 2.0;
+                            self.hop_set = HopSet::List(vec![
+                                self.frequency - half,
+                                self.frequency,
+                                self.frequency + half,
+                            ]);
+                        }
+                    });
+                    if let HopSet::List(freqs) = &mut self.hop_set {
+                        // ホップ先の周波数の編集リスト（上から順に巡回）
+                        let mut removed = None;
+                        let removable = freqs.len() > 1;
+                        for (k, freq) in freqs.iter_mut().enumerate() {
+                            ui.horizontal(|ui| {
+                                ui.label(format!("Hop {}:", k + 1));
+                                ui.add(egui::DragValue::new(freq).speed(10.0).suffix(" Hz"));
+                                if ui.add_enabled(removable, egui::Button::new("−")).clicked() {
+                                    removed = Some(k);
+                                }
+                            });
+                        }
+                        if let Some(k) = removed {
+                            freqs.remove(k);
+                        }
+                        if ui.button("Add Hop").clicked() {
+                            let freq = freqs.last().map_or(0.0, |f| f + self.hop_step);
+                            freqs.push(freq);
+                        }
+                    } else {
+                        ui.horizontal(|ui| {
+                            ui.label("Bandwidth (Hz):");
+                            ui.add(
+                                egui::DragValue::new(&mut self.hop_bandwidth)
+                                    .speed(10.0)
+                                    .range(0.0..=self.sample_rate),
+                            );
+                            ui.label("Step (Hz):");
+                            ui.add(
+                                egui::DragValue::new(&mut self.hop_step)
+                                    .speed(10.0)
+                                    .range(1.0..=self.sample_rate),
+                            );
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Seed:");
+                            ui.add(egui::DragValue::new(&mut self.seed));
+                        });
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label("Dwell (s):");
+                        ui.add(
+                            egui::DragValue::new(&mut self.hop_dwell)
+                                .speed(0.0001)
+                                .range(1e-6..=1000.0),
+                        );
+                        ui.checkbox(&mut self.hop_reset_phase, "Reset phase at each hop");
+                    });
+                }
+                ModulationType::OFDM => {
+                    // OFDM固有のパラメータ
+                    let ofdm = &mut self.ofdm;
+                    ui.horizontal(|ui| {
+                        ui.label("FFT Size:");
+                        ui.add(egui::DragValue::new(&mut ofdm.num_subcarriers).range(2..=65536));
+                        ui.label("Active:");
+                        ui.add(
+                            egui::DragValue::new(&mut ofdm.active_subcarriers)
+                                .range(1..=ofdm.num_subcarriers),
+                        );
+                        ui.checkbox(&mut ofdm.null_dc, "Null DC");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("CP (samples):");
+                        ui.add(
+                            egui::DragValue::new(&mut ofdm.cp_len).range(0..=ofdm.num_subcarriers),
+                        );
+                        ui.label("Subcarrier:");
+                        ui.radio_value(&mut ofdm.qam_order, 4, "QPSK");
+                        ui.radio_value(&mut ofdm.qam_order, 16, "16QAM");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Seed:");
+                        ui.add(egui::DragValue::new(&mut self.seed));
+                    });
+                    ui.label(format!(
+                        "Subcarrier spacing: {:.1} Hz, symbol: {} samples",
+                        self.sample_rate / self.ofdm.num_subcarriers as f64,
+                        self.ofdm.symbol_len()
+                    ));
+                }
+                ModulationType::Noise => {
+                    // 雑音系列のシード値（振幅はRMSレベルとして扱う）
+                    ui.horizontal(|ui| {
+                        ui.label("Seed:");
+                        ui.add(egui::DragValue::new(&mut self.seed));
+                    });
+                }
+                ModulationType::Chirp => {
+                    // チャープ固有のパラメータ
+                    ui.horizontal(|ui| {
+                        ui.label("Bandwidth (Hz):");
+                        ui.add(
+                            egui::DragValue::new(&mut self.chirp_bandwidth)
+                                .speed(10.0)
+                                .range(0.0..=self.sample_rate),
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Duration (s):");
+                        ui.add(
+                            egui::DragValue::new(&mut self.chirp_duration)
+                                .speed(0.0001)
+                                .range(1e-6..=1000.0),
+                        );
+                    });
+                }
+                _ => {
+                    // AM/FM/PM/SSB/Pulse共通の変調周波数設定
+                    ui.horizontal(|ui| {
+                        ui.label("Mod Frequency (Hz):");
+                        let (freq, range) = match self.mod_type {
+                            ModulationType::AM => {
+                                (&mut self.am_mod_freq, 0.0..=self.sample_rate / 2.0)
+                            }
+                            ModulationType::FM => {
+                                (&mut self.fm_mod_freq, 0.0..=self.sample_rate / 2.0)
+                            }
+                            ModulationType::PM | ModulationType::SSB => {
+                                (&mut self.am_mod_freq, 0.0..=self.sample_rate / 2.0)
+                            }
+                            ModulationType::Pulse => {
+                                (&mut self.pulse_freq, 0.0..=self.sample_rate / 2.0)
+                            }
+                            _ => (&mut self.am_mod_freq, 0.0..=0.0),
+                        };
+                        ui.add(freq_drag_value(freq, self.freq_step).range(range));
+                    });
+
+                    // 変調タイプ別の変調強度パラメータ
+                    ui.horizontal(|ui| match self.mod_type {
+                        ModulationType::AM => {
+                            ui.label("Mod Index (0-1):");
+                            ui.add(
+                                egui::DragValue::new(&mut self.am_mod_index)
+                                    .speed(0.01)
+                                    .range(0.0..=10.0),
+                            );
+                            ui.label("Waveform:");
+                            egui::ComboBox::new("am_waveform", "")
+                                .selected_text(format!("{:?}", self.am_waveform))
+                                .show_ui(ui, |ui| {
+                                    for waveform in [
+                                        AmWaveform::Sine,
+                                        AmWaveform::Triangle,
+                                        AmWaveform::Square,
+                                        AmWaveform::Sawtooth,
+                                    ] {
+                                        ui.selectable_value(
+                                            &mut self.am_waveform,
+                                            waveform,
+                                            format!("{:?}", waveform),
+                                        );
+                                    }
+                                });
+                        }
+                        ModulationType::FM => {
+                            ui.label("Peak Deviation (Hz):");
+                            ui.add(egui::DragValue::new(&mut self.fm_deviation).speed(10.0));
+                        }
+                        ModulationType::PM => {
+                            ui.label("Mod Index (Beta):");
+                            ui.add(
+                                egui::DragValue::new(&mut self.pm_mod_index)
+                                    .speed(0.01)
+                                    .range(0.0..=100.0),
+                            );
+                        }
+                        ModulationType::SSB => {
+                            ui.label("Sideband:");
+                            ui.radio_value(&mut self.ssb_sideband, Sideband::Upper, "USB");
+                            ui.radio_value(&mut self.ssb_sideband, Sideband::Lower, "LSB");
+                        }
+                        ModulationType::Pulse => {
+                            ui.label("Duty Cycle (0-1):");
+                            ui.add(
+                                egui::DragValue::new(&mut self.pulse_duty_cycle)
+                                    .speed(0.01)
+                                    .range(0.0..=1.0),
+                            );
+                            ui.label("Edge Time (s):");
+                            ui.add(
+                                egui::DragValue::new(&mut self.pulse_edge_time)
+                                    .speed(1e-6)
+                                    .range(0.0..=1.0),
+                            );
+                        }
+                        _ => {}
+                    });
+                }
+            }
+        });
+
+        // === 信号生成 ===
+        // 可視化とエクスポートのためのデータを生成
+        // パネル外で生成することで、ボトムパネル（エクスポート）と
+        // セントラルパネル（プロット）の両方で使用可能にする
+        let num_samples = self.num_samples;
+
+        // 信号生成パラメータを構築
+        let params = self.signal_params();
+
+        // 位相に影響するパラメータが変更された場合のみジェネレータをリセットして再生成
+        // それ以外のフレームではジェネレータの位相状態を保持し、表示中のブロックを再利用する
+        // "Next Block"が押された場合はリセットせずに続きを生成するため、
+        // 連続して表示・エクスポートしたブロック同士は位相が途切れずにつながる
+        // 生成はワーカースレッドで行い、完了するまでは前回完了したブロックを表示する
+        self.recomputed = false;
+        if self.imported.is_some() {
+            // 読み込んだデータをそのまま使用
+        } else {
+            let reset = needs_regen(self.last_params.as_ref(), &params)
+                || self.last_num_samples != num_samples;
+            let analysis = (reset || self.next_block_requested).then(|| self.block_analysis());
+            let worker = self
+                .worker
+                .get_or_insert_with(|| GenerationWorker::start(ctx.clone()));
+            if let Some(analysis) = analysis {
+                worker.request(params.clone(), num_samples, reset, analysis);
+                if reset {
+                    self.last_params = Some(params.clone());
+                    self.last_num_samples = num_samples;
+                }
+            }
+            // 表示するブロックがまだない起動直後は完了を待つ
+            let block = if self.raw_samples.is_empty() {
+                worker.wait()
+            } else {
+                worker.try_take()
+            };
+            self.generating = worker.is_busy();
+            // ワーカーで計算したサンプルとスペクトラムをそのまま使用
+            // （その後に振幅や表示設定が変わっていれば、以下でその部分だけ計算し直す）
+            if let Some(block) = block {
+                self.raw_samples = block.raw;
+                self.samples = Arc::new(block.samples);
+                self.samples_scaling = Some(block.scaling);
+                self.spectrum_cache = block.spectrum;
+                self.spectrogram_cache = block.spectrogram;
+                self.spectrogram_texture = None;
+            }
+        }
+        self.next_block_requested = false;
+
+        // 振幅と出力形式を適用
+        // ブロック・振幅・出力形式が変わったときのみ再計算し、スペクトラムのキャッシュも破棄
+        // 読み込んだデータは振幅を変更せず複素IQとして扱う
+        let scaling = if self.imported.is_some() {
+            (1.0, OutputMode::Complex, OutputImpairments::default())
+        } else {
+            (self.amplitude, self.output_mode, self.output_impairments())
+        };
+        if self.samples_scaling != Some(scaling) {
+            let mut samples = scale_samples(&self.raw_samples, scaling.0, scaling.1);
+            scaling.2.apply(&mut samples);
+            self.samples = Arc::new(samples);
+            self.samples_scaling = Some(scaling);
+            self.spectrum_cache = None;
+            self.spectrogram_cache = None;
+            self.spectrogram_texture = None;
+            self.recomputed = true;
+        }
+        let samples = Arc::clone(&self.samples);
+
+        // === ボトムパネル：エクスポート機能 ===
+        egui::TopBottomPanel::bottom("export_panel").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                // 生成中は表示中のブロックが現在の設定と一致しないため、
+                // 新しいサンプリングレートなどのヘッダで古いデータを書き出さないよう無効にする
+                if self.generating && self.imported.is_none() {
+                    ui.disable();
+                }
+                // CSV形式でエクスポート
+                if ui.button("Export to CSV").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("CSV", &["csv"])
+                        .set_file_name("output.csv")
+                        .save_file()
+                    {
+                        let time_column = self.csv_time_column.then_some(self.time_domain_unit);
+                        if let Err(e) = export_to_csv(
+                            &path,
+                            &samples,
+                            time_column,
+                            self.sample_rate,
+                            self.output_mode,
+                        ) {
+                            eprintln!("Failed to export: {}", e);
+                        } else {
+                            eprintln!("Exported to {:?}", path);
+                        }
+                    }
+                }
+                ui.checkbox(&mut self.csv_time_column, "Include time column");
+
+                // 窓関数・平均化・表示スケールを適用したスペクトラムをCSV形式でエクスポート
+                if ui.button("Export Spectrum (CSV)").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("CSV", &["csv"])
+                        .set_file_name("spectrum.csv")
+                        .save_file()
+                    {
+                        let (points, _) = self.compute_spectrum(&samples);
+                        if let Err(e) = export_spectrum_to_csv(&path, &points) {
+                            eprintln!("Failed to export: {}", e);
+                        } else {
+                            eprintln!("Exported to {:?}", path);
+                        }
+                    }
+                }
+
+                ui.separator();
+
+                // バイナリ形式でエクスポート
+                if ui.button("Export to BIN").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("Binary", &["bin"])
+                        .set_file_name("output.bin")
+                        .save_file()
+                    {
+                        let result = export_to_bin(
+                            &path,
+                            &samples,
+                            self.bin_format,
+                            self.bin_full_scale,
+                            self.output_mode,
+                        )
+                        .and_then(|()| {
+                            if !self.bin_sigmf_meta {
+                                return Ok(());
+                            }
+                            // データファイル名がSigMFの命名規則に従わないため、
+                            // core:datasetでデータファイルを指定する
+                            let dataset = path.file_name().and_then(|n| n.to_str());
+                            write_sigmf_meta(
+                                &path.with_extension("sigmf-meta"),
+                                dataset,
+                                samples.len(),
+                                &params,
+                                self.bin_format,
+                            )
+                        });
+                        if let Err(e) = result {
+                            eprintln!("Failed to export: {}", e);
+                        } else {
+                            eprintln!("Exported to {:?}", path);
+                        }
+                    }
+                }
+                let prev_bin_format = self.bin_format;
+                egui::ComboBox::new("bin_format", "")
+                    .selected_text(format!("{:?}", self.bin_format))
+                    .show_ui(ui, |ui| {
+                        for format in [
+                            BinFormat::F32,
+                            BinFormat::F64,
+                            BinFormat::I16,
+                            BinFormat::I8,
+                        ] {
+                            ui.selectable_value(
+                                &mut self.bin_format,
+                                format,
+                                format!("{:?}", format),
+                            );
+                        }
                     });
-                    ui.horizontal(|ui| {
-                        ui.label("Spacing (Hz):");
-                        ui.add(egui::DragValue::new(&mut self.multitone_spacing).speed(10.0));
+                // 形式を切り替えたらフルスケール値をその形式の最大値に戻す
+                if self.bin_format != prev_bin_format {
+                    self.bin_full_scale = self.bin_format.max_value();
+                }
+                if matches!(self.bin_format, BinFormat::I16 | BinFormat::I8) {
+                    ui.label("Full-scale:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.bin_full_scale)
+                            .speed(1.0)
+                            .range(1.0..=self.bin_format.max_value()),
+                    );
+                }
+                ui.checkbox(&mut self.bin_sigmf_meta, "SigMF");
+
+                ui.separator();
+
+                // SigMF形式（データ + メタデータ）でエクスポート
+                if ui.button("Export to SigMF").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("SigMF", &["sigmf-data"])
+                        .set_file_name("output.sigmf-data")
+                        .save_file()
+                    {
+                        if let Err(e) = export_to_sigmf(
+                            &path,
+                            &samples,
+                            &params,
+                            self.bin_format,
+                            self.bin_full_scale,
+                        ) {
+                            eprintln!("Failed to export: {}", e);
+                        } else {
+                            eprintln!("Exported to {:?}", path);
+                        }
+                    }
+                }
+
+                // NumPy形式（complex64）でエクスポート
+                if ui.button("Export to NPY").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("NumPy", &["npy"])
+                        .set_file_name("output.npy")
+                        .save_file()
+                    {
+                        if let Err(e) = export_to_npy(&path, &samples) {
+                            eprintln!("Failed to export: {}", e);
+                        } else {
+                            eprintln!("Exported to {:?}", path);
+                        }
+                    }
+                }
+
+                // MATLAB形式（複素数の倍精度配列iqとサンプリングレートfs）でエクスポート
+                if ui.button("Export to MAT").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("MATLAB", &["mat"])
+                        .set_file_name("output.mat")
+                        .save_file()
+                    {
+                        if let Err(e) = export_to_mat(&path, &samples, self.sample_rate) {
+                            eprintln!("Failed to export: {}", e);
+                        } else {
+                            eprintln!("Exported to {:?}", path);
+                        }
+                    }
+                }
+
+                ui.separator();
+
+                // WAV形式でエクスポート
+                if ui.button("Export to WAV").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("WAV", &["wav"])
+                        .set_file_name("output.wav")
+                        .save_file()
+                    {
+                        if let Err(e) = export_to_wav(
+                            &path,
+                            &samples,
+                            self.sample_rate,
+                            self.wav_format,
+                            self.output_mode,
+                        ) {
+                            eprintln!("Failed to export: {}", e);
+                        } else {
+                            eprintln!("Exported to {:?}", path);
+                        }
+                    }
+                }
+                egui::ComboBox::new("wav_format", "")
+                    .selected_text(match self.wav_format {
+                        hound::SampleFormat::Float => "Float32",
+                        hound::SampleFormat::Int => "Int16",
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut self.wav_format,
+                            hound::SampleFormat::Float,
+                            "Float32",
+                        );
+                        ui.selectable_value(
+                            &mut self.wav_format,
+                            hound::SampleFormat::Int,
+                            "Int16",
+                        );
                     });
-                    ui.horizontal(|ui| {
-                        ui.label("Initial Phase:");
-                        egui::ComboBox::new("multitone_phase", "")
-                            .selected_text(format!("{:?}", self.multitone_phase))
-                            .show_ui(ui, |ui| {
-                                ui.selectable_value(
-                                    &mut self.multitone_phase,
-                                    MultitonePhase::Zero,
-                                    "Zero",
-                                );
-                                ui.selectable_value(
-                                    &mut self.multitone_phase,
-                                    MultitonePhase::Random,
-                                    "Random",
-                                );
-                                ui.selectable_value(
-                                    &mut self.multitone_phase,
-                                    MultitonePhase::Schroeder,
-                                    "Schroeder",
-                                );
-                            });
+            });
+
+            // TCPストリーミング（32ビット浮動小数点のIQを連続送信、GNU RadioのTCP Sourceなどで受信）
+            let stream_settings = (
+                params.clone(),
+                self.amplitude,
+                self.output_mode,
+                self.output_impairments(),
+            );
+            #[cfg(feature = "audio")]
+            self.audio_ui(ui, stream_settings.clone());
+            ui.horizontal(|ui| {
+                if let Some(streamer) = &self.streamer {
+                    streamer.update(stream_settings);
+                    ui.label(format!("TCP {}", streamer.addr));
+                    if ui.button("Stop").clicked() {
+                        self.streamer = None;
+                    } else {
+                        match streamer.status() {
+                            status @ StreamStatus::Failed(_) => {
+                                ui.colored_label(egui::Color32::RED, status.to_string())
+                            }
+                            status => ui.label(status.to_string()),
+                        };
+                        // 接続状態は送信スレッドが更新するため、定期的に再描画する
+                        ui.ctx().request_repaint_after(Duration::from_millis(250));
+                    }
+                } else {
+                    ui.label("TCP Bind:");
+                    ui.add(egui::TextEdit::singleline(&mut self.stream_bind).desired_width(100.0));
+                    ui.label("Port:");
+                    ui.add(egui::DragValue::new(&mut self.stream_port));
+                    if ui.button("Start TCP Stream").clicked() {
+                        match TcpStreamer::start(
+                            &self.stream_bind,
+                            self.stream_port,
+                            stream_settings,
+                        ) {
+                            Ok(streamer) => {
+                                self.streamer = Some(streamer);
+                                self.stream_error = None;
+                            }
+                            Err(e) => self.stream_error = Some(e.to_string()),
+                        }
+                    }
+                    if let Some(error) = &self.stream_error {
+                        ui.colored_label(egui::Color32::RED, error);
+                    }
+                }
+            });
+
+            // 信号の統計量（PAPR・クレストファクタ・RMS・ピーク）
+            ui.horizontal(|ui| {
+                let peak = samples.iter().map(|s| s.norm()).fold(0.0, f64::max);
+                ui.label(format!(
+                    "PAPR: {:.2} dB   Crest Factor: {:.3}   RMS: {:.4}   Peak: {:.4}",
+                    papr_db(&samples),
+                    crest_factor(&samples),
+                    rms(&samples),
+                    peak
+                ));
+                // 再計算が発生したフレームでは表示し、次のフレームで消す
+                if self.recomputed {
+                    ui.spinner();
+                    ui.label("regenerating…");
+                    ui.ctx().request_repaint();
+                } else if self.generating && self.imported.is_none() {
+                    // ワーカーの生成が完了すると再描画が要求される
+                    ui.spinner();
+                    ui.label("computing…");
+                }
+            });
+        });
+
+        // === セントラルパネル：プロット表示 ===
+        egui::CentralPanel::default().show(ctx, |ui| {
+            // プロットの高さを計算
+            // 表示中のプロット（時間領域・周波数領域・スペクトログラム）の数で
+            // 利用可能な高さを分割し、ラベルとスペース分を考慮
+            let available_height = ui.available_height();
+            let plot_count = [
+                self.show_time_domain,
+                self.show_freq_domain,
+                self.show_spectrogram,
+            ]
+            .iter()
+            .filter(|&&shown| shown)
+            .count()
+            .max(1);
+            let plot_height = (available_height - 30.0 * plot_count as f32) / plot_count as f32;
+
+            // === 時間領域プロット ===
+            // 波形表示とコンスタレーション表示は同じ領域を切り替えて使用する
+            if self.show_time_domain {
+                ui.horizontal(|ui| {
+                    ui.label("Time Domain");
+                    ui.selectable_value(&mut self.time_view, TimeView::Waveform, "Waveform");
+                    ui.selectable_value(
+                        &mut self.time_view,
+                        TimeView::Constellation,
+                        "Constellation",
+                    );
+                    ui.selectable_value(&mut self.time_view, TimeView::Polar, "Polar")
+                        .on_hover_text("Magnitude and phase relative to the carrier");
+                    // 振幅スケーリング後の信号レベル（振幅1をフルスケールとする）
+                    let stats = signal_stats(&samples);
+                    ui.label(format!(
+                        "Power: {:.4} ({:.2} dBFS)   RMS: {:.4} ({:.2} dBFS)   Peak: {:.4} ({:.2} dBFS)",
+                        stats.mean_power,
+                        stats.mean_power_dbfs(),
+                        stats.rms,
+                        stats.rms_dbfs(),
+                        stats.peak,
+                        stats.peak_dbfs()
+                    ));
+                    if self.time_view == TimeView::Constellation {
+                        ui.checkbox(&mut self.constellation_trajectory, "Trajectory");
+                    } else if self.time_view == TimeView::Waveform {
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.selectable_value(
+                                &mut self.time_domain_unit,
+                                TimeDomainUnit::Samples,
+                                "Samples",
+                            );
+                            ui.selectable_value(
+                                &mut self.time_domain_unit,
+                                TimeDomainUnit::Seconds,
+                                "Time (s)",
+                            );
+                            ui.label("Unit:");
+                        });
+                    }
+                });
+            }
+
+            if self.show_time_domain && self.time_view == TimeView::Polar {
+                // 搬送波の回転を取り除いた極座標の軌跡（縦横比1:1）
+                // 古いサンプルほど薄く描き、位相の変化の向きがわかるようにする
+                // 読み込んだデータは搬送波周波数が不明なため、そのままの位相で表示する
+                let carrier = if self.imported.is_some() {
+                    0.0
+                } else {
+                    self.frequency
+                };
+                let points =
+                    polar_points(&samples, carrier, self.sample_rate, MAX_CONSTELLATION_POINTS);
+                let radius = points
+                    .iter()
+                    .map(|[x, y]| x.hypot(*y))
+                    .fold(0.0, f64::max);
+                Plot::new("polar")
+                    .height(plot_height)
+                    .data_aspect(1.0)
+                    .show_grid(false)
+                    .show(ui, |plot_ui| {
+                        // 極座標の目盛り（最大振幅の1/4ごとの同心円と30度ごとの放射線）
+                        let grid = egui::Color32::from_gray(90);
+                        for k in 1..=4 {
+                            let r = radius * k as f64 / 4.0;
+                            let circle: PlotPoints = (0..=72)
+                                .map(|i| {
+                                    let a = i as f64 * 5f64.to_radians();
+                                    [r * a.cos(), r * a.sin()]
+                                })
+                                .collect();
+                            plot_ui.line(Line::new(circle).color(grid).width(0.5));
+                        }
+                        for i in 0..12 {
+                            let a = (i as f64 * 30.0).to_radians();
+                            plot_ui.line(
+                                Line::new(vec![[0.0, 0.0], [radius * a.cos(), radius * a.sin()]])
+                                    .color(grid)
+                                    .width(0.5),
+                            );
+                        }
+                        let len = points.len().div_ceil(POLAR_FADE_SEGMENTS).max(1);
+                        let segments = points.len().div_ceil(len);
+                        for i in 0..segments {
+                            // 隣の区間とつながるよう、直後の点まで含めて描く
+                            let end = ((i + 1) * len + 1).min(points.len());
+                            let alpha = 255 * (i + 1) / segments;
+                            plot_ui.line(
+                                Line::new(PlotPoints::new(points[i * len..end].to_vec()))
+                                    .color(egui::Color32::from_rgba_unmultiplied(
+                                        100, 200, 255, alpha as u8,
+                                    ))
+                                    .width(1.0),
+                            );
+                        }
                     });
-                    if self.multitone_phase == MultitonePhase::Random {
-                        ui.horizontal(|ui| {
-                            ui.label("Seed:");
-                            ui.add(egui::DragValue::new(&mut self.seed));
+
+                ui.separator();
+            } else if self.show_time_domain && self.time_view == TimeView::Constellation {
+                // I/Q平面上の散布図（縦横比1:1）
+                // 描画を軽く保つため、点数が多い場合は間引いて表示する
+                let points = constellation_points(&samples, MAX_CONSTELLATION_POINTS);
+                Plot::new("constellation")
+                    .height(plot_height)
+                    .data_aspect(1.0)
+                    .show(ui, |plot_ui| {
+                        if self.constellation_trajectory {
+                            plot_ui.line(
+                                Line::new(PlotPoints::new(points.clone()))
+                                    .name("Trajectory")
+                                    .width(0.5),
+                            );
+                        }
+                        plot_ui.points(Points::new(points).radius(1.5).name("IQ"));
+                    });
+
+                ui.separator();
+            } else if self.show_time_domain {
+                // 時間軸単位の変更を検出し、プロット範囲を調整
+                // ユーザーがズーム/パン操作した範囲を新しい単位でも維持する
+                if self.time_domain_unit != self.last_time_domain_unit {
+                    if let Some(bounds) = self.last_plot_bounds {
+                        let min = bounds.min();
+                        let max = bounds.max();
+
+                        // X軸の範囲を新しい単位に変換
+                        let (new_min_x, new_max_x) = match self.time_domain_unit {
+                            TimeDomainUnit::Samples => {
+                                // 秒 → サンプル数：サンプリングレートを掛ける
+                                (min[0] * self.sample_rate, max[0] * self.sample_rate)
+                            }
+                            TimeDomainUnit::Seconds => {
+                                // サンプル数 → 秒：サンプリングレートで割る
+                                (min[0] / self.sample_rate, max[0] / self.sample_rate)
+                            }
+                        };
+
+                        // Y軸は変更なし
+                        let new_min_y = min[1];
+                        let new_max_y = max[1];
+
+                        // 新しい範囲を設定
+                        self.forced_plot_bounds = Some(egui_plot::PlotBounds::from_min_max(
+                            [new_min_x, new_min_y],
+                            [new_max_x, new_max_y],
+                        ));
+                    }
+                    self.last_time_domain_unit = self.time_domain_unit;
+                }
+
+                // プロットを描画
+                let plot_response =
+                    Plot::new("time_domain")
+                        .height(plot_height)
+                        .show(ui, |plot_ui| {
+                            // 強制的な範囲設定がある場合は適用（単位変更時）
+                            if let Some(bounds) = self.forced_plot_bounds {
+                                plot_ui.set_plot_bounds(bounds);
+                                self.forced_plot_bounds = None;
+                            }
+
+                            // I成分（実部）のプロットポイントを生成
+                            let i_points: PlotPoints = samples
+                                .iter()
+                                .enumerate()
+                                .map(|(i, s)| {
+                                    let x = match self.time_domain_unit {
+                                        TimeDomainUnit::Seconds => i as f64 / self.sample_rate,
+                                        TimeDomainUnit::Samples => i as f64,
+                                    };
+                                    [x, s.re]
+                                })
+                                .collect();
+
+                            // Q成分（虚部）のプロットポイントを生成
+                            let q_points: PlotPoints = samples
+                                .iter()
+                                .enumerate()
+                                .map(|(i, s)| {
+                                    let x = match self.time_domain_unit {
+                                        TimeDomainUnit::Seconds => i as f64 / self.sample_rate,
+                                        TimeDomainUnit::Samples => i as f64,
+                                    };
+                                    [x, s.im]
+                                })
+                                .collect();
+
+                            // I/Q成分をプロット
+                            plot_ui.line(Line::new(i_points).name("I"));
+                            // 実信号ではQ成分は常に0のため表示しない
+                            if self.output_mode == OutputMode::Complex {
+                                plot_ui.line(Line::new(q_points).name("Q"));
+                            }
                         });
+
+                // 現在のプロット範囲を保存（単位変更検出用）
+                self.last_plot_bounds = Some(*plot_response.transform.bounds());
+
+                ui.separator();
+            }
+
+            // === 周波数領域プロット ===
+            if self.show_freq_domain {
+                ui.horizontal(|ui| {
+                    ui.label("Frequency Domain");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.selectable_value(&mut self.spectrum_scale, SpectrumScale::Decibel, "dB");
+                        ui.selectable_value(
+                            &mut self.spectrum_scale,
+                            SpectrumScale::Linear,
+                            "Linear",
+                        );
+                        ui.label("Scale:");
+                        // dB表示のノイズフロアと基準レベル（ピークを0dBとする相対表示/絶対レベル）
+                        if self.spectrum_scale == SpectrumScale::Decibel {
+                            ui.add(
+                                egui::DragValue::new(&mut self.db_floor)
+                                    .speed(1.0)
+                                    .range(-300.0..=-10.0)
+                                    .suffix(" dB"),
+                            );
+                            ui.label("Floor:");
+                        }
+                        ui.selectable_value(
+                            &mut self.reference_level,
+                            ReferenceLevel::Absolute,
+                            "Absolute",
+                        );
+                        ui.selectable_value(&mut self.reference_level, ReferenceLevel::Peak, "Peak");
+                        ui.label("Ref:");
+                        ui.separator();
+                        if self.max_hold && ui.button("Clear").clicked() {
+                            self.max_hold_trace.clear();
+                        }
+                        ui.checkbox(&mut self.max_hold, "Max Hold");
+                        ui.checkbox(&mut self.log_freq, "Log freq")
+                            .on_hover_text("Show positive frequencies on a log10 axis");
+                        ui.separator();
+                        ui.checkbox(&mut self.zero_pad_fft, "Zero-pad FFT")
+                            .on_hover_text("Pad the FFT to the next power of two (display only)");
+                        ui.separator();
+                        window_function_combo(ui, "window_function", &mut self.window_function);
+                        ui.label("Window:");
+                        // 窓関数による測定のトレードオフ（雑音帯域幅の増加とビン間のトーンの振幅低下）
+                        let coeffs = window(self.window_function, WINDOW_METRICS_LEN);
+                        ui.label(format!(
+                            "ENBW: {:.2} bins  Scalloping: {:.2} dB",
+                            enbw(&coeffs),
+                            scalloping_loss_db(&coeffs)
+                        ));
+                        ui.separator();
+                    });
+                });
+
+                // Welch法による平均化（パワースペクトル密度の表示）
+                let mut store_reference = false;
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.welch_averaging, "Averaging (Welch PSD)");
+                    if self.welch_averaging {
+                        ui.label("Segment:");
+                        ui.add(egui::DragValue::new(&mut self.welch_segment_len).range(16..=65536));
+                        ui.label("Overlap:");
+                        ui.add(
+                            egui::DragValue::new(&mut self.welch_overlap)
+                                .range(0..=self.welch_segment_len - 1),
+                        );
                     }
+                    ui.separator();
+                    // 現在のスペクトラムを参照として保存し、以降のプロットに重ねて比較する
+                    store_reference = ui.button("Store Reference").clicked();
+                    if !self.reference_trace.is_empty() && ui.button("Clear Reference").clicked() {
+                        self.reference_trace.clear();
+                    }
+                });
+                self.welch_overlap = self.welch_overlap.min(self.welch_segment_len - 1);
+                let psd_factor = self.display_psd_factor(samples.len());
+
+                // スペクトラムは新しいブロックではワーカーで計算済みのものを使い、
+                // 窓関数・表示スケール・平均化設定が変わったときのみここで再計算
+                let settings = self.spectrum_settings();
+                let measurement = self.measurement_settings();
+                let cache = match &mut self.spectrum_cache {
+                    Some(cache) if cache.settings == settings => cache,
+                    cache => {
+                        self.recomputed = true;
+                        cache.insert(SpectrumCache::compute(
+                            &samples,
+                            settings,
+                            measurement.clone(),
+                            &mut self.fft_planner,
+                        ))
+                    }
+                };
+                // 占有帯域幅・THD/SINAD・SFDR・ACPR・NPRは測定の設定が変わったときのみ再計算
+                cache.update_measurements(&measurement);
+                // 最大値保持：新しいスペクトラムごとに一度だけ各ビンの最大値を更新
+                if !self.max_hold {
+                    self.max_hold_trace.clear();
+                } else if !cache.max_hold_applied || self.max_hold_trace.is_empty() {
+                    update_max_hold(&mut self.max_hold_trace, &cache.power);
+                    cache.max_hold_applied = true;
+                }
+                let fft_points = Arc::clone(&cache.points);
+                let Measurements {
+                    obw: (obw_low, obw_high, obw),
+                    distortion: (thd_ratio, sinad_db),
+                    sfdr: (sfdr_db, spur),
+                    acpr: acpr_db,
+                    npr: npr_db,
+                } = cache.measurements.1.clone();
+                ui.horizontal(|ui| {
+                    ui.label("OBW");
+                    ui.add(
+                        egui::DragValue::new(&mut self.obw_percent)
+                            .speed(0.1)
+                            .range(1.0..=100.0)
+                            .suffix(" %"),
+                    );
+                    ui.label(format!(": {:.1} Hz", obw));
+                    ui.separator();
+                    ui.label(format!(
+                        "THD: {:.4} % ({:.1} dB)",
+                        thd_ratio * 100.0,
+                        20.0 * thd_ratio.log10()
+                    ));
+                    ui.label(format!("SINAD: {:.1} dB", sinad_db));
+                    ui.label(format!("SFDR: {:.1} dB", sfdr_db));
+                    ui.separator();
+                    ui.label("Channel BW (Hz):");
+                    ui.add(
+                        egui::DragValue::new(&mut self.acp_channel_bw)
+                            .speed(10.0)
+                            .range(1.0..=self.sample_rate),
+                    );
+                    ui.label("Offset (Hz):");
+                    ui.add(
+                        egui::DragValue::new(&mut self.acp_channel_offset)
+                            .speed(10.0)
+                            .range(0.0..=self.sample_rate),
+                    );
+                    match acpr_db {
+                        Some((lower, upper)) => {
+                            ui.label(format!("ACPR: {:.1} / {:.1} dBc", lower, upper))
+                        }
+                        None => ui.label("ACPR: no power in main channel"),
+                    };
+                    if let Some(npr_db) = npr_db {
+                        ui.separator();
+                        ui.label(format!("NPR: {:.1} dB", npr_db));
+                    }
+                });
+
+                if store_reference {
+                    self.reference_trace = fft_points.to_vec();
+                    self.reference_scale = (self.spectrum_scale, psd_factor.is_some());
+                }
+
+                // マーカーの振幅を現在のスペクトラムの最寄りのビンに追従させる
+                for marker in &mut self.markers {
+                    if let Some(snapped) = snap_to_bin(&fft_points, marker.freq) {
+                        *marker = snapped;
+                    }
+                }
+                let unit = match (self.spectrum_scale, psd_factor.is_some()) {
+                    (SpectrumScale::Decibel, false) => "dB",
+                    (SpectrumScale::Linear, false) => "",
+                    (SpectrumScale::Decibel, true) => "dB/Hz",
+                    (SpectrumScale::Linear, true) => "/Hz",
+                };
+                ui.horizontal(|ui| {
+                    ui.label("Markers (click to snap to peak):");
+                    for (i, marker) in self.markers.iter().enumerate() {
+                        ui.label(format!(
+                            "M{}: {:.1} Hz, {:.2} {}",
+                            i + 1,
+                            marker.freq,
+                            marker.mag,
+                            unit
+                        ));
+                    }
+                    if let [m1, m2] = self.markers.as_slice() {
+                        ui.separator();
+                        ui.label(format!(
+                            "Δfreq: {:.1} Hz  Δmag: {:.2} {}",
+                            m2.freq - m1.freq,
+                            m2.mag - m1.mag,
+                            unit
+                        ));
+                    }
+                    if !self.markers.is_empty() && ui.button("Clear Markers").clicked() {
+                        self.markers.clear();
+                    }
+                });
+
+                // スペクトラムをプロット
+                // 対数周波数軸ではDCと負の周波数を除き、x座標をlog10(周波数)とする
+                let log_freq = self.log_freq;
+                let to_x = |freq: f64| if log_freq {
+                    (freq > 0.0).then(|| freq.log10())
                 } else {
-                    // AM/FM/PM/Pulse共通の変調周波数設定
-                    ui.horizontal(|ui| {
-                        ui.label("Mod Frequency (Hz):");
-                        let (freq, range) = match self.mod_type {
-                            ModulationType::AM => {
-                                (&mut self.am_mod_freq, 0.0..=self.sample_rate / 2.0)
-                            }
-                            ModulationType::FM => {
-                                (&mut self.fm_mod_freq, 0.0..=self.sample_rate / 2.0)
-                            }
-                            ModulationType::PM => {
-                                (&mut self.am_mod_freq, 0.0..=self.sample_rate / 2.0)
+                    Some(freq)
+                };
+                let min_positive_freq = fft_points.iter().map(|p| p[0]).find(|&f| f > 0.0);
+                let mut plot = Plot::new("freq_domain").height(plot_height);
+                if log_freq {
+                    plot = plot.x_axis_formatter(|mark, _| format_log_freq(mark.value));
+                }
+                let clicked = plot
+                    .show(ui, |plot_ui| {
+                        let magnitude = if log_freq {
+                            to_log_freq(&fft_points)
+                        } else {
+                            fft_points.to_vec()
+                        };
+                        plot_ui.line(Line::new(PlotPoints::new(magnitude)).name("Magnitude"));
+                        if !self.max_hold_trace.is_empty() {
+                            let hold: Vec<[f64; 2]> = fft_points
+                                .iter()
+                                .zip(&self.max_hold_trace)
+                                .map(|(&[freq, _], &p)| {
+                                    [
+                                        freq,
+                                        self.spectrum_scale.apply_power(
+                                            p,
+                                            psd_factor,
+                                            self.db_floor,
+                                        ),
+                                    ]
+                                })
+                                .collect();
+                            let hold = if log_freq {
+                                to_log_freq(&hold)
+                            } else {
+                                hold
+                            };
+                            plot_ui.line(Line::new(PlotPoints::new(hold)).name("Max Hold"));
+                        }
+                        // 参照スペクトラムは保存時の周波数軸のまま重ねる（単位が異なる場合は表示しない）
+                        let reference_scale = (self.spectrum_scale, psd_factor.is_some());
+                        if !self.reference_trace.is_empty() && self.reference_scale == reference_scale
+                        {
+                            let reference = if log_freq {
+                                to_log_freq(&self.reference_trace)
+                            } else {
+                                self.reference_trace.clone()
+                            };
+                            plot_ui.line(
+                                Line::new(PlotPoints::new(reference))
+                                    .style(LineStyle::dashed_loose())
+                                    .name("Reference"),
+                            );
+                        }
+                        for (i, marker) in self.markers.iter().enumerate() {
+                            let Some(x) = to_x(marker.freq) else {
+                                continue;
+                            };
+                            let name = format!("M{}", i + 1);
+                            plot_ui.vline(VLine::new(x).name(&name));
+                            plot_ui.text(
+                                Text::new(PlotPoint::new(x, marker.mag), name)
+                                    .anchor(egui::Align2::LEFT_BOTTOM),
+                            );
+                        }
+                        // ACPRの積分帯域（主チャネルと上下の隣接チャネル）を表示
+                        let (y_min, y_max) = fft_points
+                            .iter()
+                            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+                                (lo.min(p[1]), hi.max(p[1]))
+                            });
+                        if y_min < y_max {
+                            let half = self.acp_channel_bw / 2.0;
+                            for (center, name) in [
+                                (self.frequency, "ACP Main"),
+                                (self.frequency - self.acp_channel_offset, "ACP Adjacent"),
+                                (self.frequency + self.acp_channel_offset, "ACP Adjacent"),
+                            ] {
+                                // 対数軸では正の周波数にかかる部分だけを描く
+                                let low = if log_freq {
+                                    min_positive_freq.map(|f| (center - half).max(f))
+                                } else {
+                                    Some(center - half)
+                                };
+                                let (Some(low), Some(high)) =
+                                    (low.and_then(to_x), to_x(center + half))
+                                else {
+                                    continue;
+                                };
+                                if low >= high {
+                                    continue;
+                                }
+                                let band = vec![
+                                    [low, y_min],
+                                    [high, y_min],
+                                    [high, y_max],
+                                    [low, y_max],
+                                ];
+                                plot_ui.polygon(
+                                    Polygon::new(PlotPoints::new(band))
+                                        .fill_color(egui::Color32::from_rgba_unmultiplied(
+                                            100, 150, 255, 24,
+                                        ))
+                                        .name(name),
+                                );
                             }
-                            ModulationType::Pulse => {
-                                (&mut self.pulse_freq, 0.0..=self.sample_rate / 2.0)
+                        }
+                        // 占有帯域幅の両端を表示
+                        if obw > 0.0 {
+                            for x in [obw_low, obw_high].into_iter().filter_map(to_x) {
+                                plot_ui.vline(VLine::new(x).name("OBW"));
                             }
-                            _ => (&mut self.am_mod_freq, 0.0..=0.0),
-                        };
-                        ui.add(egui::DragValue::new(freq).speed(1.0).range(range));
+                        }
+                        // 最悪スプリアスの位置を表示
+                        let spur_point = fft_points.get(spur).filter(|_| sfdr_db.is_finite());
+                        if let Some((x, mag)) =
+                            spur_point.and_then(|&[freq, mag]| Some((to_x(freq)?, mag)))
+                        {
+                            plot_ui.points(Points::new(vec![[x, mag]]).radius(4.0).name("Spur"));
+                            plot_ui.text(
+                                Text::new(PlotPoint::new(x, mag), "Spur")
+                                    .anchor(egui::Align2::LEFT_BOTTOM),
+                            );
+                        }
+                        let clicked = plot_ui
+                            .response()
+                            .clicked()
+                            .then(|| plot_ui.pointer_coordinate())
+                            .flatten();
+                        clicked
+                    })
+                    .inner;
+
+                // クリックした位置の最寄りのピークにマーカーを置く
+                // 2つ置いた後のクリックでは1つ目から置き直す
+                let clicked_freq = clicked.map(|pos| if log_freq {
+                    10f64.powf(pos.x)
+                } else {
+                    pos.x
+                });
+                if let Some(marker) = clicked_freq.and_then(|freq| snap_to_peak(&fft_points, freq)) {
+                    if self.markers.len() >= 2 {
+                        self.markers.clear();
+                    }
+                    self.markers.push(marker);
+                }
+
+                ui.separator();
+            }
+
+            // === スペクトログラム ===
+            if self.show_spectrogram {
+                ui.horizontal(|ui| {
+                    ui.label("Spectrogram");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.add(
+                            egui::DragValue::new(&mut self.stft_overlap)
+                                .range(0..=self.stft_size.saturating_sub(1)),
+                        );
+                        ui.label("Overlap:");
+                        ui.add(egui::DragValue::new(&mut self.stft_size).range(8..=8192));
+                        ui.label("FFT Size:");
+                    });
+                });
+                self.stft_overlap = self.stft_overlap.min(self.stft_size - 1);
+
+                // スペクトログラムは新しいブロックではワーカーで計算済みの画像を使い、
+                // FFTサイズ・重なり・窓関数・ノイズフロアが変わったときのみここで再計算
+                let settings = self.spectrogram_settings();
+                if self.spectrogram_cache.as_ref().map(|(cached, _)| cached) != Some(&settings) {
+                    self.spectrogram_cache =
+                        Some((settings, spectrogram_image(&samples, &settings)));
+                    self.spectrogram_texture = None;
+                    self.recomputed = true;
+                }
+                if let Some((_, Some(image))) = &self.spectrogram_cache {
+                    // テクスチャは画像が変わったときだけ作り直す
+                    let texture = self.spectrogram_texture.get_or_insert_with(|| {
+                        ctx.load_texture(
+                            "spectrogram",
+                            image.clone(),
+                            egui::TextureOptions::NEAREST,
+                        )
                     });
 
-                    // 変調タイプ別の変調強度パラメータ
-                    ui.horizontal(|ui| match self.mod_type {
-                        ModulationType::AM => {
-                            ui.label("Mod Index (0-1):");
-                            ui.add(
-                                egui::DragValue::new(&mut self.am_mod_index)
-                                    .speed(0.01)
-                                    .range(0.0..=10.0),
-                            );
-                        }
-                        ModulationType::FM => {
-                            ui.label("Deviation (Hz):");
-                            ui.add(egui::DragValue::new(&mut self.fm_deviation).speed(10.0));
-                        }
-                        ModulationType::PM => {
-                            ui.label("Mod Index (Beta):");
-                            ui.add(
-                                egui::DragValue::new(&mut self.pm_mod_index)
-                                    .speed(0.01)
-                                    .range(0.0..=100.0),
-                            );
-                        }
-                        ModulationType::Pulse => {
-                            ui.label("Duty Cycle (0-1):");
-                            ui.add(
-                                egui::DragValue::new(&mut self.pulse_duty_cycle)
-                                    .speed(0.01)
-                                    .range(0.0..=1.0),
-                            );
-                        }
-                        _ => {}
-                    });
+                    // 横軸は時間（時間領域プロットと同じ単位）、縦軸は周波数
+                    let hop = settings.size - settings.overlap;
+                    let time_scale = match self.time_domain_unit {
+                        TimeDomainUnit::Seconds => 1.0 / self.sample_rate,
+                        TimeDomainUnit::Samples => 1.0,
+                    };
+                    let duration = (image.size[0] * hop) as f64 * time_scale;
+                    let texture_id = texture.id();
+                    Plot::new("spectrogram")
+                        .height(plot_height)
+                        .show(ui, |plot_ui| {
+                            plot_ui.image(PlotImage::new(
+                                texture_id,
+                                PlotPoint::new(duration / 2.0, 0.0),
+                                [duration as f32, self.sample_rate as f32],
+                            ));
+                        });
+                }
+            }
+        });
+    }
+}
+
+/// サンプルブロックの再生成が必要かどうかを判定
+///
+/// 振幅・表示設定・窓関数など`SignalParams`に含まれない設定は、
+/// 生成済みのブロックやスペクトラムのキャッシュを使い回して反映します。
+///
+/// # 引数
+/// * `old_params` - 前回ブロック生成時のパラメータ（未生成の場合はNone）
+/// * `new_params` - 現在のパラメータ
+///
+/// # 戻り値
+/// 生成に影響するパラメータが変更された場合はtrue
+fn needs_regen(old_params: Option<&SignalParams>, new_params: &SignalParams) -> bool {
+    old_params != Some(new_params)
+}
+
+/// スペクトラムの点列から正の周波数のビンだけを取り出し、周波数をlog10に変換します。
+///
+/// DCのビンは対数軸に置けないため、最初の0でない周波数から始まります。
+fn to_log_freq(points: &[[f64; 2]]) -> Vec<[f64; 2]> {
+    points
+        .iter()
+        .filter(|p| p[0] > 0.0)
+        .map(|&[freq, mag]| [freq.log10(), mag])
+        .collect()
+}
+
+/// 対数周波数軸の目盛り（log10(周波数)）を単位付きの周波数として表示します。
+fn format_log_freq(log_freq: f64) -> String {
+    let freq = 10f64.powf(log_freq);
+    let (value, unit) = if freq >= 1e6 {
+        (freq / 1e6, "MHz")
+    } else if freq >= 1e3 {
+        (freq / 1e3, "kHz")
+    } else {
+        (freq, "Hz")
+    };
+    // 有効数字3桁で表示
+    let decimals = (2 - value.log10().floor() as i32).max(0) as usize;
+    format!("{:.*} {}", decimals, value, unit)
+}
+
+/// 最大値保持トレースを新しいスペクトラムの電力で更新
+///
+/// ビン数が異なる（サンプル数やゼロ詰めの変更、クリア後）場合は新しい電力で置き換えます。
+///
+/// # 引数
+/// * `trace` - 各ビンの電力の最大値
+/// * `power` - 新しく計算したスペクトラムの各ビンの電力
+fn update_max_hold(trace: &mut Vec<f64>, power: &[f64]) {
+    if trace.len() != power.len() {
+        *trace = power.to_vec();
+        return;
+    }
+    for (held, p) in trace.iter_mut().zip(power) {
+        *held = held.max(*p);
+    }
+}
+
+/// 指定した周波数に最も近いスペクトラムのビンを探してマーカーを作成
+///
+/// # 引数
+/// * `points` - 周波数順に並べた `[周波数, 振幅]` の点列
+/// * `freq` - マーカーを置く周波数 (Hz)
+///
+/// # 戻り値
+/// 最寄りのビンの周波数と振幅を持つマーカー。点列が空の場合はNone
+fn snap_to_bin(points: &[[f64; 2]], freq: f64) -> Option<Marker> {
+    let nearest = points[nearest_bin(points, freq)?];
+    Some(Marker {
+        freq: nearest[0],
+        mag: nearest[1],
+    })
+}
+
+/// 指定した周波数に最も近いビンから、振幅が大きくなる方向にたどった極大のビンにマーカーを作成
+///
+/// 線形/dBのどちらのスケールでも同じピークに吸着します。
+///
+/// # 引数
+/// * `points` - 周波数順に並べた `[周波数, 振幅]` の点列
+/// * `freq` - マーカーを置く周波数 (Hz)
+///
+/// # 戻り値
+/// 極大のビンの周波数と振幅を持つマーカー。点列が空の場合はNone
+fn snap_to_peak(points: &[[f64; 2]], freq: f64) -> Option<Marker> {
+    let mut idx = nearest_bin(points, freq)?;
+    loop {
+        let left = idx
+            .checked_sub(1)
+            .filter(|&i| points[i][1] > points[idx][1]);
+        let right = Some(idx + 1).filter(|&i| i < points.len() && points[i][1] > points[idx][1]);
+        // 両側が大きい場合は大きい方へ進む
+        idx = match (left, right) {
+            (Some(l), Some(r)) if points[l][1] >= points[r][1] => l,
+            (_, Some(r)) => r,
+            (Some(l), None) => l,
+            (None, None) => break,
+        };
+    }
+    Some(Marker {
+        freq: points[idx][0],
+        mag: points[idx][1],
+    })
+}
+
+/// 指定した周波数に最も近いビンの番号を探す（点列が空の場合はNone）
+fn nearest_bin(points: &[[f64; 2]], freq: f64) -> Option<usize> {
+    let idx = points.partition_point(|p| p[0] < freq);
+    // 挟んでいる2つのビンのうち近い方を選ぶ
+    [idx.checked_sub(1), Some(idx)]
+        .into_iter()
+        .flatten()
+        .filter(|&i| i < points.len())
+        .min_by(|&a, &b| {
+            (points[a][0] - freq)
+                .abs()
+                .total_cmp(&(points[b][0] - freq).abs())
+        })
+}
+
+/// 生成したサンプルに振幅と出力形式を適用
+///
+/// 実信号ではQ成分を0にするため、スペクトラムはエルミート対称になります。
+///
+/// # 引数
+/// * `raw` - 振幅適用前のサンプル配列
+/// * `amplitude` - 信号振幅
+/// * `mode` - 出力信号の形式（複素IQ/実信号）
+///
+/// # 戻り値
+/// 表示とエクスポートに使用するサンプル配列
+fn scale_samples(raw: &[Complex<f64>], amplitude: f64, mode: OutputMode) -> Vec<Complex<f64>> {
+    raw.iter()
+        .map(|s| match mode {
+            OutputMode::Complex => s * amplitude,
+            OutputMode::Real => Complex::new(s.re * amplitude, 0.0),
+        })
+        .collect()
+}
+
+/// 振幅適用後のサンプルに加える出力段の不完全性
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+struct OutputImpairments {
+    /// DACの量子化ビット数（Noneの場合は量子化しない）
+    quantization_bits: Option<u8>,
+    /// クリップの (モデル, レベル, tanhの利得)（Noneの場合はクリップしない）
+    clip: Option<(SaturationModel, f64, f64)>,
+    /// Salehモデルの増幅器のパラメータ（Noneの場合は増幅器を通さない）
+    saleh: Option<SalehParams>,
+}
+
+impl OutputImpairments {
+    /// 振幅適用後のサンプルに不完全性を適用
+    fn apply(&self, samples: &mut [Complex<f64>]) {
+        if let Some(bits) = self.quantization_bits {
+            quantize(samples, bits);
+        }
+        if let Some(params) = &self.saleh {
+            for sample in samples.iter_mut() {
+                *sample = saleh(*sample, params);
+            }
+        }
+        if let Some((model, level, gain)) = self.clip {
+            saturate(samples, model, level, gain);
+        }
+    }
+}
+
+/// コンスタレーション表示で描画する最大点数
+const MAX_CONSTELLATION_POINTS: usize = 20000;
+
+/// ベクトルスコープ表示で軌跡の濃さを変える区間の数（古いサンプルほど薄く描く）
+const POLAR_FADE_SEGMENTS: usize = 8;
+
+/// ベクトルスコープ表示用に、搬送波の回転を取り除いたサンプルの点列を計算
+///
+/// n番目のサンプルに e^{-j2πf_c(n+1)/fs} を掛けて搬送波の位相（生成器の位相と同じく
+/// 1サンプル目で2πf_c/fs）を取り除き、変調による振幅と位相の変化だけを残します。
+/// サンプル数が`max_points`を超える場合は一定間隔で間引きます。
+///
+/// # 引数
+/// * `samples` - 複素数サンプル配列
+/// * `carrier` - 取り除く搬送波周波数 (Hz)
+/// * `sample_rate` - サンプリングレート (Hz)
+/// * `max_points` - 返す点の最大数
+///
+/// # 戻り値
+/// `[振幅·cos(位相), 振幅·sin(位相)]`の点列（時間順）
+fn polar_points(
+    samples: &[Complex<f64>],
+    carrier: f64,
+    sample_rate: f64,
+    max_points: usize,
+) -> Vec<[f64; 2]> {
+    let step = samples.len().div_ceil(max_points.max(1)).max(1);
+    samples
+        .iter()
+        .enumerate()
+        .step_by(step)
+        .map(|(n, s)| {
+            let phase = 2.0 * std::f64::consts::PI * carrier * (n + 1) as f64 / sample_rate;
+            let v = s * Complex::from_polar(1.0, -phase);
+            [v.re, v.im]
+        })
+        .collect()
+}
+
+/// コンスタレーション表示用に、サンプルをI/Q平面上の点列に変換
+///
+/// サンプル数が`max_points`を超える場合は一定間隔で間引きます。
+///
+/// # 引数
+/// * `samples` - 複素数サンプル配列
+/// * `max_points` - 返す点の最大数
+///
+/// # 戻り値
+/// `[I, Q]`の点列
+fn constellation_points(samples: &[Complex<f64>], max_points: usize) -> Vec<[f64; 2]> {
+    let step = samples.len().div_ceil(max_points.max(1)).max(1);
+    samples.iter().step_by(step).map(|s| [s.re, s.im]).collect()
+}
+
+/// サンプルをCSV形式でエクスポート
+///
+/// I/Q成分を2列のCSVファイルとして出力します。実信号ではI成分の1列のみを出力します。
+/// `time_column`を指定しない場合、ヘッダー行は含みません。
+/// 指定した場合は先頭に時間（秒）またはサンプル番号の列を追加し、
+/// ヘッダー行（`t,I,Q`または`n,I,Q`、実信号では`t,I`または`n,I`）を出力します。
+///
+/// # 引数
+/// * `path` - 出力先ファイルパス
+/// * `samples` - エクスポートする複素数サンプル配列
+/// * `time_column` - 追加する時間列の単位（Noneの場合は追加しない）
+/// * `sample_rate` - 時間を秒で出力する際のサンプリングレート (Hz)
+/// * `mode` - 出力信号の形式（複素IQ/実信号）
+///
+/// # 戻り値
+/// 成功時はOk(())、失敗時はエラー
+fn export_to_csv(
+    path: &std::path::Path,
+    samples: &[Complex<f64>],
+    time_column: Option<TimeDomainUnit>,
+    sample_rate: f64,
+    mode: OutputMode,
+) -> std::io::Result<()> {
+    let mut wtr = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)?;
+    write_csv_header(&mut wtr, time_column, mode)?;
+    write_csv_rows(&mut wtr, samples, 0, time_column, sample_rate, mode)?;
+    wtr.flush()?;
+    Ok(())
+}
+
+/// スペクトラムをCSV形式でエクスポート
+///
+/// ヘッダー行`frequency,magnitude`に続けて、周波数順に周波数 (Hz) と振幅を1行ずつ出力します。
+/// 振幅は周波数領域プロットと同じ値（窓関数・平均化・表示スケールを適用済み）です。
+///
+/// # 引数
+/// * `path` - 出力先ファイルパス
+/// * `points` - `compute_spectrum`で計算した `[周波数, 振幅]` の点列
+///
+/// # 戻り値
+/// 成功時はOk(())、失敗時はエラー
+fn export_spectrum_to_csv(path: &std::path::Path, points: &[[f64; 2]]) -> std::io::Result<()> {
+    let mut wtr = csv::Writer::from_path(path)?;
+    wtr.write_record(["frequency", "magnitude"])?;
+    for [freq, mag] in points {
+        wtr.write_record([freq.to_string(), mag.to_string()])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// CSVのヘッダー行を書き込む（時間列を含めない場合は何もしない）
+fn write_csv_header<W: std::io::Write>(
+    wtr: &mut csv::Writer<W>,
+    time_column: Option<TimeDomainUnit>,
+    mode: OutputMode,
+) -> csv::Result<()> {
+    let Some(unit) = time_column else {
+        return Ok(());
+    };
+    let time_header = match unit {
+        TimeDomainUnit::Seconds => "t",
+        TimeDomainUnit::Samples => "n",
+    };
+    let header = ["I", "Q"].into_iter().take(mode.channels());
+    wtr.write_record(std::iter::once(time_header).chain(header))
+}
+
+/// サンプルをCSVの行として書き込む
+///
+/// # 引数
+/// * `wtr` - 書き込み先のCSVライター
+/// * `samples` - 書き込むサンプル配列
+/// * `first_index` - 先頭のサンプルの通し番号（時間列の計算に使用）
+/// * `time_column` - 追加する時間列の単位（Noneの場合は追加しない）
+/// * `sample_rate` - 時間を秒で出力する際のサンプリングレート (Hz)
+/// * `mode` - 出力信号の形式（複素IQ/実信号）
+fn write_csv_rows<W: std::io::Write>(
+    wtr: &mut csv::Writer<W>,
+    samples: &[Complex<f64>],
+    first_index: usize,
+    time_column: Option<TimeDomainUnit>,
+    sample_rate: f64,
+    mode: OutputMode,
+) -> csv::Result<()> {
+    // 1行分の値（実信号ではI成分のみ）
+    let values = |sample: &Complex<f64>| match mode {
+        OutputMode::Complex => vec![sample.re.to_string(), sample.im.to_string()],
+        OutputMode::Real => vec![sample.re.to_string()],
+    };
+
+    for (i, sample) in (first_index..).zip(samples) {
+        match time_column {
+            None => wtr.write_record(values(sample))?,
+            Some(unit) => {
+                let time = match unit {
+                    TimeDomainUnit::Seconds => (i as f64 / sample_rate).to_string(),
+                    TimeDomainUnit::Samples => i.to_string(),
+                };
+                wtr.write_record(std::iter::once(time).chain(values(sample)))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// サンプルをバイナリ形式でエクスポート
+///
+/// I/Q成分をインターリーブし、指定された形式（リトルエンディアン）で出力します。
+/// 32ビット浮動小数点では各サンプルは8バイト（I: 4バイト + Q: 4バイト）で表現されます。
+/// 実信号ではI成分のみを出力するため、各サンプルは半分のバイト数になります。
+/// 整数形式では振幅1.0を`full_scale`に対応させ、型の範囲外の値はクリップします。
+///
+/// # 引数
+/// * `path` - 出力先ファイルパス
+/// * `samples` - エクスポートする複素数サンプル配列
+/// * `format` - サンプル形式
+/// * `full_scale` - 振幅1.0に対応させる整数値（浮動小数点形式では無視）
+/// * `mode` - 出力信号の形式（複素IQ/実信号）
+///
+/// # 戻り値
+/// 成功時はOk(())、失敗時はエラー
+fn export_to_bin(
+    path: &std::path::Path,
+    samples: &[Complex<f64>],
+    format: BinFormat,
+    full_scale: f64,
+    mode: OutputMode,
+) -> std::io::Result<()> {
+    use std::io::Write;
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let clipped = write_bin_values(&mut writer, samples, format, full_scale, mode)?;
+    warn_clipped(clipped, samples.len() * mode.channels());
+    writer.flush()
+}
+
+/// サンプルをバイナリ形式（リトルエンディアン）で書き込む
+///
+/// # 引数
+/// * `writer` - 書き込み先
+/// * `samples` - 書き込むサンプル配列
+/// * `format` - サンプル形式
+/// * `full_scale` - 振幅1.0に対応させる整数値（浮動小数点形式では無視）
+/// * `mode` - 出力信号の形式（複素IQ/実信号）
+///
+/// # 戻り値
+/// 成功時はフルスケールでクリップした値の数、失敗時はエラー
+fn write_bin_values<W: std::io::Write>(
+    writer: &mut W,
+    samples: &[Complex<f64>],
+    format: BinFormat,
+    full_scale: f64,
+    mode: OutputMode,
+) -> std::io::Result<usize> {
+    // 整数形式への変換：フルスケールでスケーリングし、型の範囲にクリップ
+    let max = format.max_value();
+    let mut clipped = 0usize;
+    let mut quantize = |v: f64| -> f64 {
+        let scaled = (v * full_scale).round();
+        if scaled.abs() > max {
+            clipped += 1;
+        }
+        scaled.clamp(-max, max)
+    };
+
+    let mut push = |writer: &mut W, v: f64| match format {
+        // f64をf32に変換してリトルエンディアンでバイト列化
+        BinFormat::F32 => writer.write_all(&(v as f32).to_le_bytes()),
+        BinFormat::F64 => writer.write_all(&v.to_le_bytes()),
+        BinFormat::I16 => writer.write_all(&(quantize(v) as i16).to_le_bytes()),
+        BinFormat::I8 => writer.write_all(&(quantize(v) as i8).to_le_bytes()),
+    };
+
+    for sample in samples {
+        push(writer, sample.re)?;
+        if mode == OutputMode::Complex {
+            push(writer, sample.im)?;
+        }
+    }
+    Ok(clipped)
+}
+
+/// フルスケールでクリップした値があれば警告を表示
+fn warn_clipped(clipped: usize, total: usize) {
+    if clipped > 0 {
+        eprintln!(
+            "Warning: {} of {} values were clipped to full scale",
+            clipped, total
+        );
+    }
+}
+
+/// サンプルをNumPyの.npy形式でエクスポート
+///
+/// `numpy.load`で読み込める形状`(N,)`の`complex64`配列として出力します。
+/// ヘッダー（マジック文字列、バージョン1.0、dtype・形状を記述した辞書）の後に、
+/// `export_to_bin`と同じインターリーブされた32ビット浮動小数点のI/Qデータが続きます。
+///
+/// # 引数
+/// * `path` - 出力先ファイルパス
+/// * `samples` - エクスポートする複素数サンプル配列
+///
+/// # 戻り値
+/// 成功時はOk(())、失敗時はエラー
+fn export_to_npy(path: &std::path::Path, samples: &[Complex<f64>]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    // ヘッダー全体（マジック6 + バージョン2 + 長さ2 + 辞書）が64バイトの倍数になるよう
+    // 空白で埋め、改行で終える
+    let mut header = format!(
+        "{{'descr': '<c8', 'fortran_order': False, 'shape': ({},), }}",
+        samples.len()
+    );
+    let unpadded = 10 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+    header.push('\n');
+
+    let mut buffer = Vec::with_capacity(10 + header.len() + samples.len() * 8);
+    buffer.extend_from_slice(b"\x93NUMPY");
+    buffer.extend_from_slice(&[1, 0]);
+    buffer.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    buffer.extend_from_slice(header.as_bytes());
+    for sample in samples {
+        buffer.extend_from_slice(&(sample.re as f32).to_le_bytes());
+        buffer.extend_from_slice(&(sample.im as f32).to_le_bytes());
+    }
+
+    let mut file = std::fs::File::create(path)?;
+    file.write_all(&buffer)?;
+    Ok(())
+}
+
+/// サンプルをMATLABのLevel 5 MATファイル（.mat）でエクスポート
+///
+/// `load`で読み込める、複素数の倍精度列ベクトル`iq`（N×1）と
+/// サンプリングレートのスカラー`fs`を出力します。
+/// 32ビット浮動小数点に丸めないため、生成したサンプルをそのまま解析に使えます。
+///
+/// # 引数
+/// * `path` - 出力先ファイルパス
+/// * `samples` - エクスポートする複素数サンプル配列
+/// * `sample_rate` - サンプリングレート (Hz)
+///
+/// # 戻り値
+/// 成功時はOk(())、失敗時はエラー
+fn export_to_mat(
+    path: &std::path::Path,
+    samples: &[Complex<f64>],
+    sample_rate: f64,
+) -> std::io::Result<()> {
+    use std::io::Write;
+
+    // 128バイトのヘッダー：説明文（116バイト）、サブシステムデータの位置（8バイト）、
+    // バージョン0x0100、エンディアン識別子（リトルエンディアンでは"IM"）
+    let mut header = format!(
+        "MATLAB 5.0 MAT-file, Platform: {}, Created by: IVSG",
+        std::env::consts::OS
+    )
+    .into_bytes();
+    header.resize(116, b' ');
+    header.extend_from_slice(&[0; 8]);
+    header.extend_from_slice(&0x0100u16.to_le_bytes());
+    header.extend_from_slice(b"IM");
+
+    let mut buffer = header;
+    let real: Vec<f64> = samples.iter().map(|s| s.re).collect();
+    let imag: Vec<f64> = samples.iter().map(|s| s.im).collect();
+    write_mat_array(&mut buffer, "iq", &real, Some(&imag));
+    write_mat_array(&mut buffer, "fs", &[sample_rate], None);
+
+    let mut file = std::fs::File::create(path)?;
+    file.write_all(&buffer)?;
+    Ok(())
+}
+
+/// MATファイルの倍精度の列ベクトル（miMATRIX要素）を書き込む
+///
+/// # 引数
+/// * `buffer` - 書き込み先
+/// * `name` - 変数名
+/// * `real` - 実部
+/// * `imag` - 虚部（実数の配列ではNone）
+fn write_mat_array(buffer: &mut Vec<u8>, name: &str, real: &[f64], imag: Option<&[f64]>) {
+    // MATファイルのデータ型
+    const MI_INT8: u32 = 1;
+    const MI_INT32: u32 = 5;
+    const MI_UINT32: u32 = 6;
+    const MI_DOUBLE: u32 = 9;
+    const MI_MATRIX: u32 = 14;
+    // 配列フラグのクラス（倍精度）と複素数フラグ
+    const MX_DOUBLE_CLASS: u32 = 6;
+    const COMPLEX_FLAG: u32 = 0x0800;
+
+    // データ要素（タグ8バイト + データ、8バイト境界まで0で埋める）
+    let element = |data_type: u32, data: &[u8]| {
+        let mut element = Vec::with_capacity(8 + data.len().next_multiple_of(8));
+        element.extend_from_slice(&data_type.to_le_bytes());
+        element.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        element.extend_from_slice(data);
+        element.resize(8 + data.len().next_multiple_of(8), 0);
+        element
+    };
+    let doubles =
+        |values: &[f64]| -> Vec<u8> { values.iter().flat_map(|v| v.to_le_bytes()).collect() };
+
+    let flags = MX_DOUBLE_CLASS | if imag.is_some() { COMPLEX_FLAG } else { 0 };
+    let dims: Vec<u8> = [real.len() as i32, 1]
+        .iter()
+        .flat_map(|d| d.to_le_bytes())
+        .collect();
+    let mut matrix = element(
+        MI_UINT32,
+        &[flags.to_le_bytes(), 0u32.to_le_bytes()].concat(),
+    );
+    matrix.extend(element(MI_INT32, &dims));
+    matrix.extend(element(MI_INT8, name.as_bytes()));
+    matrix.extend(element(MI_DOUBLE, &doubles(real)));
+    if let Some(imag) = imag {
+        matrix.extend(element(MI_DOUBLE, &doubles(imag)));
+    }
+    buffer.extend(element(MI_MATRIX, &matrix));
+}
+
+/// サンプルをSigMF形式でエクスポート
+///
+/// `export_to_bin`と同じ形式のI/Qデータを`.sigmf-data`ファイルに、
+/// サンプリングレートやデータ形式、中心周波数、変調方式を記述したメタデータを
+/// `.sigmf-meta`ファイル（JSON）に出力します。
+///
+/// # 引数
+/// * `path` - 出力先ファイルパス（拡張子は`.sigmf-data`/`.sigmf-meta`に置き換えられる）
+/// * `samples` - エクスポートする複素数サンプル配列
+/// * `params` - 信号生成パラメータ（メタデータに記録）
+/// * `format` - サンプル形式
+/// * `full_scale` - 振幅1.0に対応させる整数値（整数形式のみ使用）
+///
+/// # 戻り値
+/// 成功時はOk(())、失敗時はエラー
+fn export_to_sigmf(
+    path: &std::path::Path,
+    samples: &[Complex<f64>],
+    params: &SignalParams,
+    format: BinFormat,
+    full_scale: f64,
+) -> std::io::Result<()> {
+    export_to_bin(
+        &path.with_extension("sigmf-data"),
+        samples,
+        format,
+        full_scale,
+        OutputMode::Complex,
+    )?;
+    write_sigmf_meta(
+        &path.with_extension("sigmf-meta"),
+        None,
+        samples.len(),
+        params,
+        format,
+    )
+}
+
+/// SigMFのメタデータファイル（JSON）を出力
+///
+/// # 引数
+/// * `path` - 出力先の`.sigmf-meta`ファイルパス
+/// * `dataset` - データファイル名（`.sigmf-data`以外の名前の場合に`core:dataset`として記録）
+/// * `num_samples` - データファイルのサンプル数
+/// * `params` - 信号生成パラメータ（メタデータに記録）
+/// * `format` - データファイルのサンプル形式
+///
+/// # 戻り値
+/// 成功時はOk(())、失敗時はエラー
+fn write_sigmf_meta(
+    path: &std::path::Path,
+    dataset: Option<&str>,
+    num_samples: usize,
+    params: &SignalParams,
+    format: BinFormat,
+) -> std::io::Result<()> {
+    let mut meta = serde_json::json!({
+        "global": {
+            "core:datatype": format.sigmf_datatype(),
+            "core:sample_rate": params.sample_rate,
+            "core:version": "1.0.0",
+            "core:recorder": "IVSG",
+        },
+        "captures": [{
+            "core:sample_start": 0,
+            "core:frequency": params.frequency,
+        }],
+        "annotations": [{
+            "core:sample_start": 0,
+            "core:sample_count": num_samples,
+            "core:freq_lower_edge": params.frequency - params.sample_rate / 2.0,
+            "core:freq_upper_edge": params.frequency + params.sample_rate / 2.0,
+            "core:label": format!("{:?}", params.mod_type),
+        }],
+    });
+    if let Some(dataset) = dataset {
+        meta["global"]["core:dataset"] = dataset.into();
+    }
+    let json = serde_json::to_string_pretty(&meta)?;
+    std::fs::write(path, json)
+}
+
+/// サンプルをWAV形式でエクスポート
+///
+/// I成分を左チャンネル、Q成分を右チャンネルとした2チャンネルのWAVファイルを出力します。
+/// 実信号ではI成分のみのモノラルWAVファイルを出力します。
+/// 16ビット整数形式では±1.0をフルスケールとし、範囲外の値はクリップされます。
+///
+/// # 引数
+/// * `path` - 出力先ファイルパス
+/// * `samples` - エクスポートする複素数サンプル配列
+/// * `sample_rate` - WAVヘッダーに記録するサンプリングレート (Hz)
+/// * `format` - サンプル形式（Float: 32ビット浮動小数点、Int: 16ビット整数）
+/// * `mode` - 出力信号の形式（複素IQ/実信号）
+///
+/// # 戻り値
+/// 成功時はOk(())、失敗時はエラー
+fn export_to_wav(
+    path: &std::path::Path,
+    samples: &[Complex<f64>],
+    sample_rate: f64,
+    format: hound::SampleFormat,
+    mode: OutputMode,
+) -> hound::Result<()> {
+    let spec = hound::WavSpec {
+        channels: mode.channels() as u16,
+        sample_rate: sample_rate.round() as u32,
+        bits_per_sample: match format {
+            hound::SampleFormat::Float => 32,
+            hound::SampleFormat::Int => 16,
+        },
+        sample_format: format,
+    };
+    let mut writer = hound::WavWriter::create(path, spec)?;
+
+    match format {
+        hound::SampleFormat::Float => {
+            for sample in samples {
+                writer.write_sample(sample.re as f32)?;
+                if mode == OutputMode::Complex {
+                    writer.write_sample(sample.im as f32)?;
+                }
+            }
+        }
+        hound::SampleFormat::Int => {
+            // ±1.0を超える値をクリップし、クリップされたサンプル数を数える
+            let mut clipped = 0usize;
+            for sample in samples {
+                if sample.re.abs() > 1.0 || sample.im.abs() > 1.0 {
+                    clipped += 1;
                 }
+                let i = (sample.re.clamp(-1.0, 1.0) * i16::MAX as f64).round() as i16;
+                let q = (sample.im.clamp(-1.0, 1.0) * i16::MAX as f64).round() as i16;
+                writer.write_sample(i)?;
+                if mode == OutputMode::Complex {
+                    writer.write_sample(q)?;
+                }
+            }
+            if clipped > 0 {
+                eprintln!(
+                    "Warning: {} of {} samples were clipped to full scale",
+                    clipped,
+                    samples.len()
+                );
+            }
+        }
+    }
+
+    writer.finalize()
+}
+
+/// CSVファイルからIQサンプルを読み込む
+///
+/// 各行をI,Qの2列（1列のみの場合は実信号としてQ=0）として解釈します。
+/// 先頭行が数値でない場合はヘッダーとみなし、`I`・`Q`列の位置を取得します
+/// （`export_to_csv`で時間列付きで出力したファイルもそのまま読み込めます）。
+///
+/// # 引数
+/// * `path` - 読み込むファイルパス
+///
+/// # 戻り値
+/// 成功時はサンプル配列、不正な行がある場合は行番号を含むエラーメッセージ
+fn import_csv(path: &std::path::Path) -> Result<Vec<Complex<f64>>, String> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .map_err(|e| e.to_string())?;
+
+    // I列とQ列の位置（ヘッダーがない場合は先頭から順に）
+    let mut i_col = 0;
+    let mut q_col = Some(1);
+    let mut samples = Vec::new();
+    for (index, record) in rdr.records().enumerate() {
+        let line = index + 1;
+        let record = record.map_err(|e| format!("line {}: {}", line, e))?;
+        if index == 0 && record.get(0).is_some_and(|f| f.parse::<f64>().is_err()) {
+            let find = |name: &str| record.iter().position(|f| f.eq_ignore_ascii_case(name));
+            i_col = find("I").ok_or_else(|| format!("line {}: missing I column", line))?;
+            q_col = find("Q");
+            continue;
+        }
+        let value = |col: usize| -> Result<f64, String> {
+            let field = record
+                .get(col)
+                .ok_or_else(|| format!("line {}: missing column {}", line, col + 1))?;
+            field
+                .parse::<f64>()
+                .map_err(|_| format!("line {}: invalid value '{}'", line, field))
+        };
+        let re = value(i_col)?;
+        let im = match q_col {
+            Some(col) if col < record.len() => value(col)?,
+            _ => 0.0,
+        };
+        samples.push(Complex::new(re, im));
+    }
+
+    if samples.is_empty() {
+        return Err("no samples found".to_string());
+    }
+    Ok(samples)
+}
+
+/// BINファイルからインターリーブされたIQサンプルを読み込む
+///
+/// `export_to_bin`の複素IQ出力と同じリトルエンディアンのI/Q交互配置を想定し、
+/// 整数形式では型の最大値を振幅1.0として正規化します。
+///
+/// # 引数
+/// * `path` - 読み込むファイルパス
+/// * `format` - サンプル形式
+///
+/// # 戻り値
+/// 成功時はサンプル配列、ファイルサイズが不正な場合はエラーメッセージ
+fn import_bin(path: &std::path::Path, format: BinFormat) -> Result<Vec<Complex<f64>>, String> {
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    let width = format.bytes_per_value();
+    if bytes.is_empty() || bytes.len() % (2 * width) != 0 {
+        return Err(format!(
+            "file size {} is not a multiple of {} bytes per {:?} IQ sample",
+            bytes.len(),
+            2 * width,
+            format
+        ));
+    }
+
+    let max = format.max_value();
+    let value = |b: &[u8]| match format {
+        BinFormat::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+        BinFormat::F64 => f64::from_le_bytes(b.try_into().unwrap()),
+        BinFormat::I16 => i16::from_le_bytes([b[0], b[1]]) as f64 / max,
+        BinFormat::I8 => i8::from_le_bytes([b[0]]) as f64 / max,
+    };
+    Ok(bytes
+        .chunks_exact(2 * width)
+        .map(|iq| Complex::new(value(&iq[..width]), value(&iq[width..])))
+        .collect())
+}
+
+/// 0〜1の値をスペクトログラム用の色に変換
+///
+/// 黒 → 青 → 赤 → 黄 → 白 の順に明るくなるカラーマップです。
+///
+/// # 引数
+/// * `t` - 正規化された値（0: 最小、1: 最大）
+///
+/// # 戻り値
+/// 対応する色
+fn colormap(t: f64) -> egui::Color32 {
+    const STOPS: [[f64; 3]; 5] = [
+        [0.0, 0.0, 0.0],
+        [0.0, 0.0, 160.0],
+        [200.0, 0.0, 60.0],
+        [255.0, 200.0, 0.0],
+        [255.0, 255.0, 255.0],
+    ];
+    let x = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f64;
+    let i = (x.floor() as usize).min(STOPS.len() - 2);
+    let f = x - i as f64;
+    let c = |k: usize| (STOPS[i][k] + (STOPS[i + 1][k] - STOPS[i][k]) * f) as u8;
+    egui::Color32::from_rgb(c(0), c(1), c(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// テスト用の一時ファイルパスを生成
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("ivsg_test_{}_{}", std::process::id(), name))
+    }
+
+    /// CSV/BINでエクスポートしたサンプルを読み込めることをテスト
+    #[test]
+    fn test_import_round_trip() {
+        let samples = vec![
+            Complex::new(0.5, -0.25),
+            Complex::new(-1.0, 1.0),
+            Complex::new(0.0, 0.125),
+        ];
+
+        // 時間列とヘッダー付きのCSV
+        let csv_path = temp_path("import.csv");
+        export_to_csv(
+            &csv_path,
+            &samples,
+            Some(TimeDomainUnit::Seconds),
+            1000.0,
+            OutputMode::Complex,
+        )
+        .unwrap();
+        assert_eq!(import_csv(&csv_path).unwrap(), samples);
+
+        // 16ビット整数のBIN（量子化誤差の範囲で一致）
+        let bin_path = temp_path("import.bin");
+        export_to_bin(
+            &bin_path,
+            &samples,
+            BinFormat::I16,
+            BinFormat::I16.max_value(),
+            OutputMode::Complex,
+        )
+        .unwrap();
+        let imported = import_bin(&bin_path, BinFormat::I16).unwrap();
+        assert_eq!(imported.len(), samples.len());
+        for (a, b) in imported.iter().zip(&samples) {
+            assert!((a - b).norm() < 1e-4);
+        }
+
+        let _ = std::fs::remove_file(csv_path);
+        let _ = std::fs::remove_file(bin_path);
+    }
+
+    /// 不正な行やファイルサイズがエラーとして報告されることをテスト
+    #[test]
+    fn test_import_malformed() {
+        let csv_path = temp_path("import_bad.csv");
+        std::fs::write(&csv_path, "0.1,0.2\n0.3,abc\n").unwrap();
+        let err = import_csv(&csv_path).unwrap_err();
+        assert!(err.contains("line 2"), "{}", err);
+
+        let bin_path = temp_path("import_bad.bin");
+        std::fs::write(&bin_path, [0u8; 6]).unwrap();
+        assert!(import_bin(&bin_path, BinFormat::F32).is_err());
+        // F32のIQは1サンプル8バイト：12バイトは途中でサンプルが切れている
+        std::fs::write(&bin_path, [0u8; 12]).unwrap();
+        let err = import_bin(&bin_path, BinFormat::F32).unwrap_err();
+        assert!(err.contains("multiple of 8 bytes"), "{}", err);
+        // 空のファイルもエラー
+        std::fs::write(&bin_path, []).unwrap();
+        assert!(import_bin(&bin_path, BinFormat::F32).is_err());
+
+        let _ = std::fs::remove_file(csv_path);
+        let _ = std::fs::remove_file(bin_path);
+    }
+
+    /// WAVエクスポートでI/Qが左右チャンネルに書き込まれることをテスト
+    #[test]
+    fn test_export_to_wav_stereo_iq() {
+        let samples = vec![
+            Complex::new(0.5, -0.25),
+            Complex::new(-1.0, 1.0),
+            Complex::new(2.0, -3.0),
+        ];
+
+        // 32ビット浮動小数点
+        let path = temp_path("float.wav");
+        export_to_wav(
+            &path,
+            &samples,
+            48000.0,
+            hound::SampleFormat::Float,
+            OutputMode::Complex,
+        )
+        .unwrap();
+        let mut reader = hound::WavReader::open(&path).unwrap();
+        let spec = reader.spec();
+        assert_eq!(spec.channels, 2);
+        assert_eq!(spec.sample_rate, 48000);
+        assert_eq!(spec.bits_per_sample, 32);
+        let values: Vec<f32> = reader.samples::<f32>().map(|v| v.unwrap()).collect();
+        assert_eq!(values, vec![0.5, -0.25, -1.0, 1.0, 2.0, -3.0]);
+        std::fs::remove_file(&path).unwrap();
+
+        // 16ビット整数（±1.0を超える値はクリップされる）
+        let path = temp_path("int.wav");
+        export_to_wav(
+            &path,
+            &samples,
+            48000.0,
+            hound::SampleFormat::Int,
+            OutputMode::Complex,
+        )
+        .unwrap();
+        let mut reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().bits_per_sample, 16);
+        let values: Vec<i16> = reader.samples::<i16>().map(|v| v.unwrap()).collect();
+        assert_eq!(values, vec![16384, -8192, -32767, 32767, 32767, -32767]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// SigMFのメタデータがJSONとして読み戻せ、必須フィールドを含むことをテスト
+    #[test]
+    fn test_export_to_sigmf_metadata() {
+        let params = SignalParams {
+            frequency: 2.4e9,
+            sample_rate: 1e6,
+            mod_type: ModulationType::QPSK,
+            ..Default::default()
+        };
+        let samples = vec![Complex::new(0.5, -0.5); 10];
+
+        let path = temp_path("capture.sigmf-data");
+        export_to_sigmf(&path, &samples, &params, BinFormat::F32, 1.0).unwrap();
+
+        let meta_path = path.with_extension("sigmf-meta");
+        let json = std::fs::read_to_string(&meta_path).unwrap();
+        let meta: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(meta["global"]["core:datatype"], "cf32_le");
+        assert_eq!(meta["global"]["core:sample_rate"], 1e6);
+        assert_eq!(meta["captures"][0]["core:frequency"], 2.4e9);
+        assert_eq!(meta["annotations"][0]["core:label"], "QPSK");
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 10 * 8);
+        assert!(meta["global"].get("core:dataset").is_none());
+
+        // 整数形式ではデータ型とデータ長が選択した形式に従う
+        export_to_sigmf(&path, &samples, &params, BinFormat::I16, 32767.0).unwrap();
+        let meta: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&meta_path).unwrap()).unwrap();
+        assert_eq!(meta["global"]["core:datatype"], "ci16_le");
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 10 * 4);
+
+        // バイナリエクスポートに付随するメタデータはデータファイル名を記録する
+        let bin_meta_path = temp_path("capture_i8.sigmf-meta");
+        write_sigmf_meta(
+            &bin_meta_path,
+            Some("capture_i8.bin"),
+            10,
+            &params,
+            BinFormat::I8,
+        )
+        .unwrap();
+        let meta: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&bin_meta_path).unwrap()).unwrap();
+        assert_eq!(meta["global"]["core:datatype"], "ci8");
+        assert_eq!(meta["global"]["core:dataset"], "capture_i8.bin");
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&meta_path).unwrap();
+        std::fs::remove_file(&bin_meta_path).unwrap();
+    }
+
+    /// 各バイナリ形式のバイト長と、既知のサンプルの変換結果をテスト
+    #[test]
+    fn test_export_to_bin_formats() {
+        let samples = vec![Complex::new(0.5, -0.25), Complex::new(2.0, -1.0)];
+
+        let export = |format: BinFormat, full_scale: f64| {
+            let path = temp_path(&format!("{:?}.bin", format));
+            export_to_bin(&path, &samples, format, full_scale, OutputMode::Complex).unwrap();
+            let bytes = std::fs::read(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(bytes.len(), samples.len() * 2 * format.bytes_per_value());
+            bytes
+        };
+
+        let bytes = export(BinFormat::F32, 1.0);
+        assert_eq!(f32::from_le_bytes(bytes[0..4].try_into().unwrap()), 0.5);
+        assert_eq!(f32::from_le_bytes(bytes[4..8].try_into().unwrap()), -0.25);
+
+        let bytes = export(BinFormat::F64, 1.0);
+        assert_eq!(f64::from_le_bytes(bytes[0..8].try_into().unwrap()), 0.5);
+        assert_eq!(f64::from_le_bytes(bytes[24..32].try_into().unwrap()), -1.0);
+
+        // i16: フルスケール2048で 0.5 → 1024, -0.25 → -512、範囲外の値はクリップ
+        let bytes = export(BinFormat::I16, 2048.0);
+        let values: Vec<i16> = bytes
+            .chunks(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect();
+        assert_eq!(values, vec![1024, -512, 4096, -2048]);
+        let bytes = export(BinFormat::I16, i16::MAX as f64);
+        assert_eq!(i16::from_le_bytes([bytes[4], bytes[5]]), i16::MAX);
+
+        // i8: フルスケール127で 0.5 → 64（四捨五入）、2.0 → 127（クリップ）
+        let bytes = export(BinFormat::I8, 127.0);
+        let values: Vec<i8> = bytes.iter().map(|&b| b as i8).collect();
+        assert_eq!(values, vec![64, -32, 127, -127]);
+    }
+
+    /// NPYファイルのヘッダーが形状(N,)・dtype complex64として解析できることをテスト
+    #[test]
+    fn test_export_to_npy_header() {
+        let samples: Vec<Complex<f64>> = (0..5).map(|i| Complex::new(i as f64, -1.0)).collect();
+
+        let path = temp_path("array.npy");
+        export_to_npy(&path, &samples).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(&bytes[0..6], b"\x93NUMPY");
+        assert_eq!(&bytes[6..8], &[1, 0]);
+        let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        let data_start = 10 + header_len;
+        assert_eq!(data_start % 64, 0);
+
+        let header = std::str::from_utf8(&bytes[10..data_start]).unwrap();
+        assert!(header.ends_with('\n'));
+        assert!(header.contains("'descr': '<c8'"));
+        assert!(header.contains("'fortran_order': False"));
+        assert!(header.contains("'shape': (5,)"));
+        assert_eq!(bytes.len() - data_start, samples.len() * 8);
+
+        // データはnumpyのcomplex64と同じ、リトルエンディアンのfloat32のI/Q交互配置
+        let data: Vec<Complex<f64>> = bytes[data_start..]
+            .chunks_exact(8)
+            .map(|iq| {
+                let value = |b: &[u8]| f32::from_le_bytes(b.try_into().unwrap()) as f64;
+                Complex::new(value(&iq[..4]), value(&iq[4..]))
+            })
+            .collect();
+        assert_eq!(data, samples);
+
+        // 形状の桁数が変わってもデータの開始位置は64バイト境界
+        let path = temp_path("array_long.npy");
+        export_to_npy(&path, &vec![Complex::new(0.0, 0.0); 123456]).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+        assert_eq!(bytes.len() - 10 - header_len, 123456 * 8);
+    }
+
+    /// MATファイルのヘッダーと、読み戻した変数iq・fsの内容をテスト
+    #[test]
+    fn test_export_to_mat() {
+        let samples: Vec<Complex<f64>> = (0..5)
+            .map(|i| Complex::new(i as f64 * 0.1, -1.0 / (i + 1) as f64))
+            .collect();
+        let path = temp_path("array.mat");
+        export_to_mat(&path, &samples, 48000.0).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(bytes.starts_with(b"MATLAB 5.0 MAT-file"));
+        assert_eq!(&bytes[124..128], &[0x00, 0x01, b'I', b'M']);
+
+        // データ要素を順に読み出す
+        let u32_at = |pos: usize| u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap());
+        let mut variables = Vec::new();
+        let mut pos = 128;
+        while pos < bytes.len() {
+            assert_eq!(u32_at(pos), 14, "miMATRIX");
+            let end = pos + 8 + u32_at(pos + 4) as usize;
+            let mut sub = pos + 8;
+            let mut parts = Vec::new();
+            while sub < end {
+                let len = u32_at(sub + 4) as usize;
+                parts.push((u32_at(sub), bytes[sub + 8..sub + 8 + len].to_vec()));
+                sub += 8 + len.next_multiple_of(8);
+            }
+            assert_eq!(sub, end);
+            variables.push(parts);
+            pos = end;
+        }
+        let doubles = |data: &[u8]| -> Vec<f64> {
+            data.chunks_exact(8)
+                .map(|b| f64::from_le_bytes(b.try_into().unwrap()))
+                .collect()
+        };
+
+        let [iq, fs] = variables.as_slice() else {
+            panic!("expected 2 variables");
+        };
+        // iq: 複素数の倍精度 5×1
+        assert_eq!(iq[0].1[..4], (6u32 | 0x0800).to_le_bytes());
+        assert_eq!(iq[1].1, [5i32.to_le_bytes(), 1i32.to_le_bytes()].concat());
+        assert_eq!(iq[2].1, b"iq");
+        let real = doubles(&iq[3].1);
+        let imag = doubles(&iq[4].1);
+        assert_eq!(real.len(), samples.len());
+        for ((re, im), s) in real.iter().zip(&imag).zip(&samples) {
+            assert_eq!(Complex::new(*re, *im), *s);
+        }
+        // fs: 実数のスカラー
+        assert_eq!(fs.len(), 4);
+        assert_eq!(fs[0].1[..4], 6u32.to_le_bytes());
+        assert_eq!(fs[2].1, b"fs");
+        assert_eq!(doubles(&fs[3].1), [48000.0]);
+    }
+
+    /// CSVエクスポートの時間列とヘッダー行をテスト
+    #[test]
+    fn test_export_to_csv_time_column() {
+        let samples = vec![Complex::new(1.0, 0.0); 4];
+        let sample_rate = 1000.0;
+
+        let read_rows = |time_column: Option<TimeDomainUnit>| {
+            let path = temp_path("time.csv");
+            export_to_csv(
+                &path,
+                &samples,
+                time_column,
+                sample_rate,
+                OutputMode::Complex,
+            )
+            .unwrap();
+            let csv = std::fs::read_to_string(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            csv.lines()
+                .map(|line| line.split(',').map(str::to_string).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        // デフォルトはヘッダーなしの2列
+        let rows = read_rows(None);
+        assert_eq!(rows.len(), 4);
+        assert!(rows.iter().all(|r| r.len() == 2));
+
+        // 時間（秒）の列付き
+        let rows = read_rows(Some(TimeDomainUnit::Seconds));
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[0], vec!["t", "I", "Q"]);
+        assert_eq!(rows[1][0].parse::<f64>().unwrap(), 0.0);
+        assert_eq!(rows[2][0].parse::<f64>().unwrap(), 1.0 / sample_rate);
+
+        // サンプル番号の列付き
+        let rows = read_rows(Some(TimeDomainUnit::Samples));
+        assert_eq!(rows[0], vec!["n", "I", "Q"]);
+        assert_eq!(rows[4][0], "3");
+    }
+
+    /// ワーカーで生成したブロックが同じスレッドで生成したブロックと一致し、
+    /// "Next Block"では位相を保持したまま続きを生成することをテスト
+    #[test]
+    fn test_generation_worker() {
+        let params = SignalParams {
+            mod_type: ModulationType::FM,
+            mod_freq: 100.0,
+            mod_strength: 500.0,
+            ..Default::default()
+        };
+        let mut generator = SignalGenerator::new();
+        let first = generator.generate_block(&params, 1000);
+        let second = generator.generate_block(&params, 1000);
+
+        // 生成のみ（振幅1.0、解析なし）
+        let raw_only = BlockAnalysis {
+            scaling: (1.0, OutputMode::Complex, OutputImpairments::default()),
+            spectrum: None,
+            spectrogram: None,
+        };
+        let mut worker = GenerationWorker::start(egui::Context::default());
+        assert!(!worker.is_busy());
+        assert!(worker.wait().is_none());
+        worker.request(params.clone(), 1000, true, raw_only.clone());
+        assert_eq!(worker.wait().unwrap().raw, first);
+        worker.request(params.clone(), 1000, false, raw_only.clone());
+        assert_eq!(worker.wait().unwrap().raw, second);
+        assert!(!worker.is_busy());
+
+        // 続けて要求した場合は最新の要求のブロックを受け取り、古い結果は残らない
+        worker.request(SignalParams::default(), 10, true, raw_only.clone());
+        worker.request(params.clone(), 1000, false, raw_only.clone());
+        assert_eq!(worker.wait().unwrap().raw.len(), 1000);
+        assert!(worker.try_take().is_none());
+
+        // 完了を待たずに取り出す場合も、古い要求の結果は返さない
+        worker.request(SignalParams::default(), 10, true, raw_only.clone());
+        worker.request(params.clone(), 1000, true, raw_only);
+        let mut taken = Vec::new();
+        while worker.is_busy() {
+            taken.extend(worker.try_take().map(|block| block.raw));
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(taken, vec![first]);
+    }
+
+    /// ワーカーで計算した振幅適用後のサンプル・スペクトラム・測定値・スペクトログラムが、
+    /// UIスレッドで同じ設定から計算したものと一致することをテスト
+    #[test]
+    fn test_generation_worker_analysis() {
+        let mut app = MyApp {
+            amplitude: 0.5,
+            mod_type: ModulationType::Multitone,
+            notch_width_tones: 2,
+            show_spectrogram: true,
+            window_function: WindowFunction::Hann,
+            ..Default::default()
+        };
+        let params = app.signal_params();
+        let mut worker = GenerationWorker::start(egui::Context::default());
+        worker.request(params.clone(), 4096, true, app.block_analysis());
+        let block = worker.wait().unwrap();
+
+        let raw = SignalGenerator::new().generate_block(&params, 4096);
+        let samples = scale_samples(&raw, 0.5, OutputMode::Complex);
+        assert_eq!(block.raw, raw);
+        assert_eq!(block.samples, samples);
+        assert_eq!(block.scaling.0, 0.5);
+
+        let spectrum = block.spectrum.unwrap();
+        let (points, power) = app.compute_spectrum(&samples);
+        assert!(spectrum.settings == app.spectrum_settings());
+        assert_eq!(*spectrum.points, points);
+        assert_eq!(spectrum.power, power);
+        let measurement = app.measurement_settings();
+        assert!(measurement.npr_tones.is_some());
+        assert_eq!(spectrum.measurements.0, measurement);
+        let expected = Measurements::compute(&power, &app.spectrum_settings(), &measurement);
+        assert_eq!(spectrum.measurements.1.npr, expected.npr);
+        assert_eq!(spectrum.measurements.1.obw, expected.obw);
+
+        let (settings, image) = block.spectrogram.unwrap();
+        assert_eq!(settings, app.spectrogram_settings());
+        assert_eq!(image, spectrogram_image(&samples, &settings));
+
+        // 表示していないプロットは計算しない
+        app.show_freq_domain = false;
+        app.show_spectrogram = false;
+        worker.request(params, 4096, false, app.block_analysis());
+        let block = worker.wait().unwrap();
+        assert!(block.spectrum.is_none() && block.spectrogram.is_none());
+    }
+
+    /// TCPストリーミングがブロック間で位相の連続したF32のIQを送信し、停止できることをテスト
+    #[test]
+    fn test_tcp_streaming() {
+        use std::io::Read;
+        let params = SignalParams {
+            frequency: 1234.5,
+            sample_rate: 1e7,
+            ..Default::default()
+        };
+        let settings = (
+            params.clone(),
+            0.5,
+            OutputMode::Complex,
+            OutputImpairments::default(),
+        );
+        let mut streamer = TcpStreamer::start(DEFAULT_STREAM_BIND, 0, settings).unwrap();
+        // デフォルトではローカルホストのみで待ち受ける
+        assert!(streamer.addr.ip().is_loopback());
+        assert_eq!(streamer.status(), StreamStatus::Listening);
+
+        // 3ブロック分を受信し、一度に生成した信号と比較する
+        let num_samples = STREAM_BLOCK_SIZE * 3;
+        let mut client = std::net::TcpStream::connect(streamer.addr).unwrap();
+        let mut bytes = vec![0u8; num_samples * 8];
+        client.read_exact(&mut bytes).unwrap();
+        let received: Vec<f32> = bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect();
+        let expected = SignalGenerator::new().generate_block(&params, num_samples);
+        for (iq, sample) in received.chunks_exact(2).zip(&expected) {
+            assert_eq!(iq[0], (sample.re * 0.5) as f32);
+            assert_eq!(iq[1], (sample.im * 0.5) as f32);
+        }
+        assert_eq!(
+            streamer.status(),
+            StreamStatus::Connected(client.local_addr().unwrap())
+        );
+
+        streamer.stop();
+        assert!(streamer.thread.is_none());
+    }
+
+    /// SI接頭辞付きの数値入力の解析をテスト
+    #[test]
+    fn test_parse_si_value() {
+        assert_eq!(parse_si_value("1.024M"), Some(1_024_000.0));
+        assert_eq!(parse_si_value(" 10 kHz "), Some(10_000.0));
+        assert_eq!(parse_si_value("2.4GHz"), Some(2.4e9));
+        assert_eq!(parse_si_value("440"), Some(440.0));
+        assert_eq!(parse_si_value("1e3"), Some(1000.0));
+        assert_eq!(parse_si_value("-5k"), Some(-5000.0));
+        assert_eq!(parse_si_value(""), None);
+        assert_eq!(parse_si_value("k"), None);
+        assert_eq!(parse_si_value("abc"), None);
+    }
+
+    /// スペクトラムのCSVエクスポートがプロットと同じ点列を出力することをテスト
+    #[test]
+    fn test_export_spectrum_to_csv() {
+        let mut app = MyApp {
+            spectrum_scale: SpectrumScale::Decibel,
+            window_function: WindowFunction::Hann,
+            ..Default::default()
+        };
+        // 1kHzのCW（100kHzサンプリング、1000点で10ビン目）
+        let samples = SignalGenerator::new().generate_block(&SignalParams::default(), 1000);
+        let (points, _) = app.compute_spectrum(&samples);
+
+        let path = temp_path("spectrum.csv");
+        export_spectrum_to_csv(&path, &points).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("frequency,magnitude"));
+        let rows: Vec<[f64; 2]> = lines
+            .map(|line| {
+                let (freq, mag) = line.split_once(',').unwrap();
+                [freq.parse().unwrap(), mag.parse().unwrap()]
+            })
+            .collect();
+        assert_eq!(rows, points);
+
+        // ピークは1kHzで、窓関数のゲインを補正した0dB
+        let peak = rows.iter().max_by(|a, b| a[1].total_cmp(&b[1])).unwrap();
+        assert_eq!(peak[0], 1000.0);
+        assert!(peak[1].abs() < 1e-6, "{}", peak[1]);
+    }
+
+    /// 2つのマーカーの差分でAMの側波帯の間隔と抑圧量を読み取れ、表示スケールに追従することをテスト
+    #[test]
+    fn test_marker_delta_am_sidebands() {
+        // 10kHzの搬送波を1kHz、変調指数0.5でAM変調（側波帯は搬送波の0.25倍）
+        let params = SignalParams {
+            frequency: 10000.0,
+            mod_type: ModulationType::AM,
+            mod_freq: 1000.0,
+            mod_strength: 0.5,
+            ..Default::default()
+        };
+        let samples = SignalGenerator::new().generate_block(&params, 1000);
+        let mut app = MyApp {
+            spectrum_scale: SpectrumScale::Decibel,
+            ..Default::default()
+        };
+        let (points, _) = app.compute_spectrum(&samples);
+        let m1 = snap_to_peak(&points, 9900.0).unwrap();
+        let m2 = snap_to_peak(&points, 11100.0).unwrap();
+        assert_eq!(m2.freq - m1.freq, 1000.0);
+        assert!((m2.mag - m1.mag - 20.0 * 0.25_f64.log10()).abs() < 1e-6);
+
+        // 線形スケールに切り替えるとマーカーの振幅もビンに追従し、差分は線形の値になる
+        app.spectrum_scale = SpectrumScale::Linear;
+        let (points, _) = app.compute_spectrum(&samples);
+        let m1 = snap_to_bin(&points, m1.freq).unwrap();
+        let m2 = snap_to_bin(&points, m2.freq).unwrap();
+        assert!((m2.mag - m1.mag + 0.75).abs() < 1e-9);
+    }
+
+    /// dBスペクトラムの基準レベルとノイズフロアの設定をテスト
+    #[test]
+    fn test_spectrum_reference_level_and_floor() {
+        // 振幅0.5の1kHz CW（ビンに一致するため他のビンは0）
+        let samples: Vec<Complex<f64>> = SignalGenerator::new()
+            .generate_block(&SignalParams::default(), 1000)
+            .iter()
+            .map(|s| s * 0.5)
+            .collect();
+        let peak = |points: &[[f64; 2]]| points.iter().map(|p| p[1]).fold(f64::MIN, f64::max);
+        let floor = |points: &[[f64; 2]]| points.iter().map(|p| p[1]).fold(f64::MAX, f64::min);
+
+        // 絶対レベルでは -6.02 dBFS、フロアはデフォルトの-120dB
+        let mut app = MyApp {
+            spectrum_scale: SpectrumScale::Decibel,
+            ..Default::default()
+        };
+        let (points, _) = app.compute_spectrum(&samples);
+        assert!((peak(&points) - 20.0 * 0.5_f64.log10()).abs() < 1e-9);
+        assert_eq!(floor(&points), DEFAULT_DB_FLOOR);
+
+        // ピーク基準では最大のビンが0dBになり、フロアも設定値に従う
+        app.reference_level = ReferenceLevel::Peak;
+        app.db_floor = -60.0;
+        let (points, power) = app.compute_spectrum(&samples);
+        assert!(peak(&points).abs() < 1e-9);
+        assert_eq!(floor(&points), -60.0);
+        assert!((power.iter().cloned().fold(0.0, f64::max) - 1.0).abs() < 1e-12);
+
+        // Welch法のPSD表示でもピーク基準では0dBが最大になる
+        app.welch_averaging = true;
+        app.welch_segment_len = 500;
+        app.welch_overlap = 250;
+        assert!(app.display_psd_factor(samples.len()).is_none());
+        let (points, _) = app.compute_spectrum(&samples);
+        assert!(peak(&points).abs() < 1e-9);
+    }
+
+    /// 保存したパラメータの検証と、不正な値の拒否をテスト
+    #[test]
+    fn test_app_params_validate() {
+        let app = MyApp::default();
+        let json = serde_json::to_string(&AppParams::from_app(&app)).unwrap();
+        let params: AppParams = serde_json::from_str(&json).unwrap();
+        assert!(params.validate().is_ok());
+
+        let mut params = AppParams::from_app(&app);
+        params.sample_rate = 0.0;
+        assert!(params.validate().is_err());
+
+        let mut params = AppParams::from_app(&app);
+        params.frequency = f64::NAN;
+        assert!(params.validate().is_err());
+
+        let mut params = AppParams::from_app(&app);
+        params.stft_overlap = params.stft_size;
+        assert!(params.validate().is_err());
+
+        let mut params = AppParams::from_app(&app);
+        params.multitone_spec = MultitoneSpec::Explicit(Vec::new());
+        assert!(params.validate().is_err());
+
+        // コマンドラインのトーンリスト
+        assert_eq!(
+            parse_tone_list("1000, -2500:0.5"),
+            Ok(MultitoneSpec::Explicit(vec![
+                Tone {
+                    freq: 1000.0,
+                    amplitude: 1.0,
+                },
+                Tone {
+                    freq: -2500.0,
+                    amplitude: 0.5,
+                },
+            ]))
+        );
+        assert!(parse_tone_list("1000,abc").is_err());
+        assert_eq!(
+            parse_hop_list("1000, -2500,4e3"),
+            Ok(HopSet::List(vec![1000.0, -2500.0, 4000.0]))
+        );
+        assert!(parse_hop_list("1000,").is_err());
+        assert_eq!(
+            parse_channel_taps("0:1.0, 5:-0.5"),
+            Ok(vec![(0, 1.0), (5, -0.5)])
+        );
+        assert!(parse_channel_taps("5").is_err());
+        assert!(parse_channel_taps("-1:0.5").is_err());
+    }
+
+    /// ヘッドレスモードの表示に、選択中の変調方式のパラメータと有効な雑音だけが含まれることをテスト
+    #[test]
+    fn test_describe_params() {
+        let params = SignalParams {
+            mod_type: ModulationType::FM,
+            frequency: 1000.0,
+            mod_freq: 50.0,
+            ..Default::default()
+        };
+        let text = describe_params(&params);
+        assert!(text.starts_with("mod_type: FM, frequency: 1000.0, sample_rate: "));
+        assert!(text.contains("mod_freq: 50.0"));
+        // 選択していない変調方式のパラメータや無効な雑音は表示しない
+        assert!(!text.contains("symbol_rate") && !text.contains("snr_db"));
+
+        let noisy = SignalParams {
+            add_noise: true,
+            snr_db: 30.0,
+            ..params
+        };
+        assert!(describe_params(&noisy).ends_with("snr_db: 30.0"));
+    }
+
+    /// すべてのフィールドを変更したパラメータの保存/復元の往復をテスト
+    #[test]
+    fn test_app_params_round_trip() {
+        let mut app = MyApp {
+            frequency: 2500.0,
+            initial_phase_deg: -45.0,
+            freq_step: 1000.0,
+            amplitude: 0.7,
+            amplitude_unit: AmplitudeUnit::Dbm,
+            impedance: 75.0,
+            sample_rate: 48000.0,
+            num_samples: 2048,
+            add_noise: true,
+            snr_db: 15.0,
+            iq_gain_imbalance_db: 0.5,
+            iq_phase_error_deg: 3.0,
+            dc_offset_i: 0.1,
+            dc_offset_q: -0.1,
+            phase_noise_rms_deg: 1.5,
+            phase_noise_offset_hz: 10000.0,
+            amplitude_profile: AmplitudeProfile {
+                kind: AmplitudeProfileKind::Staircase,
+                start_db: -35.0,
+                stop_db: -5.0,
+                ramp_samples: 4000,
+                steps: 7,
+                dwell_samples: 300,
+            },
+            burst_enabled: true,
+            burst: BurstConfig {
+                period_samples: 800,
+                on_samples: 300,
+                ramp_samples: 10,
+            },
+            ofdm: OfdmConfig {
+                num_subcarriers: 128,
+                active_subcarriers: 100,
+                null_dc: false,
+                cp_len: 32,
+                qam_order: 16,
+            },
+            channel_enabled: true,
+            channel: ChannelConfig {
+                doppler_hz: -150.0,
+                taps: vec![(0, 1.0), (3, -0.25)],
+            },
+            quantization_enabled: true,
+            quantization_bits: 8,
+            clip_enabled: true,
+            clip_level: 0.5,
+            clip_model: SaturationModel::Tanh,
+            clip_gain: 1.5,
+            saleh_enabled: true,
+            saleh: SalehParams {
+                alpha_a: 1.0,
+                beta_a: 0.5,
+                alpha_phi: 0.2,
+                beta_phi: 1.0,
+            },
+            spectrum_scale: SpectrumScale::Linear,
+            window_function: WindowFunction::Blackman,
+            zero_pad_fft: true,
+            welch_averaging: true,
+            welch_segment_len: 256,
+            welch_overlap: 64,
+            max_hold: true,
+            log_freq: true,
+            reference_level: ReferenceLevel::Peak,
+            db_floor: -90.0,
+            acp_channel_bw: 500.0,
+            acp_channel_offset: 1500.0,
+            obw_percent: 95.0,
+            mod_type: ModulationType::Multitone,
+            am_mod_freq: 20.0,
+            am_mod_index: 0.3,
+            am_waveform: AmWaveform::Square,
+            fm_mod_freq: 30.0,
+            fm_deviation: 200.0,
+            pm_mod_index: 0.8,
+            ssb_sideband: Sideband::Lower,
+            pulse_freq: 50.0,
+            pulse_duty_cycle: 0.25,
+            pulse_edge_time: 0.001,
+            multitone_count: 7,
+            multitone_spacing: 300.0,
+            multitone_phase: MultitonePhase::Random,
+            multitone_spec: MultitoneSpec::Explicit(vec![
+                Tone {
+                    freq: -500.0,
+                    amplitude: 1.0,
+                },
+                Tone {
+                    freq: 1200.0,
+                    amplitude: 0.25,
+                },
+            ]),
+            multitone_amplitude_window: WindowFunction::Hann,
+            notch_start_tone: 3,
+            notch_width_tones: 2,
+            seed: 1234,
+            tone_spacing: 400.0,
+            symbol_rate: 2000.0,
+            prbs_order: PrbsOrder::Prbs15,
+            ask_low_level: 0.2,
+            psk_order: 8,
+            fsk_deviation: 600.0,
+            fsk_gaussian: true,
+            fsk_bt: 0.3,
+            pulse_shaping: true,
+            rrc_rolloff: 0.22,
+            rrc_span: 12,
+            chirp_bandwidth: 4000.0,
+            chirp_duration: 0.02,
+            sweep_start: 100.0,
+            sweep_stop: 9000.0,
+            sweep_time: 0.5,
+            sweep_mode: SweepMode::LogStep,
+            sweep_dwell: 250,
+            hop_set: HopSet::List(vec![1000.0, -2500.0, 4000.0]),
+            hop_bandwidth: 20000.0,
+            hop_step: 500.0,
+            hop_dwell: 0.002,
+            hop_reset_phase: true,
+            time_domain_unit: TimeDomainUnit::Samples,
+            show_time_domain: false,
+            time_view: TimeView::Polar,
+            constellation_trajectory: true,
+            show_freq_domain: false,
+            show_spectrogram: true,
+            stft_size: 512,
+            stft_overlap: 128,
+            output_mode: OutputMode::Real,
+            ..MyApp::default()
+        };
+        let json = serde_json::to_string(&AppParams::from_app(&app)).unwrap();
+
+        let mut restored = MyApp::default();
+        let params: AppParams = serde_json::from_str(&json).unwrap();
+        assert!(params.validate().is_ok());
+        params.apply_to_app(&mut restored);
+        assert_eq!(
+            serde_json::to_string(&AppParams::from_app(&restored)).unwrap(),
+            json
+        );
+        assert_eq!(restored.mod_type, ModulationType::Multitone);
+        assert_eq!(restored.multitone_phase, MultitonePhase::Random);
+
+        // 欠けたフィールドはデフォルト値、未知のフィールドは無視
+        let params: AppParams =
+            serde_json::from_str(r#"{"frequency": 3000.0, "unknown_field": 1}"#).unwrap();
+        params.apply_to_app(&mut app);
+        assert_eq!(app.frequency, 3000.0);
+        assert_eq!(app.sample_rate, MyApp::default().sample_rate);
+        assert_eq!(app.mod_type, ModulationType::CW);
+    }
+
+    /// 終了時に保存したパラメータが次回起動時に復元されることをテスト
+    #[test]
+    fn test_persisted_settings() {
+        use eframe::App;
+
+        /// メモリ上の保存領域
+        #[derive(Default)]
+        struct MemoryStorage(std::collections::HashMap<String, String>);
+        impl eframe::Storage for MemoryStorage {
+            fn get_string(&self, key: &str) -> Option<String> {
+                self.0.get(key).cloned()
             }
-        });
+            fn set_string(&mut self, key: &str, value: String) {
+                self.0.insert(key.to_string(), value);
+            }
+            fn flush(&mut self) {}
+        }
 
-        // === 信号生成 ===
-        // 可視化とエクスポートのためのデータを生成
-        // パネル外で生成することで、ボトムパネル（エクスポート）と
-        // セントラルパネル（プロット）の両方で使用可能にする
-        let num_samples = self.num_samples;
+        // 保存領域がない、または空の場合はデフォルト値
+        let mut storage = MemoryStorage::default();
+        assert_eq!(
+            MyApp::from_storage(None).frequency,
+            MyApp::default().frequency
+        );
+        assert_eq!(
+            MyApp::from_storage(Some(&storage)).frequency,
+            MyApp::default().frequency
+        );
 
-        // 変調タイプに応じて変調パラメータを設定
-        let (mod_freq, mod_strength) = match self.mod_type {
-            ModulationType::CW => (0.0, 0.0),
-            ModulationType::AM => (self.am_mod_freq, self.am_mod_index),
-            ModulationType::FM => (self.fm_mod_freq, self.fm_deviation),
-            ModulationType::PM => (self.am_mod_freq, self.pm_mod_index),
-            ModulationType::Pulse => (self.pulse_freq, self.pulse_duty_cycle),
-            ModulationType::Multitone => (0.0, 0.0),
+        let mut app = MyApp {
+            frequency: 12345.0,
+            mod_type: ModulationType::FM,
+            window_function: WindowFunction::Tukey(0.25),
+            spectrum_scale: SpectrumScale::Linear,
+            ..MyApp::default()
         };
+        app.save(&mut storage);
+        let restored = MyApp::from_storage(Some(&storage));
+        assert_eq!(restored.frequency, 12345.0);
+        assert_eq!(restored.mod_type, ModulationType::FM);
+        assert_eq!(restored.window_function, WindowFunction::Tukey(0.25));
+        assert!(restored.spectrum_scale == SpectrumScale::Linear);
+        assert!(restored.spectrum_cache.is_none());
 
-        // 信号生成パラメータを構築
+        // 不正な値が保存されている場合は無視する
+        app.sample_rate = 1.0;
+        app.save(&mut storage);
+        assert_eq!(
+            MyApp::from_storage(Some(&storage)).sample_rate,
+            MyApp::default().sample_rate
+        );
+    }
+
+    /// ベクトルスコープの点列から搬送波の回転が取り除かれることをテスト
+    #[test]
+    fn test_polar_points() {
+        // CWは搬送波を取り除くと初期位相の一点に留まる
         let params = SignalParams {
-            frequency: self.frequency,
-            sample_rate: self.sample_rate,
-            mod_type: self.mod_type,
-            mod_freq,
-            mod_strength,
-            multitone_count: self.multitone_count,
-            multitone_spacing: self.multitone_spacing,
-            multitone_phase: self.multitone_phase,
-            seed: self.seed,
+            frequency: 1234.0,
+            initial_phase_deg: 30.0,
+            ..Default::default()
         };
+        let samples = SignalGenerator::new().generate_block(&params, 1000);
+        let points = polar_points(&samples, 1234.0, params.sample_rate, 2000);
+        assert_eq!(points.len(), 1000);
+        let expected = Complex::from_polar(1.0, 30f64.to_radians());
+        assert!(points
+            .iter()
+            .all(|&[x, y]| (Complex::new(x, y) - expected).norm() < 1e-9));
 
-        // 信号を生成
-        let mut viz_gen = SignalGenerator::new();
-        let samples = viz_gen.generate_block(&params, num_samples);
+        // PMは変調指数の範囲で位相が振れる
+        let params = SignalParams {
+            mod_type: ModulationType::PM,
+            mod_freq: 100.0,
+            mod_strength: 1.0,
+            ..params
+        };
+        let samples = SignalGenerator::new().generate_block(&params, 1000);
+        let phases: Vec<f64> = polar_points(&samples, 1234.0, params.sample_rate, 2000)
+            .iter()
+            .map(|[x, y]| y.atan2(*x) - 30f64.to_radians())
+            .collect();
+        let max = phases.iter().cloned().fold(f64::MIN, f64::max);
+        let min = phases.iter().cloned().fold(f64::MAX, f64::min);
+        assert!((max - 1.0).abs() < 1e-3 && (min + 1.0).abs() < 1e-3);
 
-        // 振幅を適用
-        let samples: Vec<Complex<f64>> = samples.iter().map(|s| s * self.amplitude).collect();
+        // 最大点数を超える場合は間引く
+        assert!(polar_points(&samples, 0.0, 1000.0, 300).len() <= 300);
+    }
 
-        // === ボトムパネル：エクスポート機能 ===
-        egui::TopBottomPanel::bottom("export_panel").show(ctx, |ui| {
-            ui.horizontal(|ui| {
-                // CSV形式でエクスポート
-                if ui.button("Export to CSV").clicked() {
-                    if let Some(path) = rfd::FileDialog::new()
-                        .add_filter("CSV", &["csv"])
-                        .set_file_name("output.csv")
-                        .save_file()
-                    {
-                        if let Err(e) = export_to_csv(&path, &samples) {
-                            eprintln!("Failed to export: {}", e);
-                        } else {
-                            eprintln!("Exported to {:?}", path);
-                        }
-                    }
-                }
+    /// コンスタレーション表示の点列が最大点数以下に間引かれることをテスト
+    #[test]
+    fn test_constellation_points_decimation() {
+        let samples: Vec<Complex<f64>> = (0..1000).map(|i| Complex::new(i as f64, 0.0)).collect();
 
-                // バイナリ形式でエクスポート
-                if ui.button("Export to BIN").clicked() {
-                    if let Some(path) = rfd::FileDialog::new()
-                        .add_filter("Binary", &["bin"])
-                        .set_file_name("output.bin")
-                        .save_file()
-                    {
-                        if let Err(e) = export_to_bin(&path, &samples) {
-                            eprintln!("Failed to export: {}", e);
-                        } else {
-                            eprintln!("Exported to {:?}", path);
-                        }
-                    }
-                }
-            });
-        });
+        let points = constellation_points(&samples, 2000);
+        assert_eq!(points.len(), 1000);
 
-        // === セントラルパネル：プロット表示 ===
-        egui::CentralPanel::default().show(ctx, |ui| {
-            // プロットの高さを計算
-            // 2つのプロット（時間領域・周波数領域）を表示するため、
-            // 利用可能な高さを2分割し、ラベルとスペース分を考慮
-            let available_height = ui.available_height();
-            let plot_height = (available_height - 60.0) / 2.0;
+        let points = constellation_points(&samples, 300);
+        assert!(points.len() <= 300);
+        assert_eq!(points[0], [0.0, 0.0]);
+        assert_eq!(points[1], [4.0, 0.0]);
+    }
 
-            // === 時間領域プロット ===
-            if self.show_time_domain {
-                ui.horizontal(|ui| {
-                    ui.label("Time Domain");
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        ui.selectable_value(
-                            &mut self.time_domain_unit,
-                            TimeDomainUnit::Samples,
-                            "Samples",
-                        );
-                        ui.selectable_value(
-                            &mut self.time_domain_unit,
-                            TimeDomainUnit::Seconds,
-                            "Time (s)",
-                        );
-                        ui.label("Unit:");
-                    });
-                });
+    /// 実信号モードで各エクスポート形式がI成分のみを出力することをテスト
+    #[test]
+    fn test_real_output_exports() {
+        let raw = vec![Complex::new(0.5, -0.25), Complex::new(-0.5, 0.25)];
+        let samples = scale_samples(&raw, 2.0, OutputMode::Real);
+        assert_eq!(samples[0], Complex::new(1.0, 0.0));
 
-                // 時間軸単位の変更を検出し、プロット範囲を調整
-                // ユーザーがズーム/パン操作した範囲を新しい単位でも維持する
-                if self.time_domain_unit != self.last_time_domain_unit {
-                    if let Some(bounds) = self.last_plot_bounds {
-                        let min = bounds.min();
-                        let max = bounds.max();
+        let path = temp_path("real.csv");
+        export_to_csv(&path, &samples, None, 1000.0, OutputMode::Real).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(csv.lines().collect::<Vec<_>>(), vec!["1", "-1"]);
 
-                        // X軸の範囲を新しい単位に変換
-                        let (new_min_x, new_max_x) = match self.time_domain_unit {
-                            TimeDomainUnit::Samples => {
-                                // 秒 → サンプル数：サンプリングレートを掛ける
-                                (min[0] * self.sample_rate, max[0] * self.sample_rate)
-                            }
-                            TimeDomainUnit::Seconds => {
-                                // サンプル数 → 秒：サンプリングレートで割る
-                                (min[0] / self.sample_rate, max[0] / self.sample_rate)
-                            }
-                        };
+        let path = temp_path("real.bin");
+        export_to_bin(&path, &samples, BinFormat::F32, 1.0, OutputMode::Real).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(bytes.len(), samples.len() * 4);
+        assert_eq!(f32::from_le_bytes(bytes[4..8].try_into().unwrap()), -1.0);
 
-                        // Y軸は変更なし
-                        let new_min_y = min[1];
-                        let new_max_y = max[1];
+        let path = temp_path("real.wav");
+        export_to_wav(
+            &path,
+            &samples,
+            48000.0,
+            hound::SampleFormat::Float,
+            OutputMode::Real,
+        )
+        .unwrap();
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().channels, 1);
+        assert_eq!(reader.len() as usize, samples.len());
+        std::fs::remove_file(&path).unwrap();
+    }
 
-                        // 新しい範囲を設定
-                        self.forced_plot_bounds = Some(egui_plot::PlotBounds::from_min_max(
-                            [new_min_x, new_min_y],
-                            [new_max_x, new_max_y],
-                        ));
-                    }
-                    self.last_time_domain_unit = self.time_domain_unit;
-                }
+    /// 実信号モードのスペクトラムに負の周波数のイメージが対称に現れることをテスト
+    #[test]
+    fn test_real_output_spectrum_image() {
+        let mut app = MyApp {
+            frequency: 12500.0,
+            output_mode: OutputMode::Real,
+            ..MyApp::default()
+        };
+        let raw = SignalGenerator::new().generate_block(&app.signal_params(), 1000);
+        let power_at = |mode: OutputMode, app: &mut MyApp| {
+            let (freqs, power) = app
+                .spectrum_settings()
+                .compute_power(&scale_samples(&raw, 1.0, mode), &mut app.fft_planner);
+            let bin = |f: f64| freqs.iter().position(|&x| (x - f).abs() < 1e-6).unwrap();
+            (power[bin(12500.0)], power[bin(-12500.0)])
+        };
 
-                // プロットを描画
-                let plot_response =
-                    Plot::new("time_domain")
-                        .height(plot_height)
-                        .show(ui, |plot_ui| {
-                            // 強制的な範囲設定がある場合は適用（単位変更時）
-                            if let Some(bounds) = self.forced_plot_bounds {
-                                plot_ui.set_plot_bounds(bounds);
-                                self.forced_plot_bounds = None;
-                            }
+        // 複素IQでは正の周波数のみ、実信号では振幅が半分ずつの正負のトーンになる
+        let (pos, neg) = power_at(OutputMode::Complex, &mut app);
+        assert!((pos - 1.0).abs() < 1e-9);
+        assert!(neg < 1e-20);
+        let (pos, neg) = power_at(OutputMode::Real, &mut app);
+        assert!((pos - 0.25).abs() < 1e-9);
+        assert!((neg - pos).abs() < 1e-12);
+    }
 
-                            // I成分（実部）のプロットポイントを生成
-                            let i_points: PlotPoints = samples
-                                .iter()
-                                .enumerate()
-                                .map(|(i, s)| {
-                                    let x = match self.time_domain_unit {
-                                        TimeDomainUnit::Seconds => i as f64 / self.sample_rate,
-                                        TimeDomainUnit::Samples => i as f64,
-                                    };
-                                    [x, s.re]
-                                })
-                                .collect();
+    /// 生成に影響するパラメータが変わったときのみ再生成が必要になることをテスト
+    #[test]
+    fn test_needs_regen() {
+        let mut app = MyApp::default();
+        let old = app.signal_params();
+        assert!(needs_regen(None, &old));
+        assert!(!needs_regen(Some(&old), &app.signal_params()));
 
-                            // Q成分（虚部）のプロットポイントを生成
-                            let q_points: PlotPoints = samples
-                                .iter()
-                                .enumerate()
-                                .map(|(i, s)| {
-                                    let x = match self.time_domain_unit {
-                                        TimeDomainUnit::Seconds => i as f64 / self.sample_rate,
-                                        TimeDomainUnit::Samples => i as f64,
-                                    };
-                                    [x, s.im]
-                                })
-                                .collect();
+        // 振幅や表示設定はキャッシュしたブロックに後から適用する
+        app.amplitude = 2.0;
+        app.window_function = WindowFunction::Hann;
+        app.spectrum_scale = SpectrumScale::Linear;
+        app.time_view = TimeView::Constellation;
+        assert!(!needs_regen(Some(&old), &app.signal_params()));
 
-                            // I/Q成分をプロット
-                            plot_ui.line(Line::new(i_points).name("I"));
-                            plot_ui.line(Line::new(q_points).name("Q"));
-                        });
+        // 選択中の変調方式で使わないパラメータも再生成の対象外
+        app.fm_deviation += 100.0;
+        assert!(!needs_regen(Some(&old), &app.signal_params()));
 
-                // 現在のプロット範囲を保存（単位変更検出用）
-                self.last_plot_bounds = Some(plot_response.transform.bounds().clone());
+        app.frequency += 1.0;
+        assert!(needs_regen(Some(&old), &app.signal_params()));
 
-                ui.separator();
+        let app = MyApp {
+            dc_offset_q: 0.1,
+            ..Default::default()
+        };
+        assert!(needs_regen(Some(&old), &app.signal_params()));
+    }
+
+    /// マーカーが最寄りのFFTビンに吸着することをテスト
+    #[test]
+    fn test_snap_to_bin() {
+        let points: Vec<[f64; 2]> = (-5..5).map(|k| [k as f64 * 100.0, k as f64]).collect();
+
+        let marker = snap_to_bin(&points, 130.0).unwrap();
+        assert_eq!(
+            marker,
+            Marker {
+                freq: 100.0,
+                mag: 1.0
             }
+        );
+        let marker = snap_to_bin(&points, 160.0).unwrap();
+        assert_eq!(marker.freq, 200.0);
 
-            // === 周波数領域プロット ===
-            if self.show_freq_domain {
-                ui.horizontal(|ui| {
-                    ui.label("Frequency Domain");
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        ui.selectable_value(&mut self.spectrum_scale, SpectrumScale::Decibel, "dB");
-                        ui.selectable_value(
-                            &mut self.spectrum_scale,
-                            SpectrumScale::Linear,
-                            "Linear",
-                        );
-                        ui.label("Scale:");
-                    });
-                });
+        // 範囲外は端のビンに吸着
+        assert_eq!(snap_to_bin(&points, -1e6).unwrap().freq, -500.0);
+        assert_eq!(snap_to_bin(&points, 1e6).unwrap().freq, 400.0);
+        assert!(snap_to_bin(&[], 0.0).is_none());
+    }
 
-                // FFTを実行してスペクトラムを計算
-                let fft = self.fft_planner.plan_fft_forward(num_samples);
-                let mut spectrum = samples.clone();
-                fft.process(&mut spectrum);
+    /// クリック位置から最寄りのスペクトラムのピークに吸着することをテスト
+    #[test]
+    fn test_snap_to_peak() {
+        // 200Hzに大きいピーク、-300Hzに小さいピーク
+        let mags = [0.0, 0.1, 0.5, 0.2, 0.1, 0.2, 0.6, 1.0, 0.4, 0.0];
+        let points: Vec<[f64; 2]> = mags
+            .iter()
+            .enumerate()
+            .map(|(k, &m)| [(k as f64 - 5.0) * 100.0, m])
+            .collect();
 
-                // スペクトラムデータをプロット用に変換
-                let mut fft_points: Vec<[f64; 2]> = Vec::with_capacity(num_samples);
-                for i in 0..num_samples {
-                    // FFT結果をシフトして周波数軸を中心に配置
-                    let idx = (i + num_samples / 2) % num_samples;
+        assert_eq!(snap_to_peak(&points, 30.0).unwrap().freq, 200.0);
+        assert_eq!(snap_to_peak(&points, 200.0).unwrap().mag, 1.0);
+        assert_eq!(snap_to_peak(&points, -180.0).unwrap().freq, -300.0);
 
-                    // 周波数を計算（負の周波数を含む）
-                    let freq = (i as f64 - num_samples as f64 / 2.0) * self.sample_rate
-                        / num_samples as f64;
+        // dBスケールでも同じピーク
+        let db: Vec<[f64; 2]> = points
+            .iter()
+            .map(|&[f, m]| [f, SpectrumScale::Decibel.apply(m, DEFAULT_DB_FLOOR)])
+            .collect();
+        assert_eq!(snap_to_peak(&db, 30.0).unwrap().freq, 200.0);
+        assert!(snap_to_peak(&[], 0.0).is_none());
+    }
 
-                    // 振幅を計算して正規化
-                    let mut mag = spectrum[idx].norm() / num_samples as f64;
+    /// チャンクごとのストリーミングエクスポートが一括エクスポートと同じ内容になることをテスト
+    #[test]
+    fn test_export_streaming_matches_buffered() {
+        let params = SignalParams {
+            mod_type: ModulationType::FM,
+            mod_freq: 100.0,
+            mod_strength: 500.0,
+            ..Default::default()
+        };
+        // チャンク境界をまたぐサンプル数
+        let num_samples = EXPORT_CHUNK_SIZE * 2 + 100;
+        let samples = scale_samples(
+            &SignalGenerator::new().generate_block(&params, num_samples),
+            0.5,
+            OutputMode::Complex,
+        );
 
-                    // スケール変換（線形またはdB）
-                    if self.spectrum_scale == SpectrumScale::Decibel {
-                        mag = 20.0 * mag.log10();
-                        // ノイズフロアを-120dBでクランプ
-                        if mag < -120.0 {
-                            mag = -120.0;
-                        }
-                    }
+        for name in ["stream.bin", "stream.csv"] {
+            let streamed = temp_path(name);
+            let buffered = temp_path(&format!("buffered_{}", name));
+            export_streaming(
+                &streamed,
+                &params,
+                num_samples,
+                0.5,
+                OutputMode::Complex,
+                OutputImpairments::default(),
+            )
+            .unwrap();
+            export_by_extension(&buffered, &samples, &params, OutputMode::Complex).unwrap();
+            assert_eq!(
+                std::fs::read(&streamed).unwrap(),
+                std::fs::read(&buffered).unwrap(),
+                "{}",
+                name
+            );
+            std::fs::remove_file(&streamed).unwrap();
+            std::fs::remove_file(&buffered).unwrap();
+        }
+        assert!(export_streaming(
+            &temp_path("stream.wav"),
+            &params,
+            10,
+            1.0,
+            OutputMode::Complex,
+            OutputImpairments::default()
+        )
+        .is_err());
+    }
 
-                    fft_points.push([freq, mag]);
-                }
+    /// 各単位から線形振幅への変換と、その逆変換をテスト
+    #[test]
+    fn test_linear_from_unit() {
+        let eps = 1e-9;
+        let z = DEFAULT_IMPEDANCE;
+        assert_eq!(linear_from_unit(0.5, AmplitudeUnit::Linear, z), 0.5);
+        assert!((linear_from_unit(0.0, AmplitudeUnit::Dbfs, z) - 1.0).abs() < eps);
+        assert!((linear_from_unit(-20.0, AmplitudeUnit::Dbfs, z) - 0.1).abs() < eps);
+        // 50Ωで10dBm (10mW) はピーク1V、0dBm (1mW) はピーク√0.1 V
+        assert!((linear_from_unit(10.0, AmplitudeUnit::Dbm, z) - 1.0).abs() < eps);
+        assert!((linear_from_unit(0.0, AmplitudeUnit::Dbm, z) - 0.1_f64.sqrt()).abs() < eps);
 
-                // スペクトラムをプロット
-                Plot::new("freq_domain")
-                    .height(plot_height)
-                    .show(ui, |plot_ui| {
-                        plot_ui.line(Line::new(PlotPoints::new(fft_points)).name("Magnitude"));
-                    });
-            }
-        });
+        for unit in [
+            AmplitudeUnit::Linear,
+            AmplitudeUnit::Dbfs,
+            AmplitudeUnit::Dbm,
+        ] {
+            let amplitude = 0.37;
+            let value = unit_from_linear(amplitude, unit, z);
+            assert!((linear_from_unit(value, unit, z) - amplitude).abs() < eps);
+        }
+        assert_eq!(
+            unit_from_linear(0.0, AmplitudeUnit::Dbfs, z),
+            MIN_AMPLITUDE_DB
+        );
     }
-}
 
-/// サンプルをCSV形式でエクスポート
-///
-/// I/Q成分を2列のCSVファイルとして出力します。
-/// ヘッダー行は含みません。
-///
-/// # 引数
-/// * `path` - 出力先ファイルパス
-/// * `samples` - エクスポートする複素数サンプル配列
-///
-/// # 戻り値
-/// 成功時はOk(())、失敗時はエラー
-fn export_to_csv(path: &std::path::Path, samples: &[Complex<f64>]) -> std::io::Result<()> {
-    let mut wtr = csv::WriterBuilder::new()
-        .has_headers(false)
-        .from_path(path)?;
+    /// dBmと振幅の換算が基準インピーダンスに従うことをテスト
+    #[test]
+    fn test_dbm_to_amplitude() {
+        let eps = 1e-9;
+        // 50Ωで10dBm (10mW) はピーク1V
+        assert!((dbm_to_amplitude(10.0, 50.0) - 1.0).abs() < eps);
+        // 75Ωで0dBm (1mW) はピーク√0.15 V、600Ωでは√1.2 V
+        assert!((dbm_to_amplitude(0.0, 75.0) - 0.15_f64.sqrt()).abs() < eps);
+        assert!((dbm_to_amplitude(0.0, 600.0) - 1.2_f64.sqrt()).abs() < eps);
+        // 電力が10dB上がると振幅は√10倍
+        let ratio = dbm_to_amplitude(13.0, 50.0) / dbm_to_amplitude(3.0, 50.0);
+        assert!((ratio - 10.0_f64.sqrt()).abs() < eps);
+
+        for z in [50.0, 75.0, 600.0] {
+            assert!((amplitude_to_dbm(dbm_to_amplitude(-17.5, z), z) + 17.5).abs() < eps);
+        }
+        assert_eq!(amplitude_to_dbm(0.0, 50.0), MIN_AMPLITUDE_DB);
+    }
 
-    for sample in samples.iter() {
-        wtr.write_record(&[sample.re.to_string(), sample.im.to_string()])?;
+    /// 対数周波数軸ではDCと負の周波数を除いて周波数をlog10に変換することをテスト
+    #[test]
+    fn test_log_freq_points() {
+        let points: Vec<[f64; 2]> = (-2..4).map(|k| [k as f64 * 10.0, k as f64]).collect();
+        assert_eq!(
+            to_log_freq(&points),
+            vec![[1.0, 1.0], [20f64.log10(), 2.0], [30f64.log10(), 3.0]]
+        );
+
+        assert_eq!(format_log_freq(1.0), "10.0 Hz");
+        assert_eq!(format_log_freq(2.5), "316 Hz");
+        assert_eq!(format_log_freq(3.0), "1.00 kHz");
+        assert_eq!(format_log_freq(6.5), "3.16 MHz");
+        // 1Hz未満の目盛りも周波数 (Hz) で表示
+        assert_eq!(format_log_freq(-1.0), "0.100 Hz");
+        assert_eq!(format_log_freq(0.0), "1.00 Hz");
     }
-    wtr.flush()?;
-    Ok(())
-}
 
-/// サンプルをバイナリ形式でエクスポート
-///
-/// I/Q成分を32ビット浮動小数点数（リトルエンディアン）として出力します。
-/// 各サンプルは8バイト（I: 4バイト + Q: 4バイト）で表現されます。
-///
-/// # 引数
-/// * `path` - 出力先ファイルパス
-/// * `samples` - エクスポートする複素数サンプル配列
-///
-/// # 戻り値
-/// 成功時はOk(())、失敗時はエラー
-fn export_to_bin(path: &std::path::Path, samples: &[Complex<f64>]) -> std::io::Result<()> {
-    use std::io::Write;
-    let mut file = std::fs::File::create(path)?;
-    // バッファを事前確保（各サンプル8バイト = I(4バイト) + Q(4バイト)）
-    let mut buffer = Vec::with_capacity(samples.len() * 8);
+    /// 最大値保持トレースが各ビンの最大値を保ち、ビン数の変化で作り直されることをテスト
+    #[test]
+    fn test_max_hold_update() {
+        let mut trace = Vec::new();
+        update_max_hold(&mut trace, &[1.0, 0.5, 0.2]);
+        assert_eq!(trace, vec![1.0, 0.5, 0.2]);
+        update_max_hold(&mut trace, &[0.5, 0.8, 0.1]);
+        assert_eq!(trace, vec![1.0, 0.8, 0.2]);
 
-    for sample in samples {
-        // f64をf32に変換してリトルエンディアンでバイト列化
-        buffer.extend_from_slice(&(sample.re as f32).to_le_bytes());
-        buffer.extend_from_slice(&(sample.im as f32).to_le_bytes());
+        // ビン数が変わったら新しいスペクトラムから始め直す
+        update_max_hold(&mut trace, &[0.3, 0.3]);
+        assert_eq!(trace, vec![0.3, 0.3]);
     }
 
-    file.write_all(&buffer)?;
-    Ok(())
+    /// 平均化したスペクトラムがPSD (dB/Hz) で表示され、ビンの電力はトーンの電力を保つことをテスト
+    #[test]
+    fn test_welch_averaged_spectrum() {
+        let mut app = MyApp {
+            welch_averaging: true,
+            welch_segment_len: 1000,
+            welch_overlap: 500,
+            ..Default::default()
+        };
+        // 1kHzのCW（サンプリングレート100kHz）、セグメントあたり整数周期
+        let samples = SignalGenerator::new().generate_block(&app.signal_params(), 10000);
+        let (points, power) = app.compute_spectrum(&samples);
+        assert_eq!(points.len(), 1000);
+        let (peak_bin, &peak_power) = power
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        assert!((points[peak_bin][0] - 1000.0).abs() < 1e-9);
+        assert!((peak_power - 1.0).abs() < 1e-9);
+
+        // 表示値はビンの電力をPSDに換算したdB/Hz
+        let factor = app.spectrum_settings().psd_factor(samples.len()).unwrap();
+        let expected_db = 10.0 * (peak_power * factor).log10();
+        assert!((points[peak_bin][1] - expected_db).abs() < 1e-9);
+
+        // サンプル数がセグメント長に満たない場合は通常のFFT
+        assert!(app.spectrum_settings().psd_factor(500).is_none());
+        let (points, _) = app.compute_spectrum(&samples[..500]);
+        assert_eq!(points.len(), 500);
+    }
+
+    /// ゼロ詰めでFFTのビン数と周波数分解能が変わり、振幅の正規化は保たれることをテスト
+    #[test]
+    fn test_zero_pad_fft_resolution() {
+        let mut app = MyApp {
+            spectrum_scale: SpectrumScale::Linear,
+            ..Default::default()
+        };
+        let samples = SignalGenerator::new().generate_block(&app.signal_params(), 1000);
+        let peak = |points: &[[f64; 2]]| points.iter().map(|p| p[1]).fold(0.0, f64::max);
+
+        let (points, _) = app.compute_spectrum(&samples);
+        assert_eq!(points.len(), 1000);
+        assert!((points[1][0] - points[0][0] - 100.0).abs() < 1e-9);
+        assert!((peak(&points) - 1.0).abs() < 1e-9);
+
+        app.zero_pad_fft = true;
+        let (points, _) = app.compute_spectrum(&samples);
+        assert_eq!(points.len(), 1024);
+        assert!((points[1][0] - points[0][0] - 100000.0 / 1024.0).abs() < 1e-9);
+        // 1kHzのトーンはビンの間にあるため、最寄りのビンの振幅は1以下に下がる
+        assert!(peak(&points) <= 1.0 + 1e-9 && peak(&points) > 0.6);
+    }
 }
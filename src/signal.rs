@@ -1,16 +1,23 @@
 //! 信号生成モジュール
 //!
 //! このモジュールは、様々な変調方式をサポートする信号生成機能を提供します。
-//! CW、AM、FM、PM、パルス、マルチトーン信号の生成が可能です。
+//! CW、AM、FM、PM、SSB、パルス、マルチトーン、2トーン、BPSK、QPSK、M-PSK、16QAM、ASK、FSK/GFSK、MSK/GMSK、チャープ、掃引、周波数ホッピング、OFDM、雑音信号の生成が可能です。
 
 use num_complex::Complex;
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
+use rand_distr::StandardNormal;
+use rustfft::{Fft, FftPlanner};
+use std::collections::VecDeque;
 use std::f64::consts::PI;
+use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
+use crate::analysis::{tone_window_coefficient, WindowFunction};
+
 /// 変調方式の種類を定義する列挙型
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub enum ModulationType {
     /// CW (Continuous Wave) - 連続波
@@ -25,6 +32,115 @@ pub enum ModulationType {
     Pulse,
     /// Multitone - マルチトーン信号
     Multitone,
+    /// TwoTone - 相互変調歪み測定用の2トーン信号
+    TwoTone,
+    /// BPSK (Binary Phase Shift Keying) - 2値位相変調
+    BPSK,
+    /// QPSK (Quadrature Phase Shift Keying) - 4値位相変調
+    QPSK,
+    /// 16QAM (16-ary Quadrature Amplitude Modulation) - 16値直交振幅変調
+    QAM16,
+    /// ASK (Amplitude Shift Keying) - 振幅偏移変調（OOKを含む）
+    ASK,
+    /// FSK (Frequency Shift Keying) - 2値周波数偏移変調（ガウスフィルタ併用でGFSK）
+    FSK,
+    /// MSK (Minimum Shift Keying) - 変調指数0.5の連続位相FSK（ガウスフィルタ併用でGMSK）
+    MSK,
+    /// PSK (Phase Shift Keying) - 多値数を選択できる位相変調（2/4/8値）
+    PSK,
+    /// Chirp - 線形周波数変調（LFM）チャープ
+    Chirp,
+    /// Sweep - 開始周波数から終了周波数までの繰り返し周波数掃引
+    Sweep,
+    /// Noise - 複素白色ガウス雑音（AWGN）
+    Noise,
+    /// SSB (Single Sideband) - 解析信号による単側波帯変調
+    SSB,
+    /// Hopping - 滞在時間ごとに周波数を切り替える周波数ホッピング（FHSS）
+    Hopping,
+    /// OFDM (Orthogonal Frequency Division Multiplexing) - サイクリックプレフィックス付き直交周波数分割多重
+    OFDM,
+}
+
+impl ModulationType {
+    /// PRBSのビット列からシンボルを生成するデジタル変調方式かどうか
+    pub fn is_digital(self) -> bool {
+        matches!(
+            self,
+            ModulationType::BPSK
+                | ModulationType::QPSK
+                | ModulationType::QAM16
+                | ModulationType::ASK
+                | ModulationType::FSK
+                | ModulationType::MSK
+                | ModulationType::PSK
+        )
+    }
+}
+
+/// AMの変調波形
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum AmWaveform {
+    /// 正弦波
+    Sine,
+    /// 三角波
+    Triangle,
+    /// 方形波（デューティ50%）
+    Square,
+    /// のこぎり波（上昇）
+    Sawtooth,
+}
+
+impl AmWaveform {
+    /// 変調信号の位相 (ラジアン) における波形の値 (-1〜1)
+    ///
+    /// 位相0で正弦波（cos）と同じく1から始まる三角波・方形波と、
+    /// -1から1まで上昇するのこぎり波になります。
+    pub fn value(self, phase: f64) -> f64 {
+        let x = (phase / (2.0 * PI)).rem_euclid(1.0);
+        match self {
+            AmWaveform::Sine => phase.cos(),
+            AmWaveform::Triangle => (4.0 * x - 2.0).abs() - 1.0,
+            AmWaveform::Square => {
+                if x < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            AmWaveform::Sawtooth => 2.0 * x - 1.0,
+        }
+    }
+}
+
+/// 出力段のクリップ（振幅の飽和）のモデル
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum SaturationModel {
+    /// しきい値で振幅を打ち切るハードクリップ
+    #[default]
+    Hard,
+    /// tanhによる緩やかな圧縮（小振幅での利得を指定）
+    Tanh,
+}
+
+/// 周波数掃引の周波数の変化のさせ方
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum SweepMode {
+    /// 掃引時間内で周波数を連続的に直線変化させる
+    Continuous,
+    /// 一定のドウェル時間ごとに等間隔の周波数へステップさせる
+    LinearStep,
+    /// 一定のドウェル時間ごとに対数等間隔の周波数へステップさせる
+    LogStep,
+}
+
+/// SSB変調で残す側波帯
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Sideband {
+    /// 上側波帯 (USB) - 搬送波周波数 + 変調周波数
+    Upper,
+    /// 下側波帯 (LSB) - 搬送波周波数 - 変調周波数
+    Lower,
 }
 
 /// マルチトーン信号の初期位相設定を定義する列挙型
@@ -36,28 +152,641 @@ pub enum MultitonePhase {
     Random,
     /// Schroeder位相 - PAPR（ピーク対平均電力比）を最小化する位相設定
     Schroeder,
+    /// Newman位相 - 2次の位相配置でPAPRを低減する位相設定
+    Newman,
+}
+
+/// 周波数ホッピングのホップ先の周波数の決め方を定義する列挙型
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum HopSet {
+    /// 帯域内ランダム - 搬送波周波数を中心とする帯域をステップ間隔のチャネルに分け、
+    /// シード値に基づいて擬似ランダムに選択
+    Band,
+    /// 周波数リスト - 指定した周波数 (Hz) を順に巡回
+    List(Vec<f64>),
+}
+
+/// マルチトーンを構成する1本のトーン
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Tone {
+    /// 周波数 (Hz)
+    pub freq: f64,
+    /// 相対振幅
+    pub amplitude: f64,
+}
+
+/// マルチトーンのトーン配置を定義する列挙型
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum MultitoneSpec {
+    /// 等間隔配置 - 搬送波周波数を中心にトーン数・間隔に従って対称に配置
+    Uniform,
+    /// 任意配置 - 周波数と振幅を個別に指定したトーンのリスト
+    Explicit(Vec<Tone>),
+}
+
+/// バースト（ゲーティング）の設定
+///
+/// 変調後の信号に、周期ごとに一定時間だけ信号を通す振幅ゲートを掛けます。
+/// ゲートの立ち上がり/立ち下がりはレイズドコサインで遷移し、振幅0.5の点の間隔が
+/// オン時間と一致します。
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct BurstConfig {
+    /// 繰り返し周期 (サンプル)
+    pub period_samples: usize,
+    /// 1周期のうち信号を出力する時間 (サンプル)
+    pub on_samples: usize,
+    /// 立ち上がり/立ち下がり時間 (サンプル) - 0で矩形ゲート
+    pub ramp_samples: usize,
+}
+
+impl BurstConfig {
+    /// 生成開始からn番目のサンプルに掛けるゲートの振幅 (0-1)
+    pub fn gain(&self, n: u64) -> f64 {
+        if self.period_samples == 0 {
+            return 1.0;
+        }
+        let period = self.period_samples as f64;
+        let x = (n % self.period_samples as u64) as f64 / period;
+        pulse_envelope(
+            x,
+            self.on_samples as f64 / period,
+            self.ramp_samples as f64 / period,
+        )
+    }
+}
+
+impl Default for BurstConfig {
+    fn default() -> Self {
+        Self {
+            period_samples: 1000,
+            on_samples: 500,
+            ramp_samples: 20,
+        }
+    }
+}
+
+/// 振幅プロファイルの種類
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum AmplitudeProfileKind {
+    /// 一定振幅（プロファイルなし）
+    Constant,
+    /// 開始レベルから終了レベルまで振幅を直線的に変化させる
+    Ramp,
+    /// 開始レベルから終了レベルまでdBで等間隔の階段状に変化させる
+    Staircase,
+}
+
+/// 振幅プロファイル（時間とともに変化する振幅）の設定
+///
+/// AGCの追従や整定の測定用に、変調方式によらず生成した信号の振幅を時間とともに変化させます。
+/// レベルは信号振幅に対する相対値 (dB) で、プロファイルの終わりに達すると開始レベルから繰り返します。
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct AmplitudeProfile {
+    /// プロファイルの種類
+    pub kind: AmplitudeProfileKind,
+    /// 開始レベル (dB)
+    pub start_db: f64,
+    /// 終了レベル (dB)
+    pub stop_db: f64,
+    /// ランプの長さ (サンプル) - 最初と最後のサンプルがそれぞれ開始/終了レベルになる
+    pub ramp_samples: usize,
+    /// 階段のステップ数（開始/終了レベルを含む）
+    pub steps: usize,
+    /// 階段の各ステップに留まるサンプル数
+    pub dwell_samples: usize,
+}
+
+impl AmplitudeProfile {
+    /// 生成開始からn番目のサンプルに掛ける振幅係数
+    pub fn gain(&self, n: u64) -> f64 {
+        let amplitude = |db: f64| 10f64.powf(db / 20.0);
+        match self.kind {
+            AmplitudeProfileKind::Constant => 1.0,
+            AmplitudeProfileKind::Ramp => {
+                let len = self.ramp_samples.max(1) as u64;
+                let x = if len > 1 {
+                    (n % len) as f64 / (len - 1) as f64
+                } else {
+                    0.0
+                };
+                let (start, stop) = (amplitude(self.start_db), amplitude(self.stop_db));
+                start + (stop - start) * x
+            }
+            AmplitudeProfileKind::Staircase => {
+                let steps = self.steps.max(1) as u64;
+                let dwell = self.dwell_samples.max(1) as u64;
+                let step = n / dwell % steps;
+                let x = if steps > 1 {
+                    step as f64 / (steps - 1) as f64
+                } else {
+                    0.0
+                };
+                amplitude(self.start_db + (self.stop_db - self.start_db) * x)
+            }
+        }
+    }
+}
+
+impl Default for AmplitudeProfile {
+    fn default() -> Self {
+        Self {
+            kind: AmplitudeProfileKind::Constant,
+            start_db: -40.0,
+            stop_db: 0.0,
+            ramp_samples: 10000,
+            steps: 5,
+            dwell_samples: 2000,
+        }
+    }
+}
+
+/// 伝搬路（チャネル）の設定
+///
+/// 遅延・減衰させた複数の経路の和（タップ付き遅延線）でマルチパスを模擬し、
+/// その後にドップラー周波数シフト exp(j·2π·f_d·t) を掛けます。
+/// 遅延線の内容はブロック間で引き継ぎ、生成開始より前のサンプルは0とみなします。
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ChannelConfig {
+    /// ドップラー周波数シフト (Hz)
+    pub doppler_hz: f64,
+    /// 各経路の (遅延 (サンプル), 利得) のリスト
+    pub taps: Vec<(usize, f64)>,
+}
+
+impl ChannelConfig {
+    /// ブロックにチャネルを適用
+    ///
+    /// # 引数
+    /// * `block` - チャネルを通すサンプル配列
+    /// * `history` - 前のブロックまでの入力の末尾（最大遅延分、古い順）。適用後は今回の入力の末尾に更新される
+    /// * `sample_rate` - サンプリングレート (Hz)
+    /// * `start` - ブロックの先頭のサンプルの通し番号（ドップラーシフトの位相をブロック間で連続させる）
+    pub fn apply(
+        &self,
+        block: &mut [Complex<f64>],
+        history: &mut VecDeque<Complex<f64>>,
+        sample_rate: f64,
+        start: u64,
+    ) {
+        // 遅延線：最大遅延分の過去の入力（足りない分は0）に続けて今回の入力を並べる
+        let max_delay = self.taps.iter().map(|&(delay, _)| delay).max().unwrap_or(0);
+        while history.len() < max_delay {
+            history.push_front(Complex::new(0.0, 0.0));
+        }
+        history.drain(..history.len() - max_delay);
+        let input: Vec<Complex<f64>> = history.iter().chain(block.iter()).copied().collect();
+        for (n, sample) in block.iter_mut().enumerate() {
+            *sample = self
+                .taps
+                .iter()
+                .map(|&(delay, gain)| input[max_delay + n - delay] * gain)
+                .sum();
+        }
+        history.clear();
+        history.extend(&input[input.len() - max_delay..]);
+        if self.doppler_hz != 0.0 {
+            for (n, sample) in (start..).zip(block.iter_mut()) {
+                // 長時間の生成でも精度を保つため、周期の端数部分から位相を求める
+                let cycles = (self.doppler_hz * n as f64 / sample_rate).fract();
+                *sample *= Complex::from_polar(1.0, 2.0 * PI * cycles);
+            }
+        }
+    }
+}
+
+impl Default for ChannelConfig {
+    /// ドップラーシフトなし、遅延0・利得1の1経路（素通し）
+    fn default() -> Self {
+        Self {
+            doppler_hz: 0.0,
+            taps: vec![(0, 1.0)],
+        }
+    }
+}
+
+/// OFDMの設定
+///
+/// 1シンボルはFFTサイズ個のサブキャリアを逆FFTで合成したサンプルの先頭に、
+/// 末尾のサイクリックプレフィックス長分をコピーしたものです。
+/// サブキャリア間隔は サンプリングレート/FFTサイズ になります。
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct OfdmConfig {
+    /// FFTサイズ（サブキャリア数）
+    pub num_subcarriers: usize,
+    /// データを載せるサブキャリア数 - DCを中心に正負の周波数へ振り分ける
+    pub active_subcarriers: usize,
+    /// DCのサブキャリアを使用しないかどうか
+    pub null_dc: bool,
+    /// サイクリックプレフィックスの長さ (サンプル)
+    pub cp_len: usize,
+    /// 各サブキャリアのQAMの多値数（4または16）
+    pub qam_order: usize,
+}
+
+impl OfdmConfig {
+    /// 1シンボルのサンプル数（FFTサイズ + サイクリックプレフィックス長）
+    pub fn symbol_len(&self) -> usize {
+        self.num_subcarriers + self.cp_len
+    }
+
+    /// FFTのビン番号ごとに、データを載せるかどうかを表すマスク
+    ///
+    /// ビン0がDC、ビン1からN/2-1が正の周波数、N/2以降が負の周波数です。
+    /// 使用するサブキャリアはDCに近いものから正、負の周波数の順に交互に割り当てます。
+    pub fn active_mask(&self) -> Vec<bool> {
+        let n = self.num_subcarriers;
+        let mut mask = vec![false; n];
+        let mut remaining = self
+            .active_subcarriers
+            .min(n - usize::from(self.null_dc && n > 0));
+        if !self.null_dc && remaining > 0 {
+            mask[0] = true;
+            remaining -= 1;
+        }
+        for k in 1..n {
+            for bin in [k, n - k] {
+                if remaining > 0 && !mask[bin] {
+                    mask[bin] = true;
+                    remaining -= 1;
+                }
+            }
+        }
+        mask
+    }
+}
+
+impl Default for OfdmConfig {
+    /// FFTサイズ64、52サブキャリア（DCを除く）、CP長16、QPSK（IEEE 802.11a相当の構成）
+    fn default() -> Self {
+        Self {
+            num_subcarriers: 64,
+            active_subcarriers: 52,
+            null_dc: true,
+            cp_len: 16,
+            qam_order: 4,
+        }
+    }
+}
+
+/// PRBS（疑似ランダムビット列）の次数を定義する列挙型
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum PrbsOrder {
+    /// PRBS-9 (x^9 + x^5 + 1、周期511ビット)
+    Prbs9,
+    /// PRBS-15 (x^15 + x^14 + 1、周期32767ビット)
+    Prbs15,
+}
+
+impl PrbsOrder {
+    /// LFSRのレジスタ長と帰還タップ位置を返す
+    fn taps(self) -> (u32, u32) {
+        match self {
+            PrbsOrder::Prbs9 => (9, 5),
+            PrbsOrder::Prbs15 => (15, 14),
+        }
+    }
+}
+
+/// LFSR（線形帰還シフトレジスタ）によるPRBS生成器
+struct Prbs {
+    /// PRBSの次数
+    order: PrbsOrder,
+    /// 初期化に使用したシード値
+    seed: u64,
+    /// シフトレジスタの現在状態
+    state: u32,
+}
+
+impl Prbs {
+    /// シード値からPRBS生成器を初期化
+    ///
+    /// LFSRは全ゼロ状態から抜け出せないため、シードの下位ビットが
+    /// すべて0の場合は全ビット1の状態から開始します。
+    fn new(order: PrbsOrder, seed: u64) -> Self {
+        let (len, _) = order.taps();
+        let mask = (1u32 << len) - 1;
+        let mut state = (seed as u32) & mask;
+        if state == 0 {
+            state = mask;
+        }
+        Self { order, seed, state }
+    }
+
+    /// 次のビット（0または1）を生成
+    fn next_bit(&mut self) -> u8 {
+        let (len, tap) = self.order.taps();
+        let bit = ((self.state >> (len - 1)) ^ (self.state >> (tap - 1))) & 1;
+        self.state = ((self.state << 1) | bit) & ((1u32 << len) - 1);
+        bit as u8
+    }
+}
+
+/// 2ビットをGray符号化されたQPSKのコンスタレーション点に割り当てる
+///
+/// 1ビット目をI軸、2ビット目をQ軸の符号に対応させるため、
+/// 隣接するシンボル間のビット差は常に1ビットになります。
+///
+/// # 戻り値
+/// (±1±j)/√2 のいずれかのシンボル
+fn qpsk_symbol(b0: u8, b1: u8) -> Complex<f64> {
+    let i = if b0 == 1 { -1.0 } else { 1.0 };
+    let q = if b1 == 1 { -1.0 } else { 1.0 };
+    Complex::new(i, q) / 2.0_f64.sqrt()
+}
+
+/// 3ビットをGray符号化された8PSKのコンスタレーション点に割り当てる
+///
+/// ビット列をGray符号として復号した番号kに対し、位相 (2k+1)π/8 の点を返すため、
+/// 隣接するシンボル間のビット差は常に1ビットになります。
+///
+/// # 戻り値
+/// 単位円上の8点のいずれかのシンボル
+fn psk8_symbol(bits: [u8; 3]) -> Complex<f64> {
+    let gray = (bits[0] << 2) | (bits[1] << 1) | bits[2];
+    let k = gray ^ (gray >> 1) ^ (gray >> 2);
+    Complex::from_polar(1.0, (2 * k + 1) as f64 * PI / 8.0)
+}
+
+/// 4ビットを16QAMのコンスタレーション点に割り当てる
+///
+/// 上位2ビットをI軸、下位2ビットをQ軸のレベル（-3, -1, +1, +3）に
+/// Gray符号で対応させます。平均電力が1になるよう1/√10で正規化します。
+///
+/// # 戻り値
+/// 16QAMのシンボル（平均電力1）
+fn qam16_symbol(bits: [u8; 4]) -> Complex<f64> {
+    // Gray符号: 00→-3, 01→-1, 11→+1, 10→+3
+    let level = |b0: u8, b1: u8| match (b0, b1) {
+        (0, 0) => -3.0,
+        (0, 1) => -1.0,
+        (1, 1) => 1.0,
+        _ => 3.0,
+    };
+    let i = level(bits[0], bits[1]);
+    let q = level(bits[2], bits[3]);
+    Complex::new(i, q) / 10.0_f64.sqrt()
+}
+
+/// 周波数掃引の時刻tにおける瞬時周波数を計算
+///
+/// ステップ掃引では掃引時間をドウェル時間ごとのステップに分け、最初のステップを開始周波数、
+/// 最後のステップを終了周波数とします。対数ステップは開始・終了周波数が正の場合のみ有効で、
+/// それ以外では等間隔のステップになります。
+///
+/// # 引数
+/// * `params` - 信号生成パラメータ
+/// * `t` - 生成開始からの経過時間 (秒)
+///
+/// # 戻り値
+/// 瞬時周波数 (Hz)
+fn sweep_frequency(params: &SignalParams, t: f64) -> f64 {
+    let t = t % params.sweep_time;
+    let (start, stop) = (params.sweep_start, params.sweep_stop);
+    if params.sweep_mode == SweepMode::Continuous {
+        return start + (stop - start) * t / params.sweep_time;
+    }
+
+    // 掃引時間内のステップ数と、時刻tが属するステップ
+    let dwell = params.sweep_dwell.max(1) as f64;
+    let steps = (params.sweep_time * params.sample_rate / dwell)
+        .round()
+        .max(1.0);
+    if steps < 2.0 {
+        return start;
+    }
+    let step = (t * params.sample_rate / dwell).floor().min(steps - 1.0);
+    let x = step / (steps - 1.0);
+    if params.sweep_mode == SweepMode::LogStep && start > 0.0 && stop > 0.0 {
+        start * (stop / start).powf(x)
+    } else {
+        start + (stop - start) * x
+    }
 }
 
 /// 信号生成に必要なパラメータを保持する構造体
+#[derive(Clone, PartialEq, Debug)]
 pub struct SignalParams {
     /// 搬送波周波数 (Hz)
     pub frequency: f64,
+    /// 搬送波の初期位相 (度) - 生成開始時の位相で、すべての変調方式のサンプルを回転させる
+    pub initial_phase_deg: f64,
     /// サンプリングレート (Hz)
     pub sample_rate: f64,
     /// 変調方式
     pub mod_type: ModulationType,
     /// 変調周波数 (Hz) - AM/FM/PM/Pulseで使用
     pub mod_freq: f64,
-    /// 変調強度 - AM: 変調指数, FM: 偏移量(Hz), PM: 変調指数(Beta), Pulse: デューティサイクル
+    /// 変調強度 - AM: 変調指数, FM: ピーク周波数偏移(Hz), PM: 変調指数(Beta), Pulse: デューティサイクル
     pub mod_strength: f64,
+    /// AMの変調波形
+    pub am_waveform: AmWaveform,
     /// マルチトーンのトーン数
     pub multitone_count: usize,
     /// マルチトーンの周波数間隔 (Hz)
     pub multitone_spacing: f64,
     /// マルチトーンの初期位相設定
     pub multitone_phase: MultitonePhase,
-    /// ランダム位相生成用のシード値
+    /// マルチトーンのトーン配置（等間隔/任意）
+    pub multitone_spec: MultitoneSpec,
+    /// NPR測定用ノッチの先頭のトーン番号
+    pub notch_start_tone: usize,
+    /// NPR測定用ノッチで取り除くトーン数 - 0でノッチなし
+    pub notch_width_tones: usize,
+    /// 2トーンの周波数間隔 (Hz) - 2つのトーンは搬送波周波数 ± 間隔/2 に配置
+    pub tone_spacing: f64,
+    /// ランダム位相生成用のシード値（デジタル変調ではPRBSの初期値）
     pub seed: u64,
+    /// SSB変調で残す側波帯
+    pub ssb_sideband: Sideband,
+    /// シンボルレート (シンボル/秒) - デジタル変調で使用
+    pub symbol_rate: f64,
+    /// PRBSの次数 - デジタル変調で使用
+    pub prbs_order: PrbsOrder,
+    /// ASKでビット0のときの振幅 (0-1、0でOOK)
+    pub ask_low_level: f64,
+    /// PSKの多値数（2, 4, 8のいずれか）
+    pub psk_order: usize,
+    /// FSKの周波数偏移 (Hz) - ビットに応じて搬送波から±この値だけずらす
+    pub fsk_deviation: f64,
+    /// FSK/MSKの周波数遷移をガウスフィルタで平滑化するかどうか（GFSK/GMSK）
+    pub fsk_gaussian: bool,
+    /// GFSK/GMSKのガウスフィルタの帯域幅・シンボル周期積 (BT)
+    pub fsk_bt: f64,
+    /// デジタル変調（FSK/MSKを除く）のシンボル列をルートレイズドコサインフィルタで整形するかどうか
+    pub pulse_shaping: bool,
+    /// ルートレイズドコサインフィルタのロールオフ率 α (0-1)
+    pub rrc_rolloff: f64,
+    /// ルートレイズドコサインフィルタの長さ (シンボル)
+    pub rrc_span: usize,
+    /// パルスの立ち上がり/立ち下がり時間 (秒) - 0で矩形パルス
+    pub pulse_edge_time: f64,
+    /// チャープの掃引帯域幅 (Hz)
+    pub chirp_bandwidth: f64,
+    /// チャープの掃引時間 (秒) - この時間ごとに掃引を繰り返す
+    pub chirp_duration: f64,
+    /// 掃引の開始周波数 (Hz)
+    pub sweep_start: f64,
+    /// 掃引の終了周波数 (Hz)
+    pub sweep_stop: f64,
+    /// 1回の掃引にかける時間 (秒) - この時間ごとに開始周波数に戻る
+    pub sweep_time: f64,
+    /// 掃引の周波数の変化のさせ方（連続/ステップ）
+    pub sweep_mode: SweepMode,
+    /// ステップ掃引で各周波数に留まるサンプル数
+    pub sweep_dwell: usize,
+    /// 周波数ホッピングのホップ先の決め方（帯域内ランダム/リスト）
+    pub hop_set: HopSet,
+    /// 周波数ホッピングの帯域幅 (Hz) - 帯域内ランダムで使用
+    pub hop_bandwidth: f64,
+    /// 周波数ホッピングのチャネル間隔 (Hz) - 帯域内ランダムで使用
+    pub hop_step: f64,
+    /// 1ホップあたりの滞在時間 (秒)
+    pub hop_dwell: f64,
+    /// ホップのたびに搬送波の位相を0に戻すかどうか（falseでは位相連続）
+    pub hop_reset_phase: bool,
+    /// 生成した信号に白色ガウス雑音を付加するかどうか
+    pub add_noise: bool,
+    /// 雑音付加時の信号対雑音比 (dB)
+    pub snr_db: f64,
+    /// IQ不平衡：Q経路に対するI経路の利得比 (dB)
+    pub iq_gain_imbalance_db: f64,
+    /// IQ不平衡：直交位相の誤差 (度)
+    pub iq_phase_error_deg: f64,
+    /// I成分に加えるDCオフセット（キャリアリーク）
+    pub dc_offset_i: f64,
+    /// Q成分に加えるDCオフセット（キャリアリーク）
+    pub dc_offset_q: f64,
+    /// 位相雑音：1サンプルあたりの位相のランダムウォークの標準偏差 (度)
+    pub phase_noise_rms_deg: f64,
+    /// 変調方式によらず信号の振幅を時間とともに変化させるプロファイル
+    pub amplitude_profile: AmplitudeProfile,
+    /// 変調後の信号にバーストのゲートを掛けるかどうか
+    pub burst_enabled: bool,
+    /// バーストの周期・オン時間・立ち上がり時間
+    pub burst: BurstConfig,
+    /// 送信信号にチャネル（マルチパス・ドップラーシフト）を適用するかどうか
+    pub channel_enabled: bool,
+    /// チャネルのドップラーシフトと遅延タップ
+    pub channel: ChannelConfig,
+    /// マルチトーンの各トーンの振幅に掛ける窓関数（トーンの並び順に適用）
+    pub multitone_amplitude_window: WindowFunction,
+    /// OFDMのサブキャリア数・サイクリックプレフィックス長・サブキャリアの変調
+    pub ofdm: OfdmConfig,
+}
+
+impl Default for SignalParams {
+    /// 1kHzのCW信号（サンプリングレート100kHz）を表すデフォルト値
+    fn default() -> Self {
+        Self {
+            frequency: 1000.0,
+            initial_phase_deg: 0.0,
+            sample_rate: 100000.0,
+            mod_type: ModulationType::CW,
+            mod_freq: 0.0,
+            mod_strength: 0.0,
+            am_waveform: AmWaveform::Sine,
+            multitone_count: 1,
+            multitone_spacing: 0.0,
+            multitone_phase: MultitonePhase::Zero,
+            multitone_spec: MultitoneSpec::Uniform,
+            notch_start_tone: 0,
+            notch_width_tones: 0,
+            tone_spacing: 1000.0,
+            seed: 0,
+            ssb_sideband: Sideband::Upper,
+            symbol_rate: 1000.0,
+            prbs_order: PrbsOrder::Prbs9,
+            ask_low_level: 0.0,
+            psk_order: 4,
+            fsk_deviation: 1000.0,
+            fsk_gaussian: false,
+            fsk_bt: 0.5,
+            pulse_shaping: false,
+            rrc_rolloff: 0.35,
+            rrc_span: 8,
+            pulse_edge_time: 0.0,
+            chirp_bandwidth: 10000.0,
+            chirp_duration: 0.01,
+            sweep_start: 1000.0,
+            sweep_stop: 10000.0,
+            sweep_time: 0.01,
+            sweep_mode: SweepMode::Continuous,
+            sweep_dwell: 100,
+            hop_set: HopSet::Band,
+            hop_bandwidth: 10000.0,
+            hop_step: 1000.0,
+            hop_dwell: 0.001,
+            hop_reset_phase: false,
+            add_noise: false,
+            snr_db: 20.0,
+            iq_gain_imbalance_db: 0.0,
+            iq_phase_error_deg: 0.0,
+            dc_offset_i: 0.0,
+            dc_offset_q: 0.0,
+            phase_noise_rms_deg: 0.0,
+            amplitude_profile: AmplitudeProfile::default(),
+            burst_enabled: false,
+            burst: BurstConfig::default(),
+            channel_enabled: false,
+            channel: ChannelConfig::default(),
+            multitone_amplitude_window: WindowFunction::Rectangular,
+            ofdm: OfdmConfig::default(),
+        }
+    }
+}
+
+impl SignalParams {
+    /// マルチトーンのトーン数
+    pub fn multitone_tone_count(&self) -> usize {
+        match &self.multitone_spec {
+            MultitoneSpec::Uniform => self.multitone_count,
+            MultitoneSpec::Explicit(tones) => tones.len(),
+        }
+    }
+
+    /// マルチトーンのk番目のトーン
+    ///
+    /// 等間隔配置では f_k = f_center + (k - (N-1)/2) * spacing、振幅1のトーンになります。
+    /// 振幅には`multitone_amplitude_window`のk番目の係数が掛かり、
+    /// NPR測定用ノッチに含まれるトーンは振幅0になり、合成されません。
+    fn multitone_tone(&self, k: usize) -> Tone {
+        let tone = self.multitone_unnotched_tone(k);
+        let notch = self.notch_start_tone..self.notch_start_tone + self.notch_width_tones;
+        let taper = if notch.contains(&k) {
+            0.0
+        } else {
+            tone_window_coefficient(
+                self.multitone_amplitude_window,
+                self.multitone_tone_count(),
+                k,
+            )
+        };
+        Tone {
+            amplitude: tone.amplitude * taper,
+            ..tone
+        }
+    }
+
+    /// ノッチと振幅の窓を適用する前のマルチトーンのk番目のトーン
+    fn multitone_unnotched_tone(&self, k: usize) -> Tone {
+        match &self.multitone_spec {
+            MultitoneSpec::Uniform => {
+                let center_offset = (self.multitone_count as f64 - 1.0) / 2.0;
+                Tone {
+                    freq: self.frequency + (k as f64 - center_offset) * self.multitone_spacing,
+                    amplitude: 1.0,
+                }
+            }
+            MultitoneSpec::Explicit(tones) => tones[k],
+        }
+    }
+
+    /// マルチトーンを構成するすべてのトーン
+    pub fn multitone_tones(&self) -> Vec<Tone> {
+        (0..self.multitone_tone_count())
+            .map(|k| self.multitone_tone(k))
+            .collect()
+    }
 }
 
 /// 信号を生成するジェネレータ構造体
@@ -68,10 +797,72 @@ pub struct SignalGenerator {
     phase: f64,
     /// 変調信号の現在位相 (ラジアン)
     mod_phase: f64,
+    /// 生成済みのサンプル数（経過時間の計算に使用）
+    sample_count: u64,
     /// マルチトーン信号の各トーンの位相 (ラジアン)
     multitone_phases: Vec<f64>,
+    /// マルチトーン信号を構成するトーン（ブロックごとにパラメータから計算）
+    multitone_tones: Vec<Tone>,
+    /// 現在の位相テーブルを構築したときの初期位相設定とシード値
+    multitone_phase_source: Option<(MultitonePhase, u64)>,
+    /// デジタル変調用のPRBS生成器（初回使用時に生成）
+    prbs: Option<Prbs>,
+    /// 次のシンボル境界までの残り時間（シンボル周期単位）
+    ///
+    /// ブロックの末尾で途中になったシンボルも、次の呼び出しで継続されます。
+    symbol_clock: f64,
+    /// 現在送出中のシンボル（ベースバンドの複素数値）
+    current_symbol: Complex<f64>,
+    /// GFSKのガウスフィルタ係数と、その計算に使用した (BT, 1シンボルあたりのサンプル数)
+    gaussian_taps: Option<((f64, f64), Vec<f64>)>,
+    /// GFSKのガウスフィルタに入力した直近の周波数シンボル（±1）
+    fsk_history: VecDeque<f64>,
+    /// ルートレイズドコサインフィルタ係数と、その計算に使用した (α, スパン, 1シンボルあたりのサンプル数)
+    rrc_taps: Option<((f64, usize, f64), Vec<f64>)>,
+    /// ルートレイズドコサインフィルタに入力した直近のサンプル（シンボル境界のみシンボル値、他は0）
+    symbol_history: VecDeque<Complex<f64>>,
+    /// 周波数ホッピングの現在のホップの残りサンプル数（0以下で次のホップに移る）
+    hop_clock: f64,
+    /// これまでのホップ数（周波数リストの巡回に使用）
+    hop_count: usize,
+    /// 現在のホップの周波数 (Hz)
+    hop_frequency: f64,
+    /// ホップ先の選択用の乱数生成器と、その初期化に使用したシード値
+    hop_rng: Option<(u64, StdRng)>,
+    /// 送出待ちのOFDMシンボルのサンプル（サイクリックプレフィックスを含む）
+    ofdm_samples: VecDeque<Complex<f64>>,
+    /// OFDMのサブキャリアのデータ用の乱数生成器と、その初期化に使用したシード値
+    ofdm_rng: Option<(u64, StdRng)>,
+    /// OFDMシンボルの合成に使用する逆FFT（FFTサイズの変更時に作り直す）
+    ofdm_ifft: Option<Arc<dyn Fft<f64>>>,
+    /// 雑音生成用の乱数生成器と、その初期化に使用したシード値
+    noise_rng: Option<(u64, StdRng)>,
+    /// 付加雑音用の乱数生成器と、その初期化に使用したシード値
+    awgn_rng: Option<(u64, StdRng)>,
+    /// 位相雑音用の乱数生成器と、その初期化に使用したシード値
+    phase_noise_rng: Option<(u64, StdRng)>,
+    /// 位相雑音として積算した位相のずれ (ラジアン)
+    phase_noise: f64,
+    /// バーストのゲートを掛けたサンプル数（ゲートの周期内の位置の計算に使用）
+    burst_position: u64,
+    /// チャネルを通したサンプル数（ドップラーシフトの位相の計算に使用）
+    channel_position: u64,
+    /// チャネルの遅延線に残っている直近の入力サンプル（最大遅延分）
+    channel_history: VecDeque<Complex<f64>>,
 }
 
+/// 付加雑音用の乱数系列をマルチトーン位相や雑音信号の系列と独立させるためのシード値の変換定数
+const AWGN_SEED_SALT: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// 位相雑音用の乱数系列を他の系列と独立させるためのシード値の変換定数
+const PHASE_NOISE_SEED_SALT: u64 = 0xD1B5_4A32_D192_ED03;
+
+/// ホップ先の乱数系列を他の系列と独立させるためのシード値の変換定数
+const HOP_SEED_SALT: u64 = 0x94D0_49BB_1331_11EB;
+
+/// OFDMのサブキャリアのデータ系列を他の系列と独立させるためのシード値の変換定数
+const OFDM_SEED_SALT: u64 = 0xBF58_476D_1CE4_E5B9;
+
 impl SignalGenerator {
     /// 新しいSignalGeneratorインスタンスを生成
     ///
@@ -80,10 +871,43 @@ impl SignalGenerator {
         Self {
             phase: 0.0,
             mod_phase: 0.0,
+            sample_count: 0,
             multitone_phases: Vec::new(),
+            multitone_tones: Vec::new(),
+            multitone_phase_source: None,
+            prbs: None,
+            symbol_clock: 0.0,
+            current_symbol: Complex::new(1.0, 0.0),
+            gaussian_taps: None,
+            fsk_history: VecDeque::new(),
+            rrc_taps: None,
+            symbol_history: VecDeque::new(),
+            hop_clock: 0.0,
+            hop_count: 0,
+            hop_frequency: 0.0,
+            hop_rng: None,
+            ofdm_samples: VecDeque::new(),
+            ofdm_rng: None,
+            ofdm_ifft: None,
+            noise_rng: None,
+            awgn_rng: None,
+            phase_noise_rng: None,
+            phase_noise: 0.0,
+            burst_position: 0,
+            channel_position: 0,
+            channel_history: VecDeque::new(),
         }
     }
 
+    /// 内部状態を初期化
+    ///
+    /// 位相・経過時間・シンボル状態をすべて生成開始時の状態に戻します。
+    /// 位相に影響するパラメータが変更されたときにのみ呼び出すことで、
+    /// それ以外のときは連続したサンプル列を生成し続けられます。
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
     /// 次のサンプルを生成
     ///
     /// 指定されたパラメータに基づいて、複素数形式のI/Qサンプルを1つ生成します。
@@ -95,11 +919,45 @@ impl SignalGenerator {
     /// # 戻り値
     /// 複素数形式のI/Qサンプル (I=実部、Q=虚部)
     pub fn next_sample(&mut self, params: &SignalParams) -> Complex<f64> {
+        // 振幅プロファイルは変調方式によらず、変調後のサンプルに時間変化する振幅係数として掛ける
+        // 初期位相も同様に、位相0から生成したサンプルを一定の位相だけ回転させて与える
+        let amplitude_factor = params.amplitude_profile.gain(self.sample_count);
+        let rotation = Complex::from_polar(amplitude_factor, params.initial_phase_deg.to_radians());
+        self.next_modulated_sample(params) * rotation
+    }
+
+    /// 振幅プロファイルを掛ける前の、変調方式に応じた次のサンプルを生成
+    ///
+    /// # 引数
+    /// * `params` - 信号生成パラメータ
+    ///
+    /// # 戻り値
+    /// 複素数形式のI/Qサンプル
+    fn next_modulated_sample(&mut self, params: &SignalParams) -> Complex<f64> {
+        // 経過時間を計算するためのサンプル番号
+        let n = self.sample_count;
+        self.sample_count += 1;
+
         // マルチトーン信号の場合は専用の処理に分岐
         if params.mod_type == ModulationType::Multitone {
             return self.next_multitone_sample(params);
         }
 
+        // 雑音信号の場合は専用の処理に分岐
+        if params.mod_type == ModulationType::Noise {
+            return self.next_noise_sample(params);
+        }
+
+        // デジタル変調の場合は専用の処理に分岐
+        if params.mod_type.is_digital() {
+            return self.next_digital_sample(params);
+        }
+
+        // OFDMの場合は専用の処理に分岐
+        if params.mod_type == ModulationType::OFDM {
+            return self.next_ofdm_sample(params);
+        }
+
         // 変調信号の位相を更新
         let mod_phase_increment = 2.0 * PI * params.mod_freq / params.sample_rate;
         self.mod_phase += mod_phase_increment;
@@ -118,34 +976,88 @@ impl SignalGenerator {
             }
             ModulationType::AM => {
                 // AM: 振幅を変調
-                // A(t) = A₀[1 + m·cos(2πf_m·t)]
-                amplitude_factor = 1.0 + params.mod_strength * self.mod_phase.cos();
+                // A(t) = A₀[1 + m·w(2πf_m·t)]  (wは正弦波ではcos、他の波形も-1〜1の範囲)
+                amplitude_factor =
+                    1.0 + params.mod_strength * params.am_waveform.value(self.mod_phase);
             }
             ModulationType::FM => {
-                // FM: 周波数を変調
-                // f(t) = f_c + Δf·cos(2πf_m·t)
-                current_freq = params.frequency + params.mod_strength * self.mod_phase.cos();
+                // FM: 瞬時周波数 f(t) = f_c + Δf·cos(2πf_m·t) を積分して搬送波の位相に加算
+                // 1サンプル区間の積分 ∫2πΔf·cos(θ)dt = (Δf/f_m)·(sin θ₁ - sin θ₀) を
+                // 厳密に計算するため、変調指数 β = Δf/f_m のベッセル関数どおりの側波帯になる
+                // 位相を積算していくので、途中で変調周波数を変えても位相は連続する
+                let prev_mod_phase = self.mod_phase - mod_phase_increment;
+                self.phase += if params.mod_freq > 0.0 {
+                    params.mod_strength / params.mod_freq
+                        * (self.mod_phase.sin() - prev_mod_phase.sin())
+                } else {
+                    2.0 * PI * params.mod_strength * self.mod_phase.cos() / params.sample_rate
+                };
             }
-            ModulationType::PM => {
-                // PM: 位相を変調（位相出力時に処理）
+            ModulationType::PM | ModulationType::SSB => {
+                // PM/SSB: 位相を変調（出力時に処理）
             }
             ModulationType::Pulse => {
                 // Pulse: デューティサイクルに基づいてON/OFFを切り替え
-                if self.mod_phase < params.mod_strength * 2.0 * PI {
-                    amplitude_factor = 1.0;
-                } else {
-                    amplitude_factor = 0.0;
+                // 立ち上がり/立ち下がりはレイズドコサインで滑らかにする
+                amplitude_factor = pulse_envelope(
+                    self.mod_phase / (2.0 * PI),
+                    params.mod_strength,
+                    params.pulse_edge_time * params.mod_freq,
+                );
+            }
+            ModulationType::TwoTone => {
+                // TwoTone: f_c ± Δ/2 の2トーンを等振幅で合成
+                // (e^{j2π(f_c+Δ/2)t} + e^{j2π(f_c-Δ/2)t})/2 = e^{j2πf_c·t}·cos(2π(Δ/2)t)
+                // 搬送波の位相と同じ時刻（1サンプル先）で評価し、合成波のピークは1.0になる
+                let t = (n + 1) as f64 / params.sample_rate;
+                amplitude_factor = (PI * params.tone_spacing * t).cos();
+            }
+            ModulationType::Chirp => {
+                // Chirp: 瞬時周波数を掃引時間内で線形に変化させる
+                // f(t) = f_c - B/2 + (B/T)·t  (tは掃引時間Tで折り返す)
+                // サンプル区間の中点の周波数を用いることで、位相は瞬時周波数の
+                // 積分（2次関数）と一致し、掃引中の位相は連続になる
+                let t = (n as f64 + 0.5) / params.sample_rate;
+                let t = t % params.chirp_duration;
+                current_freq = params.frequency - params.chirp_bandwidth / 2.0
+                    + params.chirp_bandwidth / params.chirp_duration * t;
+            }
+            ModulationType::Sweep => {
+                // Sweep: 掃引時間内で開始周波数から終了周波数まで線形に変化させる
+                // 搬送波周波数は使用せず、サンプル区間の中点の周波数で位相を積算する
+                let t = (n as f64 + 0.5) / params.sample_rate;
+                current_freq = sweep_frequency(params, t);
+            }
+            ModulationType::Hopping => {
+                // Hopping: 滞在時間ごとに次のホップ周波数に切り替える
+                // 滞在時間がサンプル周期の整数倍でない場合も、端数を次のホップに持ち越して平均の滞在時間を保つ
+                if self.hop_clock <= 0.0 {
+                    self.hop_clock += params.hop_dwell * params.sample_rate;
+                    self.hop_frequency = self.next_hop_frequency(params);
+                    if params.hop_reset_phase {
+                        // ホップの最初のサンプルの位相が0になるように、1サンプル分戻しておく
+                        self.phase = -2.0 * PI * self.hop_frequency / params.sample_rate;
+                    }
                 }
+                self.hop_clock -= 1.0;
+                current_freq = self.hop_frequency;
             }
-            ModulationType::Multitone => unreachable!(),
+            ModulationType::Multitone
+            | ModulationType::BPSK
+            | ModulationType::QPSK
+            | ModulationType::QAM16
+            | ModulationType::ASK
+            | ModulationType::FSK
+            | ModulationType::MSK
+            | ModulationType::PSK
+            | ModulationType::Noise
+            | ModulationType::OFDM => unreachable!(),
         }
 
         // 搬送波の位相を更新
+        // FMでは位相が減少する場合もあるため、[0, 2π)の範囲に折り返す
         let phase_increment = 2.0 * PI * current_freq / params.sample_rate;
-        self.phase += phase_increment;
-        if self.phase > 2.0 * PI {
-            self.phase -= 2.0 * PI;
-        }
+        self.phase = (self.phase + phase_increment).rem_euclid(2.0 * PI);
 
         // 最終的な位相を計算（PM変調の場合は位相変調を適用）
         let mut final_phase = self.phase;
@@ -155,133 +1067,2609 @@ impl SignalGenerator {
         }
 
         // 極座標形式から複素数を生成 (振幅, 位相) -> I+jQ
-        Complex::from_polar(amplitude_factor, final_phase)
+        let carrier = Complex::from_polar(amplitude_factor, final_phase);
+        if params.mod_type == ModulationType::SSB {
+            // SSB: 変調信号 m(t) = cos(2πf_m·t) の解析信号 m(t) ± j·H{m(t)} を搬送波で周波数変換
+            // H{cos} = sin なので、USBは cos + j·sin、LSBは cos - j·sin となり
+            // 不要な側波帯は打ち消される
+            let sign = match params.ssb_sideband {
+                Sideband::Upper => 1.0,
+                Sideband::Lower => -1.0,
+            };
+            let analytic = Complex::new(self.mod_phase.cos(), sign * self.mod_phase.sin());
+            return carrier * analytic;
+        }
+        carrier
     }
 
-    /// マルチトーン信号の次のサンプルを生成
+    /// デジタル変調信号の次のサンプルを生成
     ///
-    /// 複数のトーン（正弦波）を合成してマルチトーン信号を生成します。
-    /// 初回呼び出し時に、指定された初期位相設定に基づいて各トーンの位相を初期化します。
+    /// シンボル周期ごとにPRBSから新しいシンボルを取り出し、
+    /// 次のシンボル境界まで値を保持（矩形ホールド）して搬送波に乗せます。
     ///
     /// # 引数
     /// * `params` - 信号生成パラメータ
     ///
     /// # 戻り値
     /// 複素数形式のI/Qサンプル
-    fn next_multitone_sample(&mut self, params: &SignalParams) -> Complex<f64> {
-        // 初回呼び出し時または設定変更時に位相を初期化
-        if self.multitone_phases.len() != params.multitone_count {
-            self.multitone_phases = Vec::with_capacity(params.multitone_count);
-            let n = params.multitone_count as f64;
-
-            match params.multitone_phase {
-                MultitonePhase::Zero => {
-                    // すべての位相を0に設定
-                    for _ in 0..params.multitone_count {
-                        self.multitone_phases.push(0.0);
-                    }
-                }
-                MultitonePhase::Random => {
-                    // シード値を使用してランダムな位相を生成
-                    let mut rng = StdRng::seed_from_u64(params.seed);
-                    for _ in 0..params.multitone_count {
-                        self.multitone_phases.push(rng.random_range(0.0..2.0 * PI));
-                    }
-                }
-                MultitonePhase::Schroeder => {
-                    // Schroeder位相：PAPR（ピーク対平均電力比）を最小化
-                    // φ_k = -π·k·(k-1)/N
-                    for k in 0..params.multitone_count {
-                        let k_f = k as f64;
-                        let phi = -PI * k_f * (k_f - 1.0) / n;
-                        self.multitone_phases.push(phi);
-                    }
-                }
-            }
+    fn next_digital_sample(&mut self, params: &SignalParams) -> Complex<f64> {
+        // シンボル境界に達したら次のシンボルを取り出す
+        let symbol_start = self.symbol_clock <= 0.0;
+        if symbol_start {
+            self.current_symbol = self.next_symbol(params);
+            self.symbol_clock += 1.0;
         }
+        self.symbol_clock -= params.symbol_rate / params.sample_rate;
 
-        // すべてのトーンを合成
-        let mut i_sum = 0.0;
-        let mut q_sum = 0.0;
-
-        // 中心周波数からのオフセットを計算（中心周波数を基準に対称に配置）
-        let center_offset = (params.multitone_count as f64 - 1.0) / 2.0;
-
-        for (k, phase) in self.multitone_phases.iter_mut().enumerate() {
-            // 各トーンの周波数を計算
-            // f_k = f_center + (k - N/2) * spacing
-            let freq_offset = (k as f64 - center_offset) * params.multitone_spacing;
-            let tone_freq = params.frequency + freq_offset;
-
-            // 位相を更新
-            let phase_inc = 2.0 * PI * tone_freq / params.sample_rate;
-            *phase += phase_inc;
-            if *phase > 2.0 * PI {
-                *phase -= 2.0 * PI;
-            }
+        // FSK: シンボル（±1）に応じて搬送波の周波数をずらし、位相を積算して連続させる
+        // MSKは偏移をシンボルレートの1/4（変調指数0.5）に固定したFSKで、1シンボルで位相が±π/2進む
+        if matches!(params.mod_type, ModulationType::FSK | ModulationType::MSK) {
+            let deviation = if params.mod_type == ModulationType::MSK {
+                params.symbol_rate / 4.0
+            } else {
+                params.fsk_deviation
+            };
+            let shift = if params.fsk_gaussian {
+                self.gaussian_filter(params, self.current_symbol.re)
+            } else {
+                self.current_symbol.re
+            };
+            let freq = params.frequency + deviation * shift;
+            self.phase = (self.phase + 2.0 * PI * freq / params.sample_rate).rem_euclid(2.0 * PI);
+            return Complex::from_polar(1.0, self.phase);
+        }
 
-            // I/Q成分を計算して合成
-            let (sin, cos) = phase.sin_cos();
-            i_sum += cos;
-            q_sum += sin;
+        // 搬送波の位相を更新
+        let phase_increment = 2.0 * PI * params.frequency / params.sample_rate;
+        self.phase += phase_increment;
+        if self.phase > 2.0 * PI {
+            self.phase -= 2.0 * PI;
         }
 
-        // トーン数で正規化して最大振幅を1.0に調整
-        let scale = 1.0 / params.multitone_count as f64;
-        Complex::new(i_sum * scale, q_sum * scale)
+        // パルス整形：シンボル境界のインパルス列をRRCフィルタに通す
+        let baseband = if params.pulse_shaping {
+            let input = if symbol_start {
+                self.current_symbol
+            } else {
+                Complex::new(0.0, 0.0)
+            };
+            self.rrc_filter(params, input)
+        } else {
+            self.current_symbol
+        };
+        baseband * Complex::from_polar(1.0, self.phase)
     }
 
-    /// 指定された数のサンプルをブロックとして生成
-    ///
-    /// 内部状態を保持しながら連続的にサンプルを生成します。
+    /// PRBSからビットを取り出し、次のシンボルにマッピング
     ///
-    /// # 引数
-    /// * `params` - 信号生成パラメータ
-    /// * `count` - 生成するサンプル数
-    ///
-    /// # 戻り値
-    /// 複素数形式のI/Qサンプルの配列
-    pub fn generate_block(&mut self, params: &SignalParams, count: usize) -> Vec<Complex<f64>> {
-        let mut block = Vec::with_capacity(count);
+    /// PRBSの次数またはシード値が変更された場合は生成器を初期化し直します。
+    fn next_symbol(&mut self, params: &SignalParams) -> Complex<f64> {
+        let prbs = match &mut self.prbs {
+            Some(prbs) if prbs.order == params.prbs_order && prbs.seed == params.seed => prbs,
+            prbs => prbs.insert(Prbs::new(params.prbs_order, params.seed)),
+        };
+
+        match params.mod_type {
+            // BPSK: ビット1で搬送波の位相をπ反転
+            ModulationType::BPSK => {
+                if prbs.next_bit() == 1 {
+                    Complex::new(-1.0, 0.0)
+                } else {
+                    Complex::new(1.0, 0.0)
+                }
+            }
+            // QPSK: 2ビットずつGray符号化されたコンスタレーション点に割り当て
+            ModulationType::QPSK => {
+                let b0 = prbs.next_bit();
+                let b1 = prbs.next_bit();
+                qpsk_symbol(b0, b1)
+            }
+            // 16QAM: 4ビットずつ16点の格子に割り当て
+            ModulationType::QAM16 => {
+                let bits = [
+                    prbs.next_bit(),
+                    prbs.next_bit(),
+                    prbs.next_bit(),
+                    prbs.next_bit(),
+                ];
+                qam16_symbol(bits)
+            }
+            // PSK: 多値数に応じて1シンボルあたり1〜3ビットを位相に割り当て
+            // 2値と4値はBPSK/QPSKと同じコンスタレーション
+            ModulationType::PSK => match params.psk_order {
+                2 => {
+                    if prbs.next_bit() == 1 {
+                        Complex::new(-1.0, 0.0)
+                    } else {
+                        Complex::new(1.0, 0.0)
+                    }
+                }
+                8 => psk8_symbol([prbs.next_bit(), prbs.next_bit(), prbs.next_bit()]),
+                _ => {
+                    let b0 = prbs.next_bit();
+                    let b1 = prbs.next_bit();
+                    qpsk_symbol(b0, b1)
+                }
+            },
+            // FSK/MSK: ビット1で+偏移、ビット0で-偏移（NRZの±1を実部に保持）
+            ModulationType::FSK | ModulationType::MSK => {
+                if prbs.next_bit() == 1 {
+                    Complex::new(1.0, 0.0)
+                } else {
+                    Complex::new(-1.0, 0.0)
+                }
+            }
+            // ASK: ビット1で振幅1、ビット0で低レベルの振幅（位相は変化させない）
+            ModulationType::ASK => {
+                if prbs.next_bit() == 1 {
+                    Complex::new(1.0, 0.0)
+                } else {
+                    Complex::new(params.ask_low_level, 0.0)
+                }
+            }
+            _ => Complex::new(1.0, 0.0),
+        }
+    }
+
+    /// GFSK/GMSKのガウスフィルタに周波数シンボルを1サンプル分入力し、平滑化した値を返す
+    ///
+    /// フィルタは±2シンボル分の長さを持つFIRで、係数の総和は1に正規化されています。
+    /// 出力はフィルタ長の半分だけ入力から遅れます。
+    ///
+    /// # 引数
+    /// * `params` - 信号生成パラメータ
+    /// * `input` - 現在のシンボル値（±1）
+    ///
+    /// # 戻り値
+    /// 平滑化された周波数シンボル（-1〜1）
+    fn gaussian_filter(&mut self, params: &SignalParams, input: f64) -> f64 {
+        let key = (params.fsk_bt, params.sample_rate / params.symbol_rate);
+        let taps = match &self.gaussian_taps {
+            Some((k, taps)) if *k == key => taps,
+            _ => {
+                let taps = gaussian_taps(key.0, key.1);
+                // 係数が変わったら入力履歴を現在のシンボルで埋め直す
+                self.fsk_history = VecDeque::from(vec![input; taps.len()]);
+                &self.gaussian_taps.insert((key, taps)).1
+            }
+        };
+
+        self.fsk_history.pop_front();
+        self.fsk_history.push_back(input);
+        taps.iter().zip(&self.fsk_history).map(|(h, x)| h * x).sum()
+    }
+
+    /// ルートレイズドコサインフィルタに1サンプル分入力し、フィルタ出力を返す
+    ///
+    /// フィルタ係数はスパンの半分だけ出力を遅らせ、フィルタ状態はブロック間で保持されるため、
+    /// ブロック境界でも整形後の波形は途切れません。
+    ///
+    /// # 引数
+    /// * `params` - 信号生成パラメータ
+    /// * `input` - シンボル境界ではシンボル値、それ以外では0
+    ///
+    /// # 戻り値
+    /// 整形後のベースバンドサンプル
+    fn rrc_filter(&mut self, params: &SignalParams, input: Complex<f64>) -> Complex<f64> {
+        let key = (
+            params.rrc_rolloff,
+            params.rrc_span,
+            params.sample_rate / params.symbol_rate,
+        );
+        let taps = match &self.rrc_taps {
+            Some((k, taps)) if *k == key => taps,
+            _ => {
+                let taps = rrc_taps(key.0, key.1, key.2);
+                // 係数が変わったら入力履歴を消去する
+                self.symbol_history = VecDeque::from(vec![Complex::new(0.0, 0.0); taps.len()]);
+                &self.rrc_taps.insert((key, taps)).1
+            }
+        };
+
+        self.symbol_history.pop_front();
+        self.symbol_history.push_back(input);
+        taps.iter()
+            .zip(&self.symbol_history)
+            .map(|(h, x)| x * h)
+            .sum()
+    }
+
+    /// 複素白色ガウス雑音の次のサンプルを生成
+    ///
+    /// I/Q各成分は分散1/2の独立なガウス分布に従うため、
+    /// 複素サンプルの平均電力（RMS値の2乗）は1になります。
+    /// 同じシード値からは常に同じ雑音系列が生成されます。
+    ///
+    /// # 引数
+    /// * `params` - 信号生成パラメータ
+    ///
+    /// # 戻り値
+    /// 複素数形式のI/Qサンプル
+    fn next_noise_sample(&mut self, params: &SignalParams) -> Complex<f64> {
+        let rng = match &mut self.noise_rng {
+            Some((seed, rng)) if *seed == params.seed => rng,
+            noise_rng => {
+                &mut noise_rng
+                    .insert((params.seed, StdRng::seed_from_u64(params.seed)))
+                    .1
+            }
+        };
+
+        let scale = std::f64::consts::FRAC_1_SQRT_2;
+        let i: f64 = rng.sample(StandardNormal);
+        let q: f64 = rng.sample(StandardNormal);
+        Complex::new(i * scale, q * scale)
+    }
+
+    /// OFDM信号の次のサンプルを生成
+    ///
+    /// 送出待ちのサンプルがなくなるたびに次のOFDMシンボルを合成し、
+    /// サイクリックプレフィックスから順に1サンプルずつ搬送波に乗せて送出します。
+    ///
+    /// # 引数
+    /// * `params` - 信号生成パラメータ
+    ///
+    /// # 戻り値
+    /// 複素数形式のI/Qサンプル
+    fn next_ofdm_sample(&mut self, params: &SignalParams) -> Complex<f64> {
+        if self.ofdm_samples.is_empty() {
+            let symbol = self.next_ofdm_symbol(params);
+            self.ofdm_samples.extend(symbol);
+        }
+        let baseband = self
+            .ofdm_samples
+            .pop_front()
+            .unwrap_or(Complex::new(0.0, 0.0));
+
+        let phase_increment = 2.0 * PI * params.frequency / params.sample_rate;
+        self.phase = (self.phase + phase_increment).rem_euclid(2.0 * PI);
+        baseband * Complex::from_polar(1.0, self.phase)
+    }
+
+    /// 次のOFDMシンボルを合成
+    ///
+    /// 使用する各サブキャリアに、シード値に基づく乱数ビットをGray符号化したQAMシンボルを割り当て、
+    /// 逆FFTで時間領域に変換してサイクリックプレフィックスを付加します。
+    /// 平均電力が1になるよう、使用するサブキャリア数の平方根で正規化します。
+    ///
+    /// # 戻り値
+    /// サイクリックプレフィックスを含む1シンボル分のサンプル（FFTサイズ + CP長）
+    fn next_ofdm_symbol(&mut self, params: &SignalParams) -> Vec<Complex<f64>> {
+        let config = &params.ofdm;
+        let n = config.num_subcarriers;
+        if n == 0 {
+            return Vec::new();
+        }
+        let rng = match &mut self.ofdm_rng {
+            Some((seed, rng)) if *seed == params.seed => rng,
+            ofdm_rng => {
+                let rng = StdRng::seed_from_u64(params.seed ^ OFDM_SEED_SALT);
+                &mut ofdm_rng.insert((params.seed, rng)).1
+            }
+        };
+
+        let mask = config.active_mask();
+        let mut bins: Vec<Complex<f64>> = mask
+            .iter()
+            .map(|&active| {
+                if !active {
+                    return Complex::new(0.0, 0.0);
+                }
+                let mut bit = || u8::from(rng.random::<bool>());
+                if config.qam_order == 16 {
+                    qam16_symbol([bit(), bit(), bit(), bit()])
+                } else {
+                    qpsk_symbol(bit(), bit())
+                }
+            })
+            .collect();
+
+        let ifft = match &mut self.ofdm_ifft {
+            Some(ifft) if ifft.len() == n => ifft,
+            ofdm_ifft => ofdm_ifft.insert(FftPlanner::new().plan_fft_inverse(n)),
+        };
+        ifft.process(&mut bins);
+
+        let active = mask.iter().filter(|&&active| active).count().max(1);
+        let scale = 1.0 / (active as f64).sqrt();
+        let cp_len = config.cp_len.min(n);
+        bins[n - cp_len..]
+            .iter()
+            .chain(&bins)
+            .map(|&x| x * scale)
+            .collect()
+    }
+
+    /// 次のホップの周波数を選択
+    ///
+    /// 帯域内ランダムでは、搬送波周波数 ± 帯域幅/2 の範囲をチャネル間隔で分けたチャネルから
+    /// シード値に基づく乱数で選択します。周波数リストでは、リストの周波数を順に巡回します。
+    ///
+    /// # 引数
+    /// * `params` - 信号生成パラメータ
+    ///
+    /// # 戻り値
+    /// ホップ先の周波数 (Hz)
+    fn next_hop_frequency(&mut self, params: &SignalParams) -> f64 {
+        let hop = self.hop_count;
+        self.hop_count += 1;
+        match &params.hop_set {
+            HopSet::List(freqs) if !freqs.is_empty() => freqs[hop % freqs.len()],
+            HopSet::List(_) => params.frequency,
+            HopSet::Band => {
+                let rng = match &mut self.hop_rng {
+                    Some((seed, rng)) if *seed == params.seed => rng,
+                    hop_rng => {
+                        let rng = StdRng::seed_from_u64(params.seed ^ HOP_SEED_SALT);
+                        &mut hop_rng.insert((params.seed, rng)).1
+                    }
+                };
+                let channels = if params.hop_step > 0.0 {
+                    (params.hop_bandwidth / params.hop_step).floor() as usize + 1
+                } else {
+                    1
+                };
+                let channel = rng.random_range(0..channels);
+                params.frequency - params.hop_bandwidth / 2.0 + channel as f64 * params.hop_step
+            }
+        }
+    }
+
+    /// マルチトーン信号の次のサンプルを生成
+    ///
+    /// 複数のトーン（正弦波）を合成してマルチトーン信号を生成します。
+    /// 初回呼び出し時、およびトーン数・初期位相設定・シード値のいずれかが
+    /// 変更された時に、指定された初期位相設定に基づいて各トーンの位相を初期化します。
+    /// トーンの周波数と振幅は`multitone_spec`（等間隔/任意のリスト）に従います。
+    ///
+    /// # 引数
+    /// * `params` - 信号生成パラメータ
+    ///
+    /// # 戻り値
+    /// 複素数形式のI/Qサンプル
+    fn next_multitone_sample(&mut self, params: &SignalParams) -> Complex<f64> {
+        // 初回呼び出し時または設定変更時に位相を初期化
+        let phase_source = Some((params.multitone_phase, params.seed));
+        let count = params.multitone_tone_count();
+        if self.multitone_phases.len() != count || self.multitone_phase_source != phase_source {
+            self.multitone_phase_source = phase_source;
+            self.multitone_phases = Vec::with_capacity(count);
+            let n = count as f64;
+
+            match params.multitone_phase {
+                MultitonePhase::Zero => {
+                    // すべての位相を0に設定
+                    for _ in 0..count {
+                        self.multitone_phases.push(0.0);
+                    }
+                }
+                MultitonePhase::Random => {
+                    // シード値を使用してランダムな位相を生成
+                    let mut rng = StdRng::seed_from_u64(params.seed);
+                    for _ in 0..count {
+                        self.multitone_phases.push(rng.random_range(0.0..2.0 * PI));
+                    }
+                }
+                MultitonePhase::Schroeder => {
+                    // Schroeder位相：PAPR（ピーク対平均電力比）を最小化
+                    // φ_k = -π·k·(k-1)/N
+                    for k in 0..count {
+                        let k_f = k as f64;
+                        let phi = -PI * k_f * (k_f - 1.0) / n;
+                        self.multitone_phases.push(phi);
+                    }
+                }
+                MultitonePhase::Newman => {
+                    // Newman位相：φ_k = π·(k-1)²/N（kは1始まり）
+                    for k in 0..count {
+                        let k_f = k as f64;
+                        self.multitone_phases.push(PI * k_f * k_f / n);
+                    }
+                }
+            }
+        }
+
+        // generate_blockを経由せずに呼ばれた場合はここでトーンを計算
+        if self.multitone_tones.len() != count {
+            self.multitone_tones = params.multitone_tones();
+        }
+
+        // すべてのトーンを合成
+        let mut i_sum = 0.0;
+        let mut q_sum = 0.0;
+        let mut amplitude_sum = 0.0;
+
+        for (tone, phase) in self.multitone_tones.iter().zip(&mut self.multitone_phases) {
+            // 位相を更新
+            let phase_inc = 2.0 * PI * tone.freq / params.sample_rate;
+            *phase += phase_inc;
+            if *phase > 2.0 * PI {
+                *phase -= 2.0 * PI;
+            }
+
+            // I/Q成分を計算して合成
+            let (sin, cos) = phase.sin_cos();
+            i_sum += tone.amplitude * cos;
+            q_sum += tone.amplitude * sin;
+            amplitude_sum += tone.amplitude;
+        }
+
+        // 振幅の合計で正規化して最大振幅を1.0に調整
+        if amplitude_sum == 0.0 {
+            return Complex::new(0.0, 0.0);
+        }
+        Complex::new(i_sum / amplitude_sum, q_sum / amplitude_sum)
+    }
+
+    /// 指定された数のサンプルをブロックとして生成
+    ///
+    /// 内部状態を保持しながら連続的にサンプルを生成します。
+    /// `add_noise`が有効な場合は、ブロックの信号電力から指定SNRとなる
+    /// 白色ガウス雑音を計算して付加します（`next_sample`単体では付加されません）。
+    ///
+    /// # 引数
+    /// * `params` - 信号生成パラメータ
+    /// * `count` - 生成するサンプル数
+    ///
+    /// # 戻り値
+    /// 複素数形式のI/Qサンプルの配列
+    pub fn generate_block(&mut self, params: &SignalParams, count: usize) -> Vec<Complex<f64>> {
+        let mut block = Vec::with_capacity(count);
+        if params.mod_type == ModulationType::Multitone {
+            // トーンの周波数と振幅はブロック内で変わらないため、先頭で一度だけ計算
+            self.multitone_tones = params.multitone_tones();
+        }
         for _ in 0..count {
             block.push(self.next_sample(params));
         }
-        block
+        if params.burst_enabled {
+            for sample in block.iter_mut() {
+                *sample *= params.burst.gain(self.burst_position);
+                self.burst_position += 1;
+            }
+        }
+        if params.phase_noise_rms_deg > 0.0 {
+            self.add_phase_noise(params, &mut block);
+        }
+        if params.iq_gain_imbalance_db != 0.0 || params.iq_phase_error_deg != 0.0 {
+            apply_iq_imbalance(params, &mut block);
+        }
+        if params.dc_offset_i != 0.0 || params.dc_offset_q != 0.0 {
+            let dc = Complex::new(params.dc_offset_i, params.dc_offset_q);
+            block.iter_mut().for_each(|s| *s += dc);
+        }
+        if params.channel_enabled {
+            params.channel.apply(
+                &mut block,
+                &mut self.channel_history,
+                params.sample_rate,
+                self.channel_position,
+            );
+            self.channel_position += block.len() as u64;
+        }
+        if params.add_noise {
+            self.add_awgn(params, &mut block);
+        }
+        block
+    }
+
+    /// ブロックに位相雑音を付加
+    ///
+    /// サンプルごとにガウス分布に従う位相の増分を積算（ランダムウォーク）し、
+    /// その位相で各サンプルを回転させます。周波数ジッタを積分した位相雑音に相当し、
+    /// CW信号のスペクトル線は増分の分散に応じて広がります。
+    /// 積算した位相はブロック間で保持されます。
+    ///
+    /// # 引数
+    /// * `params` - 信号生成パラメータ
+    /// * `block` - 位相雑音を付加するサンプル配列
+    fn add_phase_noise(&mut self, params: &SignalParams, block: &mut [Complex<f64>]) {
+        let sigma = params.phase_noise_rms_deg.to_radians();
+        let rng = match &mut self.phase_noise_rng {
+            Some((seed, rng)) if *seed == params.seed => rng,
+            phase_noise_rng => {
+                let rng = StdRng::seed_from_u64(params.seed ^ PHASE_NOISE_SEED_SALT);
+                &mut phase_noise_rng.insert((params.seed, rng)).1
+            }
+        };
+        for sample in block.iter_mut() {
+            let increment: f64 = rng.sample(StandardNormal);
+            self.phase_noise = (self.phase_noise + increment * sigma).rem_euclid(2.0 * PI);
+            *sample *= Complex::from_polar(1.0, self.phase_noise);
+        }
+    }
+
+    /// ブロックに指定SNRの複素白色ガウス雑音を付加
+    ///
+    /// 雑音の電力は、ブロックの平均信号電力をSNRで割った値になります。
+    ///
+    /// # 引数
+    /// * `params` - 信号生成パラメータ
+    /// * `block` - 雑音を付加するサンプル配列
+    fn add_awgn(&mut self, params: &SignalParams, block: &mut [Complex<f64>]) {
+        if block.is_empty() {
+            return;
+        }
+        let signal_power = block.iter().map(|s| s.norm_sqr()).sum::<f64>() / block.len() as f64;
+        let noise_power = signal_power / 10.0_f64.powf(params.snr_db / 10.0);
+        // I/Q各成分の標準偏差（複素雑音の電力をI/Qに等分配）
+        let sigma = (noise_power / 2.0).sqrt();
+
+        let rng = match &mut self.awgn_rng {
+            Some((seed, rng)) if *seed == params.seed => rng,
+            awgn_rng => {
+                let rng = StdRng::seed_from_u64(params.seed ^ AWGN_SEED_SALT);
+                &mut awgn_rng.insert((params.seed, rng)).1
+            }
+        };
+        for sample in block.iter_mut() {
+            let i: f64 = rng.sample(StandardNormal);
+            let q: f64 = rng.sample(StandardNormal);
+            *sample += Complex::new(i * sigma, q * sigma);
+        }
+    }
+}
+
+/// 位相雑音のレベル (dBc/Hz) から、ランダムウォークの1サンプルあたりの標準偏差 (度) を計算
+///
+/// 1サンプルあたりの位相の増分の分散を σ² とすると、ランダムウォークの位相雑音は
+/// L(f) = σ²·fs / (4π²·f²) の1/f²特性を持ちます。指定したオフセット周波数で
+/// 指定したレベルになる σ を求めます。
+///
+/// # 引数
+/// * `level_dbc_hz` - オフセット周波数での単側波帯位相雑音 (dBc/Hz)
+/// * `offset_hz` - 搬送波からのオフセット周波数 (Hz)
+/// * `sample_rate` - サンプリングレート (Hz)
+///
+/// # 戻り値
+/// 1サンプルあたりの位相の増分の標準偏差 (度)
+pub fn phase_noise_rms_from_dbc(level_dbc_hz: f64, offset_hz: f64, sample_rate: f64) -> f64 {
+    let level = 10.0_f64.powf(level_dbc_hz / 10.0);
+    let variance = level * 4.0 * PI * PI * offset_hz * offset_hz / sample_rate;
+    variance.sqrt().to_degrees()
+}
+
+/// ランダムウォークの1サンプルあたりの標準偏差 (度) から、
+/// 指定したオフセット周波数での位相雑音のレベル (dBc/Hz) を計算
+/// （`phase_noise_rms_from_dbc`の逆変換）
+pub fn phase_noise_dbc_from_rms(rms_deg: f64, offset_hz: f64, sample_rate: f64) -> f64 {
+    let variance = rms_deg.to_radians().powi(2);
+    10.0 * (variance * sample_rate / (4.0 * PI * PI * offset_hz * offset_hz)).log10()
+}
+
+/// 信号ブロックの統計量
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct SignalStats {
+    /// ピーク振幅
+    pub peak: f64,
+    /// RMS値（複素振幅の2乗平均の平方根）
+    pub rms: f64,
+    /// 平均電力
+    pub mean_power: f64,
+    /// DCオフセット（サンプルの平均値）
+    pub dc_offset: Complex<f64>,
+}
+
+impl SignalStats {
+    /// ピーク振幅をdBFS（振幅1をフルスケールとする）で取得
+    pub fn peak_dbfs(&self) -> f64 {
+        20.0 * self.peak.log10()
+    }
+
+    /// RMS値をdBFSで取得
+    pub fn rms_dbfs(&self) -> f64 {
+        20.0 * self.rms.log10()
+    }
+
+    /// 平均電力をdBFSで取得
+    pub fn mean_power_dbfs(&self) -> f64 {
+        10.0 * self.mean_power.log10()
+    }
+}
+
+/// 信号ブロックの統計量を計算
+///
+/// # 引数
+/// * `samples` - 複素数サンプル配列
+///
+/// # 戻り値
+/// ピーク・RMS・平均電力・DCオフセット。空の配列の場合はすべて0
+pub fn signal_stats(samples: &[Complex<f64>]) -> SignalStats {
+    if samples.is_empty() {
+        return SignalStats::default();
+    }
+    let n = samples.len() as f64;
+    let mean_power = samples.iter().map(|s| s.norm_sqr()).sum::<f64>() / n;
+    SignalStats {
+        peak: samples.iter().map(|s| s.norm()).fold(0.0, f64::max),
+        rms: mean_power.sqrt(),
+        mean_power,
+        dc_offset: samples.iter().sum::<Complex<f64>>() / n,
+    }
+}
+
+/// クレストファクタ（ピーク振幅とRMS値の比）を計算
+///
+/// PAPR（ピーク対平均電力比）の平方根に相当し、CW信号では1になります。
+///
+/// # 引数
+/// * `samples` - 複素数サンプル配列
+///
+/// # 戻り値
+/// ピーク振幅 / RMS値。信号がない場合は0
+pub fn crest_factor(samples: &[Complex<f64>]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let mean_power = samples.iter().map(|s| s.norm_sqr()).sum::<f64>() / samples.len() as f64;
+    if mean_power == 0.0 {
+        return 0.0;
+    }
+    let peak = samples.iter().map(|s| s.norm()).fold(0.0, f64::max);
+    peak / mean_power.sqrt()
+}
+
+/// I/Q各成分をDACのビット深度で量子化
+///
+/// [-1, 1]をフルスケールとする2の補数形式の一様量子化器で、各成分を最も近い
+/// レベルに丸めます。`bits`ビットでは各成分が取りうる値は最大2^`bits`通りで、
+/// 範囲外の値は最大・最小のレベルにクリップされます。
+///
+/// # 引数
+/// * `samples` - 量子化するサンプル配列（その場で書き換え）
+/// * `bits` - 量子化ビット数 (1以上)
+pub fn quantize(samples: &mut [Complex<f64>], bits: u8) {
+    let half = 2f64.powi(bits.max(1) as i32 - 1);
+    let level = |v: f64| (v * half).round().clamp(-half, half - 1.0) / half;
+    for sample in samples {
+        *sample = Complex::new(level(sample.re), level(sample.im));
+    }
+}
+
+/// サンプルの振幅を指定レベルで飽和させる（ハードクリップ）
+///
+/// 振幅が`level`を超えるサンプルは位相を保ったまま振幅を`level`に制限します。
+/// 電力増幅器の飽和を模擬し、スペクトラムには帯域外へのスペクトル再成長が現れます。
+///
+/// # 引数
+/// * `samples` - クリップするサンプル配列（その場で書き換え）
+/// * `level` - クリップする振幅
+pub fn clip_magnitude(samples: &mut [Complex<f64>], level: f64) {
+    for sample in samples {
+        let magnitude = sample.norm();
+        if magnitude > level {
+            *sample *= level / magnitude;
+        }
+    }
+}
+
+/// サンプルの振幅を指定レベルで飽和させる
+///
+/// 各サンプルの位相を保ったまま、振幅rを飽和モデルに従って変換します。
+/// ハードクリップでは min(r, A)、tanhモデルでは A·tanh(g·r/A) となり
+/// （Aはレベル、gは利得）、いずれも出力振幅はAを超えません。
+///
+/// # 引数
+/// * `samples` - 飽和させるサンプル配列（その場で書き換え）
+/// * `model` - 飽和のモデル
+/// * `level` - 飽和する振幅
+/// * `gain` - tanhモデルの小振幅での利得
+pub fn saturate(samples: &mut [Complex<f64>], model: SaturationModel, level: f64, gain: f64) {
+    match model {
+        SaturationModel::Hard => clip_magnitude(samples, level),
+        SaturationModel::Tanh => {
+            for sample in samples.iter_mut() {
+                let r = sample.norm();
+                if r > 0.0 {
+                    *sample *= level * (gain * r / level).tanh() / r;
+                }
+            }
+        }
+    }
+}
+
+/// Salehモデルの非線形増幅器のパラメータ
+///
+/// 入力振幅rに対して、出力振幅 A(r) = αa·r / (1 + βa·r²)、
+/// 位相変化 Φ(r) = αφ·r² / (1 + βφ·r²) (ラジアン) となります。
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SalehParams {
+    /// AM/AM特性の小信号利得 αa
+    pub alpha_a: f64,
+    /// AM/AM特性の飽和係数 βa
+    pub beta_a: f64,
+    /// AM/PM特性の係数 αφ
+    pub alpha_phi: f64,
+    /// AM/PM特性の飽和係数 βφ
+    pub beta_phi: f64,
+}
+
+impl Default for SalehParams {
+    /// Salehの論文で進行波管増幅器 (TWTA) に当てはめた標準的なパラメータ
+    fn default() -> Self {
+        Self {
+            alpha_a: 2.1587,
+            beta_a: 1.1517,
+            alpha_phi: 4.0033,
+            beta_phi: 9.1040,
+        }
+    }
+}
+
+/// Salehモデルの非線形増幅器を1サンプルに適用
+///
+/// 瞬時振幅に応じたAM/AM（利得圧縮）とAM/PM（位相回転）を与えます。
+/// 小振幅では利得αaの線形増幅となり、振幅1/√βaで出力振幅が最大になります。
+///
+/// # 引数
+/// * `sample` - 入力サンプル
+/// * `params` - Salehモデルのパラメータ
+///
+/// # 戻り値
+/// 増幅後のサンプル
+pub fn saleh(sample: Complex<f64>, params: &SalehParams) -> Complex<f64> {
+    let r = sample.norm();
+    let r2 = r * r;
+    let gain = params.alpha_a / (1.0 + params.beta_a * r2);
+    let phase = params.alpha_phi * r2 / (1.0 + params.beta_phi * r2);
+    sample * Complex::from_polar(gain, phase)
+}
+
+/// パルスの包絡線の値を計算
+///
+/// 周期の先頭から`edge`の間で0から1へ、デューティサイクルの位置から`edge`の間で
+/// 1から0へ、それぞれレイズドコサインで遷移します。遷移の中点（振幅0.5）の間隔は
+/// エッジ時間によらずデューティサイクルと一致し、`edge`が0の場合は矩形パルスになります。
+///
+/// # 引数
+/// * `x` - 周期内の位置 (0-1)
+/// * `duty` - デューティサイクル (0-1)
+/// * `edge` - 立ち上がり/立ち下がり時間（周期単位）
+///
+/// # 戻り値
+/// 包絡線の振幅 (0-1)
+fn pulse_envelope(x: f64, duty: f64, edge: f64) -> f64 {
+    let rise = if x < edge {
+        0.5 * (1.0 - (PI * x / edge).cos())
+    } else {
+        1.0
+    };
+    let fall = if x < duty {
+        1.0
+    } else if x < duty + edge {
+        0.5 * (1.0 + (PI * (x - duty) / edge).cos())
+    } else {
+        0.0
+    };
+    rise.min(fall)
+}
+
+/// GFSK/GMSK用ガウスフィルタの係数を計算
+///
+/// 標準偏差 σ = √(ln 2)/(2π·BT)·T のガウス関数を±2シンボルの範囲で標本化します。
+/// 係数の数は`MAX_FILTER_TAPS`程度で打ち切ります。
+///
+/// # 引数
+/// * `bt` - 帯域幅・シンボル周期積
+/// * `samples_per_symbol` - 1シンボルあたりのサンプル数
+///
+/// # 戻り値
+/// 総和が1になるように正規化したフィルタ係数
+fn gaussian_taps(bt: f64, samples_per_symbol: f64) -> Vec<f64> {
+    let sigma = 2.0_f64.ln().sqrt() / (2.0 * PI * bt) * samples_per_symbol;
+    let half = (GAUSSIAN_SPAN_SYMBOLS / 2.0 * samples_per_symbol)
+        .ceil()
+        .min((MAX_FILTER_TAPS / 2) as f64) as isize;
+    let taps: Vec<f64> = (-half..=half)
+        .map(|k| (-(k as f64).powi(2) / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let sum: f64 = taps.iter().sum();
+    taps.into_iter().map(|h| h / sum).collect()
+}
+
+/// シンボル整形フィルタの係数の数の上限
+///
+/// フィルタは出力サンプルごとに全係数を畳み込むため、1シンボルあたりのサンプル数が
+/// 大きい（シンボルレートが低い）ときに生成が極端に遅くならないよう制限します。
+pub const MAX_FILTER_TAPS: usize = 4096;
+
+/// シンボル整形フィルタの長さ (シンボル)
+///
+/// # 引数
+/// * `mod_type` - 変調方式
+/// * `pulse_shaping` - RRCフィルタでシンボルを整形するかどうか
+/// * `rrc_span` - RRCフィルタの長さ (シンボル)
+/// * `fsk_gaussian` - FSK/MSKの周波数遷移をガウスフィルタで平滑化するかどうか
+///
+/// # 戻り値
+/// 使用するフィルタの長さ、フィルタを使用しない場合はNone
+pub fn shaping_filter_span(
+    mod_type: ModulationType,
+    pulse_shaping: bool,
+    rrc_span: usize,
+    fsk_gaussian: bool,
+) -> Option<f64> {
+    match mod_type {
+        ModulationType::FSK | ModulationType::MSK => fsk_gaussian.then_some(GAUSSIAN_SPAN_SYMBOLS),
+        _ => (mod_type.is_digital() && pulse_shaping).then_some(rrc_span as f64),
+    }
+}
+
+/// GFSK/GMSK用ガウスフィルタの長さ (シンボル)
+const GAUSSIAN_SPAN_SYMBOLS: f64 = 4.0;
+
+/// フィルタの係数の数が上限に収まる最小のシンボルレート
+///
+/// # 引数
+/// * `sample_rate` - サンプリングレート (Hz)
+/// * `span` - シンボル整形フィルタの長さ (シンボル)、フィルタを使用しない場合はNone
+///
+/// # 戻り値
+/// 最小のシンボルレート (シンボル/秒) - フィルタを使用しない場合も1以上
+pub fn min_symbol_rate(sample_rate: f64, span: Option<f64>) -> f64 {
+    span.map_or(1.0, |span| {
+        (span * sample_rate / MAX_FILTER_TAPS as f64).max(1.0)
+    })
+}
+
+/// ルートレイズドコサインフィルタの係数を計算
+///
+/// ロールオフ率αのRRCインパルス応答を±span/2シンボルの範囲で標本化します。
+/// シンボル周期ごとのインパルス列を入力したとき、一定のシンボル列に対する出力が
+/// およそ1になるよう、係数の総和を1シンボルあたりのサンプル数に正規化します。
+/// 係数の数は`MAX_FILTER_TAPS`程度で打ち切ります。
+///
+/// # 引数
+/// * `rolloff` - ロールオフ率 α (0-1)
+/// * `span` - フィルタの長さ (シンボル)
+/// * `samples_per_symbol` - 1シンボルあたりのサンプル数
+///
+/// # 戻り値
+/// フィルタ係数
+fn rrc_taps(rolloff: f64, span: usize, samples_per_symbol: f64) -> Vec<f64> {
+    let a = rolloff;
+    let half = (span as f64 * samples_per_symbol / 2.0)
+        .round()
+        .min((MAX_FILTER_TAPS / 2) as f64) as isize;
+    let taps: Vec<f64> = (-half..=half)
+        .map(|k| {
+            // シンボル周期で正規化した時刻
+            let t = k as f64 / samples_per_symbol;
+            if t == 0.0 {
+                1.0 - a + 4.0 * a / PI
+            } else if a > 0.0 && ((4.0 * a * t).abs() - 1.0).abs() < 1e-8 {
+                // t = ±1/(4α) では分子・分母がともに0になるため、極限値を使う
+                a / 2.0_f64.sqrt()
+                    * ((1.0 + 2.0 / PI) * (PI / (4.0 * a)).sin()
+                        + (1.0 - 2.0 / PI) * (PI / (4.0 * a)).cos())
+            } else {
+                ((PI * t * (1.0 - a)).sin() + 4.0 * a * t * (PI * t * (1.0 + a)).cos())
+                    / (PI * t * (1.0 - (4.0 * a * t).powi(2)))
+            }
+        })
+        .collect();
+    let sum: f64 = taps.iter().sum();
+    taps.into_iter()
+        .map(|h| h * samples_per_symbol / sum)
+        .collect()
+}
+
+/// ブロックにIQ不平衡（利得不平衡と直交位相誤差）を適用
+///
+/// I経路に利得係数を掛け、Q経路を位相誤差だけ回転させます。
+/// 理想的な信号 `x` に対し、出力は鏡像周波数にイメージ成分を持ちます。
+///
+/// # 引数
+/// * `params` - 信号生成パラメータ
+/// * `block` - 不平衡を適用するサンプル配列
+fn apply_iq_imbalance(params: &SignalParams, block: &mut [Complex<f64>]) {
+    let gain = 10.0_f64.powf(params.iq_gain_imbalance_db / 20.0);
+    let (sin_phi, cos_phi) = params.iq_phase_error_deg.to_radians().sin_cos();
+    for s in block.iter_mut() {
+        let i = gain * s.re;
+        let q = s.im * cos_phi - s.re * sin_phi;
+        *s = Complex::new(i, q);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// サンプル列をFFTしたスペクトル
+    fn fft(mut samples: Vec<Complex<f64>>) -> Vec<Complex<f64>> {
+        FftPlanner::new()
+            .plan_fft_forward(samples.len())
+            .process(&mut samples);
+        samples
+    }
+
+    /// 新しいジェネレータでnサンプルを生成し、n点FFTしたスペクトル
+    fn spectrum_of(params: &SignalParams, n: usize) -> Vec<Complex<f64>> {
+        fft(SignalGenerator::new().generate_block(params, n))
+    }
+
+    /// 指定したビンの電力の合計（負のビン番号はスペクトルの末尾側に折り返す）
+    fn bin_power(spectrum: &[Complex<f64>], bins: impl IntoIterator<Item = isize>) -> f64 {
+        let n = spectrum.len() as isize;
+        bins.into_iter()
+            .map(|k| spectrum[k.rem_euclid(n) as usize].norm_sqr())
+            .sum()
+    }
+
+    /// スペクトル全体の電力
+    fn total_power(spectrum: &[Complex<f64>]) -> f64 {
+        spectrum.iter().map(|s| s.norm_sqr()).sum()
+    }
+
+    /// 指定したビンの振幅をFFT点数で正規化した値（負のビン番号は末尾側に折り返す）
+    fn bin_magnitude(spectrum: &[Complex<f64>], k: isize) -> f64 {
+        let n = spectrum.len() as isize;
+        spectrum[k.rem_euclid(n) as usize].norm() / n as f64
+    }
+
+    /// 周波数の正確性をテスト
+    ///
+    /// 100Hzの信号を1000Hzでサンプリングした場合、
+    /// 10サンプル後（0.01秒後）に位相が2π進むことを確認
+    #[test]
+    fn test_frequency() {
+        let mut gen = SignalGenerator::new();
+        let params = SignalParams {
+            frequency: 100.0,
+            sample_rate: 1000.0,
+            mod_type: ModulationType::CW,
+            mod_freq: 0.0,
+            mod_strength: 0.0,
+            multitone_count: 1,
+            multitone_spacing: 0.0,
+            multitone_phase: MultitonePhase::Zero,
+            seed: 0,
+            ..Default::default()
+        };
+
+        // 1秒分のデータを生成
+        let samples = gen.generate_block(&params, params.sample_rate as usize);
+
+        let s0 = samples[0];
+        let s10 = samples[10];
+
+        // 10サンプル = 1周期なので、同じ値になるはず
+        let epsilon = 1e-5;
+        assert!((s0.re - s10.re).abs() < epsilon);
+        assert!((s0.im - s10.im).abs() < epsilon);
+    }
+
+    /// BPSK信号の包絡線が一定であることをテスト
+    ///
+    /// 位相が反転するだけなので、平均振幅は1.0のままになるはず
+    #[test]
+    fn test_bpsk_constant_envelope() {
+        let mut gen = SignalGenerator::new();
+        let params = SignalParams {
+            mod_type: ModulationType::BPSK,
+            symbol_rate: 3000.0,
+            prbs_order: PrbsOrder::Prbs15,
+            seed: 1,
+            ..Default::default()
+        };
+
+        // シンボル周期が整数サンプルにならない設定で複数ブロックを生成
+        let mut samples = gen.generate_block(&params, 1000);
+        samples.extend(gen.generate_block(&params, 1000));
+
+        let mean_mag = samples.iter().map(|s| s.norm()).sum::<f64>() / samples.len() as f64;
+        assert!((mean_mag - 1.0).abs() < 1e-9);
+
+        // 位相反転が実際に発生していること
+        let flips = samples
+            .windows(2)
+            .filter(|w| (w[1] / w[0]).re < 0.0)
+            .count();
+        assert!(flips > 0);
+    }
+
+    /// QPSKのコンスタレーション点がすべて出現することをテスト
+    ///
+    /// 搬送波周波数を0にしてベースバンドのシンボルを直接観測する
+    #[test]
+    fn test_qpsk_constellation() {
+        let mut gen = SignalGenerator::new();
+        let params = SignalParams {
+            frequency: 0.0,
+            mod_type: ModulationType::QPSK,
+            symbol_rate: 10000.0,
+            ..Default::default()
+        };
+
+        let samples = gen.generate_block(&params, 1000);
+        let a = 1.0 / 2.0_f64.sqrt();
+        for (re, im) in [(a, a), (-a, a), (-a, -a), (a, -a)] {
+            assert!(
+                samples
+                    .iter()
+                    .any(|s| (s.re - re).abs() < 1e-9 && (s.im - im).abs() < 1e-9),
+                "constellation point ({}, {}) not found",
+                re,
+                im
+            );
+        }
+    }
+
+    /// チャープ信号のスペクトラムが掃引帯域内でほぼ平坦であることをテスト
+    #[test]
+    fn test_chirp_flat_spectrum() {
+        let params = SignalParams {
+            frequency: 0.0,
+            sample_rate: 100000.0,
+            mod_type: ModulationType::Chirp,
+            chirp_bandwidth: 20000.0,
+            chirp_duration: 0.01,
+            ..Default::default()
+        };
+
+        // ちょうど1掃引分（1000サンプル）を生成してFFT
+        let spectrum = spectrum_of(&params, 1000);
+
+        // 周波数分解能は100Hz、ビンkは k*100Hz（負の周波数は末尾側）
+        let mag = |k: isize| bin_magnitude(&spectrum, k);
+
+        // 帯域の中央部（±7kHz）の振幅は平均値の±3dB以内
+        let in_band: Vec<f64> = (-70..=70).map(mag).collect();
+        let mean = in_band.iter().sum::<f64>() / in_band.len() as f64;
+        for m in &in_band {
+            assert!((20.0 * (m / mean).log10()).abs() < 3.0);
+        }
+
+        // 帯域外（±20kHz以上）は帯域内より十分小さい
+        for k in 200..=300 {
+            assert!(mag(k) < mean * 0.1);
+            assert!(mag(-k) < mean * 0.1);
+        }
+    }
+
+    /// 16QAM信号の平均電力が1になることをテスト
+    #[test]
+    fn test_qam16_unit_average_power() {
+        let mut gen = SignalGenerator::new();
+        let params = SignalParams {
+            mod_type: ModulationType::QAM16,
+            symbol_rate: 1000.0,
+            prbs_order: PrbsOrder::Prbs15,
+            ..Default::default()
+        };
+
+        // 10000シンボル分を生成
+        let samples = gen.generate_block(&params, 1_000_000);
+        let power = samples.iter().map(|s| s.norm_sqr()).sum::<f64>() / samples.len() as f64;
+        assert!((power - 1.0).abs() < 0.05, "average power = {}", power);
+    }
+
+    /// チャープの瞬時周波数が1掃引内で単調増加し、掃引ごとに折り返すことをテスト
+    #[test]
+    fn test_chirp_monotonic_sweep() {
+        let mut gen = SignalGenerator::new();
+        let params = SignalParams {
+            frequency: 10000.0,
+            sample_rate: 100000.0,
+            mod_type: ModulationType::Chirp,
+            chirp_bandwidth: 20000.0,
+            chirp_duration: 0.01,
+            ..Default::default()
+        };
+
+        // 2掃引分を生成し、隣接サンプル間の位相差から瞬時周波数を求める
+        let samples = gen.generate_block(&params, 2000);
+        let inst_freq: Vec<f64> = samples
+            .windows(2)
+            .map(|w| (w[1] * w[0].conj()).arg() * params.sample_rate / (2.0 * PI))
+            .collect();
+
+        // 1掃引目（1000サンプル）の中では単調増加
+        for w in inst_freq[..999].windows(2) {
+            assert!(w[1] > w[0]);
+        }
+        // 掃引開始・終了付近の周波数はf_c ∓ B/2
+        assert!((inst_freq[0] - 0.0).abs() < 50.0);
+        assert!((inst_freq[998] - 20000.0).abs() < 50.0);
+        // 掃引の境界で周波数が下限に戻る
+        assert!(inst_freq[1000] < inst_freq[998]);
+    }
+
+    /// 初期位相設定のみを変更した場合にも位相テーブルが再構築されることをテスト
+    #[test]
+    fn test_multitone_phase_mode_change() {
+        let zero = SignalParams {
+            mod_type: ModulationType::Multitone,
+            multitone_count: 8,
+            multitone_spacing: 1000.0,
+            multitone_phase: MultitonePhase::Zero,
+            ..Default::default()
+        };
+        let schroeder = SignalParams {
+            multitone_phase: MultitonePhase::Schroeder,
+            ..zero.clone()
+        };
+
+        // 同じジェネレータでZero位相のあとSchroeder位相に切り替える
+        let mut gen = SignalGenerator::new();
+        let zero_first = gen.generate_block(&zero, 100)[0];
+        let switched_first = gen.generate_block(&schroeder, 1)[0];
+
+        // 新しいジェネレータでSchroeder位相を生成した場合と一致するはず
+        let expected = SignalGenerator::new().generate_block(&schroeder, 1)[0];
+        assert!((switched_first - expected).norm() < 1e-12);
+        assert!((switched_first - zero_first).norm() > 1e-3);
+    }
+
+    /// シード値のみを変更した場合にもランダム位相が作り直されることをテスト
+    #[test]
+    fn test_multitone_seed_change() {
+        let seed1 = SignalParams {
+            mod_type: ModulationType::Multitone,
+            multitone_count: 8,
+            multitone_spacing: 1000.0,
+            multitone_phase: MultitonePhase::Random,
+            seed: 1,
+            ..Default::default()
+        };
+        let seed2 = SignalParams {
+            seed: 2,
+            ..seed1.clone()
+        };
+
+        // 同じジェネレータでシード値だけを切り替える
+        let mut gen = SignalGenerator::new();
+        gen.generate_block(&seed1, 1);
+        let phases1 = gen.multitone_phases.clone();
+        let switched_first = gen.generate_block(&seed2, 1)[0];
+
+        let mut fresh = SignalGenerator::new();
+        let expected = fresh.generate_block(&seed2, 1)[0];
+        assert_eq!(gen.multitone_phases, fresh.multitone_phases);
+        assert_ne!(gen.multitone_phases, phases1);
+        assert!((switched_first - expected).norm() < 1e-12);
+    }
+
+    /// 雑音信号の分散（平均電力）が1になり、シード値で再現できることをテスト
+    #[test]
+    fn test_noise_power_and_determinism() {
+        let params = SignalParams {
+            mod_type: ModulationType::Noise,
+            seed: 42,
+            ..Default::default()
+        };
+
+        let samples = SignalGenerator::new().generate_block(&params, 200_000);
+        let mean = samples.iter().sum::<Complex<f64>>() / samples.len() as f64;
+        let variance =
+            samples.iter().map(|s| (s - mean).norm_sqr()).sum::<f64>() / samples.len() as f64;
+        assert!(mean.norm() < 0.01);
+        assert!((variance - 1.0).abs() < 0.02, "variance = {}", variance);
+
+        // 同じシード値なら同じ系列になる
+        let again = SignalGenerator::new().generate_block(&params, 100);
+        assert_eq!(&samples[..100], &again[..]);
+    }
+
+    /// OFDMシンボルの長さがFFTサイズ+CP長で、サブキャリアがサンプリングレート/FFTサイズ間隔に並ぶことをテスト
+    #[test]
+    fn test_ofdm_symbol_structure() {
+        let config = OfdmConfig::default();
+        let n = config.num_subcarriers;
+        let cp = config.cp_len;
+        assert_eq!(config.symbol_len(), n + cp);
+
+        // DCを除いて正負26本ずつ（IEEE 802.11a相当）
+        let mask = config.active_mask();
+        assert_eq!(mask.iter().filter(|&&m| m).count(), 52);
+        assert!(!mask[0]);
+        assert!(mask[1..=26].iter().all(|&m| m));
+        assert!(mask[27..38].iter().all(|&m| !m));
+        assert!(mask[38..].iter().all(|&m| m));
+        let with_dc = OfdmConfig {
+            null_dc: false,
+            active_subcarriers: 4,
+            ..config
+        };
+        let active: Vec<usize> = (0..n).filter(|&k| with_dc.active_mask()[k]).collect();
+        assert_eq!(active, vec![0, 1, 2, n - 1]);
+
+        // 搬送波をサブキャリア5本分ずらしても、各サブキャリアはFFTのビンにちょうど乗る
+        let sample_rate = 64000.0;
+        let spacing = sample_rate / n as f64;
+        let offset = 5;
+        let params = SignalParams {
+            mod_type: ModulationType::OFDM,
+            frequency: offset as f64 * spacing,
+            sample_rate,
+            seed: 7,
+            ..Default::default()
+        };
+        let symbols = 4;
+        let samples = SignalGenerator::new().generate_block(&params, symbols * (n + cp));
+        let power = samples.iter().map(|s| s.norm_sqr()).sum::<f64>() / samples.len() as f64;
+        assert!((power - 1.0).abs() < 0.1, "power = {}", power);
+
+        for symbol in samples.chunks(n + cp) {
+            // サイクリックプレフィックスはシンボル末尾のコピー
+            for k in 0..cp {
+                assert!((symbol[k] - symbol[n + k]).norm() < 1e-9);
+            }
+            // CPを除いたN点のFFTで、使用するサブキャリアはQPSKの振幅、それ以外は0
+            let bins = fft(symbol[cp..].to_vec());
+            let scale = (52.0_f64).sqrt() / n as f64;
+            for (k, &active) in mask.iter().enumerate() {
+                let magnitude = bins[(k + offset) % n].norm() * scale;
+                let expected = if active { 1.0 } else { 0.0 };
+                assert!(
+                    (magnitude - expected).abs() < 1e-9,
+                    "bin {}: {}",
+                    k,
+                    magnitude
+                );
+            }
+        }
+
+        // 同じシード値なら同じ系列、異なるシード値なら異なる系列になる
+        let again = SignalGenerator::new().generate_block(&params, n + cp);
+        assert_eq!(&samples[..n + cp], &again[..]);
+        let other = SignalParams { seed: 8, ..params };
+        let other = SignalGenerator::new().generate_block(&other, n + cp);
+        assert_ne!(&samples[..n + cp], &other[..]);
+    }
+
+    /// 付加雑音のSNRが指定値と一致することをテスト
+    #[test]
+    fn test_add_noise_snr() {
+        let clean = SignalParams {
+            mod_type: ModulationType::AM,
+            mod_freq: 100.0,
+            mod_strength: 0.5,
+            ..Default::default()
+        };
+        let noisy = SignalParams {
+            add_noise: true,
+            snr_db: 10.0,
+            ..clean.clone()
+        };
+
+        let n = 100_000;
+        let signal = SignalGenerator::new().generate_block(&clean, n);
+        let output = SignalGenerator::new().generate_block(&noisy, n);
+
+        // 出力と元の信号の差が付加された雑音
+        let signal_power = signal.iter().map(|s| s.norm_sqr()).sum::<f64>();
+        let noise_power = output
+            .iter()
+            .zip(&signal)
+            .map(|(o, s)| (o - s).norm_sqr())
+            .sum::<f64>();
+        let snr = 10.0 * (signal_power / noise_power).log10();
+        assert!((snr - 10.0).abs() < 1.0, "measured SNR = {} dB", snr);
+    }
+
+    /// ASK信号の振幅が2つの値のみをとることをテスト
+    #[test]
+    fn test_ask_two_levels() {
+        let mut gen = SignalGenerator::new();
+        let params = SignalParams {
+            mod_type: ModulationType::ASK,
+            symbol_rate: 5000.0,
+            ask_low_level: 0.25,
+            ..Default::default()
+        };
+
+        let samples = gen.generate_block(&params, 2000);
+        let mut levels: Vec<f64> = Vec::new();
+        for s in &samples {
+            let mag = s.norm();
+            if !levels.iter().any(|l| (l - mag).abs() < 1e-9) {
+                levels.push(mag);
+            }
+        }
+        levels.sort_by(f64::total_cmp);
+        assert_eq!(levels.len(), 2);
+        assert!((levels[0] - 0.25).abs() < 1e-9);
+        assert!((levels[1] - 1.0).abs() < 1e-9);
+    }
+
+    /// 第1種ベッセル関数 J_n(x) を級数展開で計算（テスト用）
+    fn bessel_j(n: u32, x: f64) -> f64 {
+        let mut sum = 0.0;
+        let mut k_fact = 1.0;
+        for k in 0..30 {
+            if k > 0 {
+                k_fact *= k as f64;
+            }
+            let kn_fact: f64 = (1..=(k + n)).map(|v| v as f64).product();
+            let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+            sum += sign * (x / 2.0).powi((2 * k + n) as i32) / (k_fact * kn_fact);
+        }
+        sum
+    }
+
+    /// FM信号の搬送波と第1側波帯の比がJ0(β)/J1(β)に一致することをテスト
+    #[test]
+    fn test_fm_bessel_sidebands() {
+        let n = 1000;
+        let params = SignalParams {
+            frequency: 10000.0,
+            sample_rate: 100000.0,
+            mod_type: ModulationType::FM,
+            mod_freq: 1000.0,
+            mod_strength: 1500.0,
+            ..Default::default()
+        };
+        let beta = params.mod_strength / params.mod_freq;
+
+        // 周波数分解能100Hzで、搬送波はビン100、側波帯は±10ビンごと
+        let spectrum = spectrum_of(&params, n);
+        let carrier = bin_magnitude(&spectrum, 100);
+        let upper = bin_magnitude(&spectrum, 110);
+        let lower = bin_magnitude(&spectrum, 90);
+
+        assert!((carrier - bessel_j(0, beta).abs()).abs() < 1e-6);
+        assert!((upper - bessel_j(1, beta).abs()).abs() < 1e-6);
+        assert!((lower - bessel_j(1, beta).abs()).abs() < 1e-6);
+        let ratio = carrier / upper;
+        let expected = bessel_j(0, beta).abs() / bessel_j(1, beta).abs();
+        assert!((ratio - expected).abs() / expected < 1e-3);
+    }
+
+    /// 方形波AMの包絡線が 1±変調指数 の2つのレベルを半周期ごとに交互にとることをテスト
+    #[test]
+    fn test_square_wave_am() {
+        let params = SignalParams {
+            frequency: 1000.0,
+            sample_rate: 100000.0,
+            mod_type: ModulationType::AM,
+            mod_freq: 1000.0,
+            mod_strength: 0.5,
+            am_waveform: AmWaveform::Square,
+            ..Default::default()
+        };
+        // 変調周期は100サンプル
+        let envelope: Vec<f64> = SignalGenerator::new()
+            .generate_block(&params, 400)
+            .iter()
+            .map(|s| s.norm())
+            .collect();
+        for period in envelope.chunks(100) {
+            assert!(period[..48].iter().all(|e| (e - 1.5).abs() < 1e-9));
+            assert!(period[51..98].iter().all(|e| (e - 0.5).abs() < 1e-9));
+        }
+
+        // 他の波形も包絡線は 1±変調指数 の範囲に収まり、両端に達する
+        for waveform in [AmWaveform::Sine, AmWaveform::Triangle, AmWaveform::Sawtooth] {
+            let params = SignalParams {
+                am_waveform: waveform,
+                ..params.clone()
+            };
+            let envelope: Vec<f64> = SignalGenerator::new()
+                .generate_block(&params, 400)
+                .iter()
+                .map(|s| s.norm())
+                .collect();
+            let max = envelope.iter().cloned().fold(f64::MIN, f64::max);
+            let min = envelope.iter().cloned().fold(f64::MAX, f64::min);
+            assert!((max - 1.5).abs() < 0.02, "{:?} max={}", waveform, max);
+            assert!((min - 0.5).abs() < 0.02, "{:?} min={}", waveform, min);
+        }
+    }
+
+    /// SSB変調で選択した側波帯のみが現れ、搬送波と反対側の側波帯が抑圧されることをテスト
+    #[test]
+    fn test_ssb_sideband() {
+        let n = 1000;
+        for sideband in [Sideband::Upper, Sideband::Lower] {
+            let params = SignalParams {
+                frequency: 10000.0,
+                sample_rate: 100000.0,
+                mod_type: ModulationType::SSB,
+                mod_freq: 1000.0,
+                ssb_sideband: sideband,
+                ..Default::default()
+            };
+
+            // 周波数分解能100Hzで、搬送波はビン100、側波帯は±10ビン
+            let spectrum = spectrum_of(&params, n);
+            let mag = |bin: isize| bin_magnitude(&spectrum, bin);
+            let (wanted, unwanted) = match sideband {
+                Sideband::Upper => (110, 90),
+                Sideband::Lower => (90, 110),
+            };
+
+            assert!((mag(wanted) - 1.0).abs() < 1e-9, "{:?}", sideband);
+            assert!(mag(unwanted) < 1e-9, "{:?}", sideband);
+            assert!(mag(100) < 1e-9, "{:?}", sideband);
+        }
+    }
+
+    /// RRCのパルス整形で、ロールオフ率が小さいほど占有帯域幅が狭くなることをテスト
+    #[test]
+    fn test_rrc_occupied_bandwidth() {
+        use crate::analysis::occupied_bandwidth;
+
+        let n = 64000;
+        let obw = |pulse_shaping: bool, rolloff: f64| {
+            let params = SignalParams {
+                frequency: 0.0,
+                sample_rate: 16000.0,
+                mod_type: ModulationType::BPSK,
+                symbol_rate: 1000.0,
+                pulse_shaping,
+                rrc_rolloff: rolloff,
+                rrc_span: 16,
+                ..Default::default()
+            };
+            let spectrum = spectrum_of(&params, n);
+            let mut power: Vec<f64> = spectrum.iter().map(|c| c.norm_sqr()).collect();
+            power.rotate_left(n / 2);
+            occupied_bandwidth(&power, params.sample_rate, 0.99).2
+        };
+
+        let rectangular = obw(false, 0.0);
+        let wide = obw(true, 0.8);
+        let narrow = obw(true, 0.2);
+        // 99%占有帯域幅はおよそ (1 + α)·シンボルレート 以下
+        assert!(narrow < wide, "{} >= {}", narrow, wide);
+        assert!(wide < rectangular / 4.0, "{} vs {}", wide, rectangular);
+        assert!(narrow < 1.2 * 1000.0 && wide < 1.8 * 1000.0);
+        assert!(narrow > 900.0, "{}", narrow);
+    }
+
+    /// RRCフィルタの係数の数が上限で打ち切られ、特異点の近傍でも極限値を使うことをテスト
+    #[test]
+    fn test_rrc_taps_limits() {
+        // シンボルレート1 sym/s、100kHzでは本来80万係数になる
+        assert!(rrc_taps(0.35, 8, 100000.0).len() <= MAX_FILTER_TAPS + 1);
+        assert_eq!(
+            min_symbol_rate(
+                100000.0,
+                shaping_filter_span(ModulationType::BPSK, true, 8, false)
+            ),
+            100000.0 * 8.0 / MAX_FILTER_TAPS as f64
+        );
+        assert_eq!(
+            min_symbol_rate(
+                100000.0,
+                shaping_filter_span(ModulationType::BPSK, false, 8, true)
+            ),
+            1.0
+        );
+
+        // α=0.07、7サンプル/シンボルでは k=25 が浮動小数点で 4αt = 1+2^-52 になる
+        let a = 0.07;
+        let taps = rrc_taps(a, 8, 7.0);
+        let center = taps.len() / 2;
+        let limit = a / 2.0_f64.sqrt()
+            * ((1.0 + 2.0 / PI) * (PI / (4.0 * a)).sin()
+                + (1.0 - 2.0 / PI) * (PI / (4.0 * a)).cos());
+        let ratio = taps[center + 25] / taps[center];
+        assert!(
+            (ratio - limit / (1.0 - a + 4.0 * a / PI)).abs() < 1e-6,
+            "ratio = {}",
+            ratio
+        );
+    }
+
+    /// FM信号の電力の大部分がCarson帯域 2(Δf + f_m) 内に収まることをテスト
+    #[test]
+    fn test_fm_carson_bandwidth() {
+        let n = 10000;
+        let params = SignalParams {
+            frequency: 0.0,
+            sample_rate: 100000.0,
+            mod_type: ModulationType::FM,
+            mod_freq: 500.0,
+            mod_strength: 5000.0,
+            ..Default::default()
+        };
+
+        let spectrum = spectrum_of(&params, n);
+
+        // 周波数分解能10Hz、Carson帯域の片側は Δf + f_m = 5500Hz
+        let resolution = params.sample_rate / n as f64;
+        let half_band = ((params.mod_strength + params.mod_freq) / resolution).floor() as isize;
+        let total = total_power(&spectrum);
+        let in_band = bin_power(&spectrum, -half_band..=half_band);
+        assert!(
+            in_band / total > 0.98,
+            "in-band ratio = {}",
+            in_band / total
+        );
+    }
+
+    /// 2トーン信号のスペクトラムに指定した2本の線のみが現れることをテスト
+    #[test]
+    fn test_two_tone_lines() {
+        let n = 1000;
+        let params = SignalParams {
+            frequency: 10000.0,
+            sample_rate: 100000.0,
+            mod_type: ModulationType::TwoTone,
+            tone_spacing: 2000.0,
+            ..Default::default()
+        };
+
+        let samples = SignalGenerator::new().generate_block(&params, n);
+        assert!(samples.iter().all(|s| s.norm() <= 1.0 + 1e-12));
+
+        // 周波数分解能100Hzで、トーンは9kHz（ビン90）と11kHz（ビン110）
+        let spectrum = fft(samples);
+        for k in 0..n {
+            let mag = bin_magnitude(&spectrum, k as isize);
+            if k == 90 || k == 110 {
+                assert!((mag - 0.5).abs() < 1e-9);
+            } else {
+                assert!(mag < 1e-9, "unexpected line at bin {}: {}", k, mag);
+            }
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// 2トーン信号を飽和させると相互変調の線が現れることをテスト
+    #[test]
+    fn test_saturation_intermod() {
+        let n = 1000;
+        let params = SignalParams {
+            frequency: 10000.0,
+            sample_rate: 100000.0,
+            mod_type: ModulationType::TwoTone,
+            tone_spacing: 2000.0,
+            ..Default::default()
+        };
+        let level = 0.6;
+        // 周波数分解能100Hzで、トーンはビン90と110、3次相互変調はビン70と130
+        let im3 = |model: Option<SaturationModel>| {
+            let mut samples = SignalGenerator::new().generate_block(&params, n);
+            if let Some(model) = model {
+                saturate(&mut samples, model, level, 2.0);
+                assert!(samples.iter().all(|s| s.norm() <= level + 1e-12));
+            }
+            let spectrum = fft(samples);
+            let tone = spectrum[110].norm();
+            (spectrum[70].norm() / tone, spectrum[130].norm() / tone)
+        };
 
-    /// 周波数の正確性をテスト
-    ///
-    /// 100Hzの信号を1000Hzでサンプリングした場合、
-    /// 10サンプル後（0.01秒後）に位相が2π進むことを確認
+        let (lower, upper) = im3(None);
+        assert!(lower < 1e-9 && upper < 1e-9);
+        for model in [SaturationModel::Hard, SaturationModel::Tanh] {
+            let (lower, upper) = im3(Some(model));
+            assert!(
+                lower > 1e-3 && upper > 1e-3,
+                "{:?}: {} {}",
+                model,
+                lower,
+                upper
+            );
+        }
+    }
+
+    /// 同じジェネレータで連続して生成したブロックの境界で位相が連続することをテスト
     #[test]
-    fn test_frequency() {
+    fn test_block_boundary_continuity() {
+        let params = SignalParams {
+            frequency: 1234.5,
+            mod_type: ModulationType::FM,
+            mod_freq: 77.0,
+            mod_strength: 500.0,
+            ..Default::default()
+        };
+
+        // 2つのブロックに分けて生成した結果は、1つのブロックで生成した結果と一致するはず
+        let mut gen = SignalGenerator::new();
+        let mut split = gen.generate_block(&params, 333);
+        split.extend(gen.generate_block(&params, 667));
+        let whole = SignalGenerator::new().generate_block(&params, 1000);
+        for (a, b) in split.iter().zip(&whole) {
+            assert!((a - b).norm() < 1e-12);
+        }
+
+        // CWでは境界をまたぐサンプル間の位相差が通常の位相増分と等しい
+        let cw = SignalParams {
+            frequency: 1234.5,
+            ..Default::default()
+        };
         let mut gen = SignalGenerator::new();
+        let first = gen.generate_block(&cw, 100);
+        let second = gen.generate_block(&cw, 100);
+        let step = (second[0] * first[99].conj()).arg();
+        let expected = 2.0 * PI * cw.frequency / cw.sample_rate;
+        assert!((step - expected).abs() < 1e-12);
+    }
+
+    /// 周波数掃引の瞬時周波数が開始時に開始周波数、半分の時間で中間の周波数になることをテスト
+    #[test]
+    fn test_sweep_instantaneous_frequency() {
         let params = SignalParams {
-            frequency: 100.0,
+            sample_rate: 100000.0,
+            mod_type: ModulationType::Sweep,
+            sweep_start: 2000.0,
+            sweep_stop: 12000.0,
+            sweep_time: 0.01,
+            ..Default::default()
+        };
+
+        assert_eq!(sweep_frequency(&params, 0.0), 2000.0);
+        assert!((sweep_frequency(&params, 0.005) - 7000.0).abs() < 1e-9);
+        // 掃引時間が経過すると開始周波数に戻る
+        assert!((sweep_frequency(&params, 0.01) - 2000.0).abs() < 1e-9);
+
+        // 生成したサンプルの位相差から求めた瞬時周波数も一致する
+        let samples = SignalGenerator::new().generate_block(&params, 1000);
+        let inst_freq =
+            |n: usize| (samples[n + 1] * samples[n].conj()).arg() * params.sample_rate / (2.0 * PI);
+        // 1サンプルあたりの周波数変化は10Hzなので、その範囲で一致すればよい
+        assert!((inst_freq(0) - 2000.0).abs() < 20.0);
+        assert!((inst_freq(500) - 7000.0).abs() < 20.0);
+    }
+
+    /// ステップ掃引が位相を連続させたまま、ブロックの終わりで終了周波数に達することをテスト
+    #[test]
+    fn test_stepped_sweep() {
+        for mode in [SweepMode::LinearStep, SweepMode::LogStep] {
+            let params = SignalParams {
+                sample_rate: 100000.0,
+                mod_type: ModulationType::Sweep,
+                sweep_start: 1000.0,
+                sweep_stop: 16000.0,
+                sweep_time: 0.01,
+                sweep_mode: mode,
+                sweep_dwell: 200,
+                ..Default::default()
+            };
+            // 1000サンプルの掃引時間を200サンプルずつ5ステップに分ける
+            let samples = SignalGenerator::new().generate_block(&params, 1000);
+            let inst_freq = |n: usize| {
+                (samples[n + 1] * samples[n].conj()).arg() * params.sample_rate / (2.0 * PI)
+            };
+            let expected: [f64; 5] = match mode {
+                SweepMode::LogStep => [1000.0, 2000.0, 4000.0, 8000.0, 16000.0],
+                _ => [1000.0, 4750.0, 8500.0, 12250.0, 16000.0],
+            };
+            for (step, freq) in expected.iter().enumerate() {
+                // ステップ内では周波数が一定
+                for n in [step * 200, step * 200 + 198] {
+                    assert!((inst_freq(n) - freq).abs() < 1e-6, "{:?} n={}", mode, n);
+                }
+            }
+            assert!((inst_freq(998) - params.sweep_stop).abs() < 1e-6);
+            // ステップ境界でも振幅が一定のまま位相は連続する
+            assert!(samples.iter().all(|s| (s.norm() - 1.0).abs() < 1e-12));
+        }
+    }
+
+    /// 周波数ホッピングの瞬時周波数が滞在時間ごとのサンプル境界で切り替わることをテスト
+    #[test]
+    fn test_frequency_hopping() {
+        let list = SignalParams {
+            sample_rate: 100000.0,
+            mod_type: ModulationType::Hopping,
+            hop_set: HopSet::List(vec![1000.0, 5000.0, -3000.0]),
+            hop_dwell: 0.001,
+            ..Default::default()
+        };
+        let inst_freq = |samples: &[Complex<f64>], n: usize| {
+            (samples[n + 1] * samples[n].conj()).arg() * list.sample_rate / (2.0 * PI)
+        };
+
+        // 100サンプルごとにリストの周波数を巡回し、位相は連続する
+        let samples = SignalGenerator::new().generate_block(&list, 400);
+        for (hop, freq) in [1000.0, 5000.0, -3000.0, 1000.0].iter().enumerate() {
+            for n in [hop * 100, hop * 100 + 98] {
+                assert!((inst_freq(&samples, n) - freq).abs() < 1e-6, "n={}", n);
+            }
+        }
+        let phase = |n: usize| samples[n].arg();
+        assert!((phase(100) - phase(99) - 2.0 * PI * 5000.0 / list.sample_rate).abs() < 1e-9);
+
+        // 位相リセットでは各ホップの最初のサンプルの位相が0になる
+        let reset = SignalParams {
+            hop_reset_phase: true,
+            ..list.clone()
+        };
+        let samples = SignalGenerator::new().generate_block(&reset, 400);
+        for n in [0, 100, 200, 300] {
+            assert!(samples[n].arg().abs() < 1e-9, "n={}", n);
+        }
+
+        // 帯域内ランダムではシード値で決まるチャネル上にだけホップし、ブロックを分けても同じ系列になる
+        let band = SignalParams {
+            frequency: 10000.0,
+            hop_set: HopSet::Band,
+            hop_bandwidth: 8000.0,
+            hop_step: 2000.0,
+            seed: 7,
+            ..list.clone()
+        };
+        let samples = SignalGenerator::new().generate_block(&band, 2000);
+        let mut generator = SignalGenerator::new();
+        let mut split = generator.generate_block(&band, 750);
+        split.extend(generator.generate_block(&band, 1250));
+        assert_eq!(samples, split);
+        let mut hops = Vec::new();
+        for hop in 0..20 {
+            let freq = inst_freq(&samples, hop * 100 + 50);
+            assert!((inst_freq(&samples, hop * 100) - freq).abs() < 1e-6);
+            let channel = (freq - 6000.0) / 2000.0;
+            assert!((channel - channel.round()).abs() < 1e-6 && (0.0..=4.0).contains(&channel));
+            hops.push(channel.round() as usize);
+        }
+        hops.dedup();
+        assert!(hops.len() > 5, "{:?}", hops);
+    }
+
+    /// 遅延0・利得1の1タップのチャネルは素通しになり、ドップラーシフトがCWを移動させることをテスト
+    #[test]
+    fn test_channel() {
+        let cw = SignalParams {
+            frequency: 1000.0,
+            sample_rate: 100000.0,
+            ..Default::default()
+        };
+        let direct = SignalGenerator::new().generate_block(&cw, 1000);
+        let pass = SignalParams {
+            channel_enabled: true,
+            ..cw.clone()
+        };
+        assert_eq!(SignalGenerator::new().generate_block(&pass, 1000), direct);
+
+        // 2kHzのドップラーシフトで瞬時周波数が3kHzになり、ブロックを分けても位相が連続する
+        let doppler = SignalParams {
+            channel: ChannelConfig {
+                doppler_hz: 2000.0,
+                ..Default::default()
+            },
+            ..pass.clone()
+        };
+        let mut generator = SignalGenerator::new();
+        let mut samples = generator.generate_block(&doppler, 400);
+        samples.extend(generator.generate_block(&doppler, 600));
+        for n in 0..999 {
+            let freq = (samples[n + 1] * samples[n].conj()).arg() * cw.sample_rate / (2.0 * PI);
+            assert!((freq - 3000.0).abs() < 1e-6, "n={} freq={}", n, freq);
+        }
+
+        // 遅延タップは過去のサンプルを減衰させて加算し、生成開始より前は0とみなす
+        let mut block = vec![
+            Complex::new(1.0, 0.0),
+            Complex::new(0.0, 1.0),
+            Complex::new(0.0, 0.0),
+        ];
+        let multipath = ChannelConfig {
+            doppler_hz: 0.0,
+            taps: vec![(0, 1.0), (2, 0.5)],
+        };
+        let mut history = VecDeque::new();
+        multipath.apply(&mut block, &mut history, 1000.0, 0);
+        assert_eq!(
+            block,
+            vec![
+                Complex::new(1.0, 0.0),
+                Complex::new(0.0, 1.0),
+                Complex::new(0.5, 0.0)
+            ]
+        );
+        // 次のブロックの先頭には前のブロックの末尾の入力が遅れて届く
+        assert_eq!(history, [Complex::new(0.0, 1.0), Complex::new(0.0, 0.0)]);
+        let mut next = vec![Complex::new(0.0, 0.0); 3];
+        multipath.apply(&mut next, &mut history, 1000.0, 3);
+        assert_eq!(
+            next,
+            vec![
+                Complex::new(0.0, 0.5),
+                Complex::new(0.0, 0.0),
+                Complex::new(0.0, 0.0)
+            ]
+        );
+
+        // ブロックに分けて生成しても、遅延タップを含めて1回で生成したブロックと一致する
+        let channel = SignalParams {
+            mod_type: ModulationType::QPSK,
+            channel: ChannelConfig {
+                doppler_hz: 50.0,
+                taps: vec![(0, 1.0), (7, 0.5), (120, -0.25)],
+            },
+            ..pass.clone()
+        };
+        let whole = SignalGenerator::new().generate_block(&channel, 2000);
+        let mut generator = SignalGenerator::new();
+        let mut blocks = generator.generate_block(&channel, 1000);
+        blocks.extend(generator.generate_block(&channel, 1000));
+        for (n, (a, b)) in whole.iter().zip(&blocks).enumerate() {
+            assert!((a - b).norm() < 1e-12, "n={}: {} vs {}", n, a, b);
+        }
+    }
+
+    /// IQ不平衡によるイメージ抑圧比が設定値から計算される理論値と一致することをテスト
+    #[test]
+    fn test_iq_imbalance_image_rejection() {
+        let params = SignalParams {
+            frequency: 1000.0,
+            sample_rate: 100000.0,
+            iq_gain_imbalance_db: 1.0,
+            iq_phase_error_deg: 5.0,
+            ..Default::default()
+        };
+        // 整数周期分のブロックを生成し、±f0での成分を直接相関で求める
+        let n = 10000;
+        let samples = SignalGenerator::new().generate_block(&params, n);
+        let tone_power = |f: f64| {
+            samples
+                .iter()
+                .enumerate()
+                .map(|(k, s)| {
+                    s * Complex::from_polar(1.0, -2.0 * PI * f * k as f64 / params.sample_rate)
+                })
+                .sum::<Complex<f64>>()
+                .norm_sqr()
+        };
+        let measured_db = 10.0 * (tone_power(1000.0) / tone_power(-1000.0)).log10();
+
+        let g = 10.0_f64.powf(params.iq_gain_imbalance_db / 20.0);
+        let cos_phi = params.iq_phase_error_deg.to_radians().cos();
+        let expected_db =
+            10.0 * ((1.0 + 2.0 * g * cos_phi + g * g) / (1.0 - 2.0 * g * cos_phi + g * g)).log10();
+        assert!(
+            (measured_db - expected_db).abs() < 0.1,
+            "IRR = {} dB, expected {} dB",
+            measured_db,
+            expected_db
+        );
+    }
+
+    /// DCオフセットにより0Hzに期待どおりの大きさのスペクトル線が現れることをテスト
+    #[test]
+    fn test_dc_offset_line() {
+        let params = SignalParams {
+            frequency: 1000.0,
+            sample_rate: 100000.0,
+            dc_offset_i: 0.03,
+            dc_offset_q: -0.04,
+            ..Default::default()
+        };
+        let n = 10000;
+        let samples = SignalGenerator::new().generate_block(&params, n);
+        let line = |f: f64| {
+            samples
+                .iter()
+                .enumerate()
+                .map(|(k, s)| {
+                    s * Complex::from_polar(1.0, -2.0 * PI * f * k as f64 / params.sample_rate)
+                })
+                .sum::<Complex<f64>>()
+                .norm()
+                / n as f64
+        };
+
+        // 主トーンに対して |0.03 - 0.04j| = 0.05（-26dB）の線
+        let relative_db = 20.0 * (line(0.0) / line(1000.0)).log10();
+        let expected_db = 20.0 * 0.05_f64.log10();
+        assert!(
+            (relative_db - expected_db).abs() < 0.01,
+            "DC line = {} dBc, expected {} dBc",
+            relative_db,
+            expected_db
+        );
+    }
+
+    /// FSK/GFSKのスペクトラムが搬送波から±偏移の位置にエネルギーを持つことをテスト
+    #[test]
+    fn test_fsk_deviation_spectrum() {
+        for gaussian in [false, true] {
+            let params = SignalParams {
+                frequency: 10000.0,
+                sample_rate: 100000.0,
+                mod_type: ModulationType::FSK,
+                symbol_rate: 1000.0,
+                fsk_deviation: 5000.0,
+                fsk_gaussian: gaussian,
+                fsk_bt: 0.5,
+                ..Default::default()
+            };
+
+            let samples = SignalGenerator::new().generate_block(&params, 100000);
+            // 周波数偏移変調は定包絡線
+            assert!(samples.iter().all(|s| (s.norm() - 1.0).abs() < 1e-9));
+            let spectrum = fft(samples);
+
+            // 周波数分解能は1Hz、指定周波数を中心とする±500Hzの帯域の電力
+            let band_power = |center: isize| bin_power(&spectrum, center - 500..=center + 500);
+            let total = total_power(&spectrum);
+            let upper = band_power(15000) / total;
+            let lower = band_power(5000) / total;
+            let center = band_power(10000) / total;
+            // 電力の大部分は±偏移の周辺に集中し、搬送波周波数の周辺には少ない
+            assert!(upper > 0.2 && lower > 0.2, "gaussian={}", gaussian);
+            assert!(center < upper / 5.0, "gaussian={}", gaussian);
+        }
+    }
+
+    /// ガウスフィルタの係数の数が上限で打ち切られ、FSK/MSKではRRCの代わりにその長さで制限することをテスト
+    #[test]
+    fn test_gaussian_taps_limits() {
+        assert!(gaussian_taps(0.3, 100000.0).len() <= MAX_FILTER_TAPS + 1);
+        assert_eq!(gaussian_taps(0.3, 10.0).len(), 41);
+        assert_eq!(
+            min_symbol_rate(
+                100000.0,
+                shaping_filter_span(ModulationType::MSK, true, 64, true)
+            ),
+            100000.0 * GAUSSIAN_SPAN_SYMBOLS / MAX_FILTER_TAPS as f64
+        );
+        assert_eq!(
+            min_symbol_rate(
+                100000.0,
+                shaping_filter_span(ModulationType::FSK, true, 64, false)
+            ),
+            1.0
+        );
+    }
+
+    /// MSKが1シンボルで±π/2の位相を進め、ガウスフィルタのBT積が小さいほど帯域外電力が下がることをテスト
+    #[test]
+    fn test_gmsk_sidelobes() {
+        let n = 100000;
+        let msk = SignalParams {
+            frequency: 0.0,
+            sample_rate: 100000.0,
+            mod_type: ModulationType::MSK,
+            symbol_rate: 1000.0,
+            ..Default::default()
+        };
+
+        // 1シンボル（100サンプル）ごとの位相の変化は ±π/2
+        // （シンボル境界の丸めで1サンプルずれると、1サンプル分の位相 ±π/200 の2倍だけずれる）
+        let samples = SignalGenerator::new().generate_block(&msk, 1000);
+        assert!(samples.iter().all(|s| (s.norm() - 1.0).abs() < 1e-9));
+        for k in 0..9 {
+            let step = (samples[100 * (k + 1)] / samples[100 * k]).arg();
+            assert!(
+                (step.abs() - PI / 2.0).abs() < 0.04,
+                "symbol {}: {}",
+                k,
+                step
+            );
+        }
+
+        // 周波数分解能は1Hz、±1.5シンボルレートの外側の電力の割合
+        let out_of_band = |params: &SignalParams| {
+            let spectrum = spectrum_of(params, n);
+            let outside = total_power(&spectrum[1500..n - 1500]);
+            10.0 * (outside / total_power(&spectrum)).log10()
+        };
+        let gmsk = |bt: f64| SignalParams {
+            fsk_gaussian: true,
+            fsk_bt: bt,
+            ..msk.clone()
+        };
+        let plain = out_of_band(&msk);
+        let bt05 = out_of_band(&gmsk(0.5));
+        let bt03 = out_of_band(&gmsk(0.3));
+        assert!(bt05 < plain - 10.0, "MSK {} dB, BT=0.5 {} dB", plain, bt05);
+        assert!(bt03 < bt05 - 10.0, "BT=0.5 {} dB, BT=0.3 {} dB", bt05, bt03);
+    }
+
+    /// PSKのシンボルが多値数に応じた単位円上の位相に載ることをテスト
+    #[test]
+    fn test_psk_constellation_phases() {
+        for (order, offset) in [(2, 0.0), (4, PI / 4.0), (8, PI / 8.0)] {
+            let params = SignalParams {
+                frequency: 0.0,
+                mod_type: ModulationType::PSK,
+                psk_order: order,
+                symbol_rate: 10000.0,
+                prbs_order: PrbsOrder::Prbs15,
+                ..Default::default()
+            };
+
+            // 搬送波0Hzではサンプルがそのままシンボルになる
+            let samples = SignalGenerator::new().generate_block(&params, 10000);
+            let step = 2.0 * PI / order as f64;
+            let mut used = vec![false; order];
+            for s in &samples {
+                assert!((s.norm() - 1.0).abs() < 1e-9);
+                let k = (s.arg() - offset).rem_euclid(2.0 * PI) / step;
+                assert!(
+                    (k - k.round()).abs() < 1e-9,
+                    "order {}: arg {}",
+                    order,
+                    s.arg()
+                );
+                used[k.round() as usize % order] = true;
+            }
+            // PRBSによりすべてのシンボルが現れる
+            assert!(used.iter().all(|&u| u), "order {}", order);
+        }
+    }
+
+    /// パルスのエッジを滑らかにするとスペクトルのサイドローブが下がることをテスト
+    #[test]
+    fn test_pulse_edge_sidelobes() {
+        let hard = SignalParams {
+            frequency: 0.0,
+            sample_rate: 100000.0,
+            mod_type: ModulationType::Pulse,
+            mod_freq: 1000.0,
+            mod_strength: 0.5,
+            ..Default::default()
+        };
+        let smooth = SignalParams {
+            pulse_edge_time: 200e-6,
+            ..hard.clone()
+        };
+
+        // 搬送波から10kHz以上離れた成分の電力の割合 (dB)
+        let sidelobe_db = |params: &SignalParams| {
+            let n = 100000;
+            let spectrum = spectrum_of(params, n);
+            let far = total_power(&spectrum[10000..n - 10000]);
+            10.0 * (far / total_power(&spectrum)).log10()
+        };
+
+        let hard_db = sidelobe_db(&hard);
+        let smooth_db = sidelobe_db(&smooth);
+        assert!(
+            smooth_db < hard_db - 20.0,
+            "smooth {} dB vs hard {} dB",
+            smooth_db,
+            hard_db
+        );
+
+        // エッジ時間0では従来どおりの矩形パルス
+        assert_eq!(pulse_envelope(0.49, 0.5, 0.0), 1.0);
+        assert_eq!(pulse_envelope(0.5, 0.5, 0.0), 0.0);
+        assert!((pulse_envelope(0.05, 0.5, 0.1) - 0.5).abs() < 1e-12);
+    }
+
+    /// 位相雑音のレベルを上げるとCWのスペクトル線が広がることをテスト
+    #[test]
+    fn test_phase_noise_broadening() {
+        // 搬送波ビンに残る電力の割合（整数周期分のブロックなので位相雑音なしでは1）
+        let carrier_fraction = |rms_deg: f64| {
+            let params = SignalParams {
+                frequency: 1000.0,
+                sample_rate: 100000.0,
+                phase_noise_rms_deg: rms_deg,
+                ..Default::default()
+            };
+            let samples = SignalGenerator::new().generate_block(&params, 10000);
+            // 位相雑音は振幅を変えない
+            assert!(samples.iter().all(|s| (s.norm() - 1.0).abs() < 1e-9));
+            let spectrum = fft(samples);
+            bin_power(&spectrum, [100]) / total_power(&spectrum)
+        };
+
+        let clean = carrier_fraction(0.0);
+        let low = carrier_fraction(0.5);
+        let high = carrier_fraction(2.0);
+        assert!((clean - 1.0).abs() < 1e-9);
+        assert!(low < clean && high < low, "{} {} {}", clean, low, high);
+        assert!(high < 0.8);
+    }
+
+    /// Nビットの量子化で各成分の値が2^N通り以下になることをテスト
+    #[test]
+    fn test_quantize_levels() {
+        let params = SignalParams {
+            frequency: 37.0,
             sample_rate: 1000.0,
-            mod_type: ModulationType::CW,
-            mod_freq: 0.0,
-            mod_strength: 0.0,
-            multitone_count: 1,
-            multitone_spacing: 0.0,
+            ..Default::default()
+        };
+        let raw = SignalGenerator::new().generate_block(&params, 4096);
+        for bits in [1u8, 3, 4, 8] {
+            let mut samples: Vec<Complex<f64>> = raw.iter().map(|s| s * 1.2).collect();
+            quantize(&mut samples, bits);
+            let levels = |f: fn(&Complex<f64>) -> f64| {
+                let mut values: Vec<f64> = samples.iter().map(f).collect();
+                values.sort_by(f64::total_cmp);
+                values.dedup();
+                values
+            };
+            for values in [levels(|s| s.re), levels(|s| s.im)] {
+                assert!(values.len() <= 1 << bits, "{} bits: {}", bits, values.len());
+                assert!(values.iter().all(|v| (-1.0..1.0).contains(v)));
+            }
+        }
+
+        // 十分なビット数では量子化誤差はステップ幅の半分以下
+        let mut samples = raw.clone();
+        quantize(&mut samples, 16);
+        let step = 1.0 / 32768.0;
+        for (q, s) in samples.iter().zip(&raw) {
+            assert!((q.re - s.re).abs() <= step && (q.im - s.im).abs() <= step);
+        }
+    }
+
+    /// クリップ後の振幅がレベルを超えず、ピーク以上のレベルでは信号が変化しないことをテスト
+    #[test]
+    fn test_clip_magnitude() {
+        let params = SignalParams {
+            mod_type: ModulationType::Multitone,
+            multitone_count: 8,
+            sample_rate: 1000.0,
+            ..Default::default()
+        };
+        let raw = SignalGenerator::new().generate_block(&params, 4096);
+        let peak = raw.iter().map(|s| s.norm()).fold(0.0, f64::max);
+
+        let level = 0.5 * peak;
+        let mut clipped = raw.clone();
+        clip_magnitude(&mut clipped, level);
+        assert!(clipped.iter().all(|s| s.norm() <= level + 1e-12));
+        // 位相は保たれる
+        for (c, s) in clipped.iter().zip(&raw) {
+            if s.norm() > 0.0 {
+                assert!((c.arg() - s.arg()).abs() < 1e-9);
+            }
+        }
+
+        let mut unchanged = raw.clone();
+        clip_magnitude(&mut unchanged, peak * 1.01);
+        assert_eq!(unchanged, raw);
+    }
+
+    /// Salehモデルが小振幅では線形に増幅し、大振幅では利得が圧縮されることをテスト
+    #[test]
+    fn test_saleh_compression() {
+        let params = SalehParams::default();
+
+        // 小振幅では利得αa、位相変化なし
+        let small = Complex::from_polar(1e-4, 0.7);
+        let out = saleh(small, &params);
+        assert!((out.norm() / small.norm() - params.alpha_a).abs() < 1e-6);
+        assert!((out.arg() - small.arg()).abs() < 1e-6);
+
+        // 大振幅では利得が下がり、AM/PMで位相が回転する
+        let large = Complex::from_polar(1.0, 0.7);
+        let out = saleh(large, &params);
+        let gain = out.norm() / large.norm();
+        assert!((gain - params.alpha_a / (1.0 + params.beta_a)).abs() < 1e-12);
+        assert!(gain < params.alpha_a / 2.0);
+        let expected_phase = params.alpha_phi / (1.0 + params.beta_phi);
+        assert!((out.arg() - large.arg() - expected_phase).abs() < 1e-12);
+
+        // 出力振幅は入力振幅1/√βaで最大となり、それ以上では減少する
+        let peak_in = 1.0 / params.beta_a.sqrt();
+        let amplitude = |r: f64| saleh(Complex::new(r, 0.0), &params).norm();
+        assert!(amplitude(peak_in) > amplitude(0.9 * peak_in));
+        assert!(amplitude(peak_in) > amplitude(1.1 * peak_in));
+    }
+
+    /// クレストファクタの計算と、Schroeder位相でマルチトーンの値が下がることをテスト
+    #[test]
+    fn test_crest_factor() {
+        assert_eq!(crest_factor(&[]), 0.0);
+        let cw = SignalGenerator::new().generate_block(&SignalParams::default(), 1000);
+        assert!((crest_factor(&cw) - 1.0).abs() < 1e-12);
+
+        // ピーク2、RMS√2のパルス列
+        let pulse = [Complex::new(2.0, 0.0), Complex::new(0.0, 0.0)];
+        assert!((crest_factor(&pulse) - 2.0_f64.sqrt()).abs() < 1e-12);
+
+        // ゼロ位相のN本のトーンはクレストファクタ√N
+        let zero = SignalParams {
+            mod_type: ModulationType::Multitone,
+            multitone_count: 16,
+            multitone_spacing: 1000.0,
             multitone_phase: MultitonePhase::Zero,
-            seed: 0,
+            ..Default::default()
+        };
+        let schroeder = SignalParams {
+            multitone_phase: MultitonePhase::Schroeder,
+            ..zero.clone()
         };
+        let zero_cf = crest_factor(&SignalGenerator::new().generate_block(&zero, 1000));
+        let schroeder_cf = crest_factor(&SignalGenerator::new().generate_block(&schroeder, 1000));
+        assert!((zero_cf - 4.0).abs() < 0.05);
+        assert!(schroeder_cf < zero_cf / 2.0);
+    }
 
-        // 1秒分のデータを生成
-        let samples = gen.generate_block(&params, params.sample_rate as usize);
+    /// Newman位相のPAPRがゼロ位相より低いことをテスト
+    #[test]
+    fn test_multitone_newman_papr() {
+        use crate::analysis::papr_db;
 
-        let s0 = samples[0];
-        let s10 = samples[10];
+        let zero = SignalParams {
+            frequency: 0.0,
+            mod_type: ModulationType::Multitone,
+            multitone_count: 32,
+            multitone_spacing: 1000.0,
+            multitone_phase: MultitonePhase::Zero,
+            ..Default::default()
+        };
+        let newman = SignalParams {
+            multitone_phase: MultitonePhase::Newman,
+            ..zero.clone()
+        };
+        // 合成波形の周期（200サンプル）の整数倍で比較
+        let zero_papr = papr_db(&SignalGenerator::new().generate_block(&zero, 1000));
+        let newman_papr = papr_db(&SignalGenerator::new().generate_block(&newman, 1000));
+        // ゼロ位相は10·log10(N) ≈ 15 dB、Newman位相は数dB程度
+        assert!(
+            (zero_papr - 10.0 * 32f64.log10()).abs() < 0.1,
+            "{}",
+            zero_papr
+        );
+        assert!(newman_papr < zero_papr - 6.0, "{}", newman_papr);
+    }
 
-        // 10サンプル = 1周期なので、同じ値になるはず
-        let epsilon = 1e-5;
-        assert!((s0.re - s10.re).abs() < epsilon);
-        assert!((s0.im - s10.im).abs() < epsilon);
+    /// CWトーンの統計量をテスト
+    #[test]
+    fn test_signal_stats() {
+        assert_eq!(signal_stats(&[]), SignalStats::default());
+
+        // 1000周期ちょうどのCWトーンを振幅0.5にスケーリング
+        let amplitude = 0.5;
+        let samples: Vec<Complex<f64>> = SignalGenerator::new()
+            .generate_block(&SignalParams::default(), 100_000)
+            .into_iter()
+            .map(|s| s * amplitude)
+            .collect();
+        let stats = signal_stats(&samples);
+
+        // 複素振幅で見たRMSは振幅そのもの
+        assert!((stats.peak - amplitude).abs() < 1e-12);
+        assert!((stats.rms - amplitude).abs() < 1e-12);
+        assert!((stats.mean_power - amplitude * amplitude).abs() < 1e-12);
+        assert!(stats.dc_offset.norm() < 1e-9);
+        assert!((stats.rms_dbfs() - 20.0 * amplitude.log10()).abs() < 1e-9);
+        assert!((stats.mean_power_dbfs() - stats.rms_dbfs()).abs() < 1e-9);
+
+        // 実部だけで見たRMSは振幅/√2
+        let real: Vec<Complex<f64>> = samples.iter().map(|s| Complex::new(s.re, 0.0)).collect();
+        let real_stats = signal_stats(&real);
+        assert!((real_stats.rms - amplitude / 2.0_f64.sqrt()).abs() < 1e-9);
+    }
+
+    /// 任意に指定した3本のトーンのスペクトラムの線が指定通りになることをテスト
+    #[test]
+    fn test_multitone_explicit_tones() {
+        let n = 1000;
+        let tones = vec![
+            Tone {
+                freq: -12000.0,
+                amplitude: 1.0,
+            },
+            Tone {
+                freq: 3000.0,
+                amplitude: 0.5,
+            },
+            Tone {
+                freq: 25000.0,
+                amplitude: 0.5,
+            },
+        ];
+        let params = SignalParams {
+            sample_rate: 100000.0,
+            mod_type: ModulationType::Multitone,
+            multitone_spec: MultitoneSpec::Explicit(tones.clone()),
+            ..Default::default()
+        };
+        assert_eq!(params.multitone_tones(), tones);
+
+        // 周波数分解能100Hz、振幅は合計(2.0)で正規化される
+        let spectrum = spectrum_of(&params, n);
+        for k in 0..n {
+            let mag = bin_magnitude(&spectrum, k as isize);
+            let expected = match k {
+                880 => 0.5,
+                30 | 250 => 0.25,
+                _ => 0.0,
+            };
+            assert!(
+                (mag - expected).abs() < 1e-9,
+                "bin {}: {} != {}",
+                k,
+                mag,
+                expected
+            );
+        }
+    }
+
+    /// ノッチ内のトーンのエネルギーが0で、ノッチ外のトーンが残ることをテスト
+    #[test]
+    fn test_multitone_notch() {
+        let n = 1000;
+        let params = SignalParams {
+            frequency: 0.0,
+            sample_rate: 100000.0,
+            mod_type: ModulationType::Multitone,
+            multitone_count: 16,
+            multitone_spacing: 1000.0,
+            notch_start_tone: 5,
+            notch_width_tones: 4,
+            ..Default::default()
+        };
+
+        let spectrum = spectrum_of(&params, n);
+
+        // トーンkは (k - 7.5) * 1000 Hz、周波数分解能100Hzでビン 10k - 75 (mod n)
+        for k in 0..16 {
+            let mag = bin_magnitude(&spectrum, 10 * k as isize - 75);
+            if (5..9).contains(&k) {
+                assert!(mag < 1e-9, "notched tone {} has magnitude {}", k, mag);
+            } else {
+                // 残る12本のトーンの振幅の合計で正規化される
+                assert!((mag - 1.0 / 12.0).abs() < 1e-9, "tone {}: {}", k, mag);
+            }
+        }
+    }
+
+    /// 振幅の窓で中央のトーンに対して端のトーンが減衰することをテスト
+    #[test]
+    fn test_multitone_amplitude_window() {
+        let n = 1000;
+        let params = SignalParams {
+            frequency: 0.0,
+            sample_rate: 100000.0,
+            mod_type: ModulationType::Multitone,
+            multitone_count: 17,
+            multitone_spacing: 1000.0,
+            multitone_amplitude_window: WindowFunction::Hamming,
+            ..Default::default()
+        };
+
+        let spectrum = spectrum_of(&params, n);
+
+        // トーンkは (k - 8) * 1000 Hz、周波数分解能100Hzでビン 10k - 80 (mod n)
+        let mag = |k: isize| bin_magnitude(&spectrum, 10 * k - 80);
+        let center = mag(8);
+        // トーンkの重みは (k + 0.5) / 17 の位置のハミング窓の値（中央のトーンは1）
+        for k in [0, 4, 16] {
+            let expected = 0.54 - 0.46 * (2.0 * PI * (k as f64 + 0.5) / 17.0).cos();
+            let ratio = mag(k) / center;
+            assert!((ratio - expected).abs() < 1e-9, "tone {}: {}", k, ratio);
+        }
+
+        // 三角窓では中央から端に向かって直線的に減衰（端のトーンも残る）
+        let triangular = SignalParams {
+            multitone_amplitude_window: WindowFunction::Triangular,
+            ..params.clone()
+        };
+        let amplitudes: Vec<f64> = triangular
+            .multitone_tones()
+            .iter()
+            .map(|t| t.amplitude)
+            .collect();
+        for (k, amplitude) in amplitudes.iter().enumerate() {
+            let expected = 1.0 - (k as f64 - 8.0).abs() / 8.5;
+            assert!((amplitude - expected).abs() < 1e-12, "tone {}", k);
+        }
+
+        // 窓なしでは全トーンが同じ振幅
+        let flat = SignalParams {
+            multitone_amplitude_window: WindowFunction::Rectangular,
+            ..params.clone()
+        };
+        assert!(flat.multitone_tones().iter().all(|t| t.amplitude == 1.0));
+
+        // 両端が0になるハン窓やブラックマン窓でも、2トーンでは両方のトーンが同じ振幅で残る
+        for window in [WindowFunction::Hann, WindowFunction::Blackman] {
+            let two_tones = SignalParams {
+                multitone_count: 2,
+                multitone_amplitude_window: window,
+                ..params.clone()
+            };
+            let amplitudes: Vec<f64> = two_tones
+                .multitone_tones()
+                .iter()
+                .map(|t| t.amplitude)
+                .collect();
+            assert!((amplitudes[0] - amplitudes[1]).abs() < 1e-12);
+            assert!(amplitudes[0] > 0.3, "{:?}: {:?}", window, amplitudes);
+            let block = SignalGenerator::new().generate_block(&two_tones, n);
+            assert!(block.iter().any(|s| s.norm() > 0.1), "{:?}", window);
+        }
+    }
+
+    /// IQ不平衡がFFTの鏡像ビンにイメージを生じ、不平衡がなければ生じないことをテスト
+    #[test]
+    fn test_iq_imbalance_fft_image() {
+        let n = 1000;
+        // 周波数分解能100Hzで、トーンはビン20、鏡像はビン980
+        let image_ratio = |gain_db: f64, phase_deg: f64| {
+            let params = SignalParams {
+                frequency: 2000.0,
+                sample_rate: 100000.0,
+                iq_gain_imbalance_db: gain_db,
+                iq_phase_error_deg: phase_deg,
+                ..Default::default()
+            };
+            let spectrum = spectrum_of(&params, n);
+            bin_magnitude(&spectrum, -20) / bin_magnitude(&spectrum, 20)
+        };
+
+        assert!(image_ratio(0.0, 0.0) < 1e-12);
+        // 利得不平衡のみ、位相誤差のみのどちらでもイメージが生じる
+        // 利得0.5dB: |g-1|/|g+1| ≈ 0.0288、位相2度: tan(1度) ≈ 0.0175
+        assert!((image_ratio(0.5, 0.0) - 0.0288).abs() < 1e-3);
+        assert!((image_ratio(0.0, 2.0) - 0.0175).abs() < 1e-3);
+    }
+
+    /// バーストのゲートがオフ区間で0、オン区間で1になり、ブロック間で連続することをテスト
+    #[test]
+    fn test_burst_gate() {
+        let params = SignalParams {
+            burst_enabled: true,
+            burst: BurstConfig {
+                period_samples: 1000,
+                on_samples: 400,
+                ramp_samples: 50,
+            },
+            ..Default::default()
+        };
+        let samples = SignalGenerator::new().generate_block(&params, 2000);
+        for period in 0..2 {
+            let envelope = |k: usize| samples[period * 1000 + k].norm();
+            assert!((50..400).all(|k| (envelope(k) - 1.0).abs() < 1e-12));
+            assert!((450..1000).all(|k| envelope(k) < 1e-12));
+            // 立ち上がり/立ち下がりは単調で、中点で0.5
+            assert!((1..50).all(|k| envelope(k) > envelope(k - 1)));
+            assert!((401..450).all(|k| envelope(k) < envelope(k - 1)));
+            assert!((envelope(25) - 0.5).abs() < 1e-12);
+            assert!((envelope(425) - 0.5).abs() < 1e-12);
+        }
+
+        // ブロックを分けて生成しても同じゲートになる
+        let mut gen = SignalGenerator::new();
+        let mut split = gen.generate_block(&params, 700);
+        split.extend(gen.generate_block(&params, 1300));
+        assert!(split
+            .iter()
+            .zip(&samples)
+            .all(|(a, b)| (a - b).norm() < 1e-12));
+    }
+
+    /// 初期位相90度で最初のCWサンプルのI/Qが0度のときに対して入れ替わることをテスト
+    #[test]
+    fn test_initial_phase() {
+        let first = |initial_phase_deg: f64| {
+            let params = SignalParams {
+                initial_phase_deg,
+                ..Default::default()
+            };
+            SignalGenerator::new().next_sample(&params)
+        };
+        let (zero, quadrature) = (first(0.0), first(90.0));
+        // e^{jπ/2}を掛けるため I' = -Q、Q' = I
+        assert!((quadrature.re + zero.im).abs() < 1e-12);
+        assert!((quadrature.im - zero.re).abs() < 1e-12);
+
+        // リセット後も同じ初期位相から生成する
+        let params = SignalParams {
+            initial_phase_deg: 90.0,
+            mod_type: ModulationType::QPSK,
+            ..Default::default()
+        };
+        let mut generator = SignalGenerator::new();
+        let block = generator.generate_block(&params, 100);
+        generator.reset();
+        assert_eq!(generator.generate_block(&params, 100), block);
+    }
+
+    /// 振幅プロファイルの最初と最後のサンプルの振幅が開始/終了レベルと一致することをテスト
+    #[test]
+    fn test_amplitude_profile() {
+        let db = |v: f64| 20.0 * v.log10();
+        let profile = AmplitudeProfile {
+            kind: AmplitudeProfileKind::Ramp,
+            start_db: -20.0,
+            stop_db: -6.0,
+            ramp_samples: 1000,
+            ..Default::default()
+        };
+        // どの変調方式にも掛かる
+        for mod_type in [ModulationType::CW, ModulationType::FM, ModulationType::QPSK] {
+            let params = SignalParams {
+                mod_type,
+                amplitude_profile: profile,
+                ..Default::default()
+            };
+            let samples = SignalGenerator::new().generate_block(&params, 1000);
+            let plain = SignalGenerator::new().generate_block(
+                &SignalParams {
+                    amplitude_profile: AmplitudeProfile::default(),
+                    ..params.clone()
+                },
+                1000,
+            );
+            let level = |k: usize| db(samples[k].norm() / plain[k].norm());
+            assert!((level(0) + 20.0).abs() < 1e-9, "{:?}", mod_type);
+            assert!((level(999) + 6.0).abs() < 1e-9, "{:?}", mod_type);
+            // 振幅は直線的に変化する
+            let (a, b) = (samples[0].norm(), samples[999].norm());
+            if mod_type == ModulationType::CW {
+                assert!((samples[500].norm() - (a + (b - a) * 500.0 / 999.0)).abs() < 1e-12);
+            }
+        }
+
+        // 階段：各ステップはdBで等間隔、最後のステップが終了レベル
+        let staircase = AmplitudeProfile {
+            kind: AmplitudeProfileKind::Staircase,
+            start_db: -30.0,
+            stop_db: 0.0,
+            steps: 4,
+            dwell_samples: 100,
+            ..Default::default()
+        };
+        let params = SignalParams {
+            amplitude_profile: staircase,
+            ..Default::default()
+        };
+        let samples = SignalGenerator::new().generate_block(&params, 500);
+        for (k, expected) in [
+            (0, -30.0),
+            (99, -30.0),
+            (100, -20.0),
+            (250, -10.0),
+            (399, 0.0),
+        ] {
+            assert!(
+                (db(samples[k].norm()) - expected).abs() < 1e-9,
+                "sample {}",
+                k
+            );
+        }
+        // 終わりに達すると開始レベルから繰り返す
+        assert!((db(samples[400].norm()) + 30.0).abs() < 1e-9);
+    }
+
+    /// FFTのDCビンの振幅がDCオフセットに比例して増えることをテスト
+    #[test]
+    fn test_dc_offset_bin_proportional() {
+        let n = 1000;
+        let dc_bin = |offset: f64| {
+            let params = SignalParams {
+                dc_offset_i: offset,
+                dc_offset_q: offset / 2.0,
+                ..Default::default()
+            };
+            bin_magnitude(&spectrum_of(&params, n), 0)
+        };
+
+        assert!(dc_bin(0.0) < 1e-12);
+        let base = dc_bin(0.01);
+        assert!((base - 0.01 * 1.25_f64.sqrt()).abs() < 1e-12);
+        for scale in [2.0, 4.0, 10.0] {
+            assert!((dc_bin(0.01 * scale) / base - scale).abs() < 1e-9);
+        }
+    }
+
+    /// dBc/Hzで指定した位相雑音が、オフセット周波数で指定したレベルのスカートになることをテスト
+    #[test]
+    fn test_phase_noise_dbc_level() {
+        use crate::analysis::{welch_psd, WindowFunction};
+
+        let sample_rate = 100000.0;
+        let rms = phase_noise_rms_from_dbc(-80.0, 1000.0, sample_rate);
+        assert!((phase_noise_dbc_from_rms(rms, 1000.0, sample_rate) + 80.0).abs() < 1e-9);
+        // 1/f²特性：オフセットが2倍になると6dB下がる
+        assert!((phase_noise_dbc_from_rms(rms, 2000.0, sample_rate) + 86.02).abs() < 0.01);
+
+        let params = SignalParams {
+            frequency: 0.0,
+            sample_rate,
+            phase_noise_rms_deg: rms,
+            ..Default::default()
+        };
+        let samples = SignalGenerator::new().generate_block(&params, 200_000);
+        // 周波数分解能100Hz、ビン500が0Hz
+        let psd = welch_psd(&samples, 1000, 500, WindowFunction::BlackmanHarris);
+        for offset_bins in [20, 40] {
+            let offset = offset_bins as f64 * 100.0;
+            let expected = phase_noise_dbc_from_rms(rms, offset, sample_rate);
+            // 正負の側波帯の平均（搬送波電力1に対する1Hzあたりの電力）
+            let density = (psd[500 + offset_bins] + psd[500 - offset_bins]) / 2.0 / sample_rate;
+            let measured = 10.0 * density.log10();
+            assert!(
+                (measured - expected).abs() < 1.5,
+                "{} Hz: {} dBc/Hz, expected {} dBc/Hz",
+                offset,
+                measured,
+                expected
+            );
+        }
     }
 }
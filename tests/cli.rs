@@ -0,0 +1,146 @@
+//! ヘッドレスモード（コマンドライン実行）の統合テスト
+
+use std::process::Command;
+
+/// ヘッドレスモードでCSVを出力し、行数が指定したサンプル数と一致することをテスト
+#[test]
+fn test_headless_csv_export() {
+    let path = std::env::temp_dir().join(format!("ivsg_cli_{}.csv", std::process::id()));
+
+    let status = Command::new(env!("CARGO_BIN_EXE_ivsg"))
+        .args(["--headless", "--freq", "2000", "--sample-rate", "48000"])
+        .args(["--num-samples", "256", "--mod", "fm", "--out"])
+        .arg(&path)
+        .status()
+        .expect("failed to run ivsg");
+    assert!(status.success());
+
+    let csv = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(csv.lines().count(), 256);
+    assert!(csv.lines().all(|line| line.split(',').count() == 2));
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// 未対応の出力形式ではエラー終了することをテスト
+#[test]
+fn test_headless_unsupported_format() {
+    let path = std::env::temp_dir().join(format!("ivsg_cli_{}.xyz", std::process::id()));
+
+    let status = Command::new(env!("CARGO_BIN_EXE_ivsg"))
+        .args(["--headless", "--out"])
+        .arg(&path)
+        .status()
+        .expect("failed to run ivsg");
+    assert!(!status.success());
+    assert!(!path.exists());
+}
+
+/// 範囲外のパラメータでは終了コード2で終了し、ファイルを出力しないことをテスト
+#[test]
+fn test_headless_invalid_params() {
+    let path = std::env::temp_dir().join(format!("ivsg_cli_invalid_{}.csv", std::process::id()));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ivsg"))
+        .args(["--headless", "--sample-rate", "0", "--out"])
+        .arg(&path)
+        .output()
+        .expect("failed to run ivsg");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid parameters"));
+    assert!(!path.exists());
+}
+
+/// `--out`だけでヘッドレス実行され、BINファイルのサイズがサンプル数と一致することをテスト
+#[test]
+fn test_headless_bin_file_size() {
+    let path = std::env::temp_dir().join(format!("ivsg_cli_{}.bin", std::process::id()));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ivsg"))
+        .args([
+            "--mod", "fm", "--freq", "1000", "--fs", "100000", "--n", "4096",
+        ])
+        .args([
+            "--mod-freq",
+            "50",
+            "--fm-deviation",
+            "2000",
+            "--snr",
+            "30",
+            "--out",
+        ])
+        .arg(&path)
+        .output()
+        .expect("failed to run ivsg");
+    assert!(output.status.success());
+
+    // float32のI/Qインターリーブで1サンプルあたり8バイト
+    let size = std::fs::metadata(&path).unwrap().len();
+    assert_eq!(size, 4096 * 8);
+    std::fs::remove_file(&path).unwrap();
+
+    // 使用したパラメータが標準エラー出力に表示される
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("mod_type: FM"));
+    assert!(stderr.contains("snr_db: 30.0"));
+}
+
+/// 負のdBmを空白区切りで指定でき、50Ωで-10dBmが振幅0.1になることをテスト
+#[test]
+fn test_headless_negative_dbm() {
+    let path = std::env::temp_dir().join(format!("ivsg_cli_dbm_{}.bin", std::process::id()));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ivsg"))
+        .args([
+            "--mod",
+            "cw",
+            "--n",
+            "16",
+            "--amplitude-dbm",
+            "-10",
+            "--out",
+        ])
+        .arg(&path)
+        .output()
+        .expect("failed to run ivsg");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // CWの各サンプルの絶対値が振幅そのもの
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let value = |k: usize| f32::from_le_bytes(bytes[4 * k..4 * k + 4].try_into().unwrap());
+    let magnitude = value(0).hypot(value(1));
+    assert!((magnitude - 0.1).abs() < 1e-6, "|IQ| = {}", magnitude);
+}
+
+/// 符号付きのパラメータに負の値を空白区切りで指定できることをテスト
+#[test]
+fn test_headless_negative_values() {
+    let path = std::env::temp_dir().join(format!("ivsg_cli_negative_{}.csv", std::process::id()));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ivsg"))
+        .args([
+            "--n",
+            "16",
+            "--dc-offset-i",
+            "-0.1",
+            "--dc-offset-q",
+            "-0.2",
+        ])
+        .args(["--iq-gain-imbalance", "-0.5", "--iq-phase-error", "-2"])
+        .args(["--doppler", "-100"])
+        .arg("--out")
+        .arg(&path)
+        .output()
+        .expect("failed to run ivsg");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 16);
+    std::fs::remove_file(&path).unwrap();
+}